@@ -1,3 +1,14 @@
 fn main() {
+    // debug 构建记录源码中的资源目录，开发模式下运行时据此定位 resources/，release 构建不写入
+    if std::env::var("PROFILE").as_deref() == Ok("debug") {
+        if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
+            let resource_dir = std::path::Path::new(&manifest_dir).join("resources");
+            println!("cargo:rustc-env=MAKINGSTORE_DEV_RESOURCE_DIR={}", resource_dir.display());
+        }
+    }
+
+    // 工具仓库的发布者公钥在构建时固定，变化时需要重新编译
+    println!("cargo:rerun-if-env-changed=MAKINGSTORE_TOOL_PUBLISHER_KEY");
+
     tauri_build::build()
 }
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+#[cfg(not(target_os = "windows"))]
+use std::process::Stdio;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::{t, tf};
+#[cfg(target_os = "windows")]
+use crate::process::{check_output, output_audited};
+use crate::process::{kill_process, next_id, now_millis, spawn_audited, ProcessBuilder, SpawnContext};
+
+/// 通过 MakingStore 启动的一个 Agent（目前为 Codex）会话
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSession {
+    pub id: String,
+    pub agent: String,
+    pub project_path: String,
+    pub pid: u32,
+    /// 启动时间（Unix 毫秒）
+    pub started_at: u64,
+    /// 结束时间（Unix 毫秒），仍在运行时为空
+    pub ended_at: Option<u64>,
+    pub running: bool,
+    pub exit_code: Option<i32>,
+}
+
+/// 所有已启动会话的状态表，按会话 ID 索引
+#[derive(Default)]
+pub struct AgentSessionState {
+    sessions: Mutex<HashMap<String, AgentSession>>,
+}
+
+impl AgentSessionState {
+    fn get(&self, session_id: &str) -> AppResult<AgentSession> {
+        let sessions = self.sessions.lock().map_err(|_| AppError::lock())?;
+        sessions
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| AppError::new(ErrorCode::NotFound, tf("error.sessionNotFound", &[("id", &session_id)])))
+    }
+
+    /// 标记会话已退出并返回更新后的会话
+    fn mark_exited(&self, session_id: &str, code: Option<i32>) -> Option<AgentSession> {
+        let mut sessions = self.sessions.lock().ok()?;
+        let session = sessions.get_mut(session_id)?;
+        if session.running {
+            session.running = false;
+            session.exit_code = code;
+            session.ended_at = Some(now_millis());
+        }
+        Some(session.clone())
+    }
+
+    /// 列出会话，可按项目路径过滤，最新的在前
+    fn list(&self, project_path: Option<&str>) -> AppResult<Vec<AgentSession>> {
+        let sessions = self.sessions.lock().map_err(|_| AppError::lock())?;
+        let mut list: Vec<AgentSession> = sessions
+            .values()
+            .filter(|s| project_path.is_none_or(|p| s.project_path == p))
+            .cloned()
+            .collect();
+        list.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+        Ok(list)
+    }
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct SessionLogPayload {
+    session_id: String,
+    level: &'static str,
+    message: String,
+}
+
+/// 构建 Codex 启动命令
+///
+/// Windows 下为 Codex 创建独立的控制台窗口，使其可交互并可被前置；
+/// 其他平台以托管方式运行，输出通过事件转发给前端。
+fn codex_builder(path: &str) -> ProcessBuilder {
+    ProcessBuilder::new("codex").arg(path).cwd(path).new_console()
+}
+
+/// 按行读取子进程输出并以 `agent-session-log` 事件推送给前端
+fn forward_lines<R: Read + Send + 'static>(
+    app_handle: AppHandle,
+    session_id: String,
+    pipe: R,
+    level: &'static str,
+) {
+    std::thread::spawn(move || {
+        let reader = BufReader::new(pipe);
+        for text in reader.lines().map_while(Result::ok) {
+            let _ = app_handle.emit(
+                "agent-session-log",
+                SessionLogPayload { session_id: session_id.clone(), level, message: text },
+            );
+        }
+    });
+}
+
+/// 在后台启动 Codex 并登记会话，立即返回会话信息
+pub fn launch_codex_session(app_handle: &AppHandle, path: &str) -> AppResult<AgentSession> {
+    let ctx = SpawnContext::new("open_folder_in_codex");
+    let (mut child, audit) = spawn_audited(app_handle, &ctx, &codex_builder(path), |_cmd| {
+        #[cfg(not(target_os = "windows"))]
+        _cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+    })
+    .map_err(|e| AppError::from(e).context(t("error.openCodex")))?;
+
+    let session = AgentSession {
+        id: next_id("codex"),
+        agent: "codex".to_string(),
+        project_path: path.to_string(),
+        pid: child.id(),
+        started_at: now_millis(),
+        ended_at: None,
+        running: true,
+        exit_code: None,
+    };
+
+    {
+        let state = app_handle.state::<AgentSessionState>();
+        let mut sessions = state.sessions.lock().map_err(|_| AppError::lock())?;
+        sessions.insert(session.id.clone(), session.clone());
+    }
+
+    // 转发 stdout / stderr（仅托管模式下存在管道）
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(app_handle.clone(), session.id.clone(), stdout, "info");
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(app_handle.clone(), session.id.clone(), stderr, "error");
+    }
+
+    // 监听进程退出，更新状态并通知前端
+    {
+        let app_handle_clone = app_handle.clone();
+        let session_id = session.id.clone();
+        std::thread::spawn(move || {
+            let code = child.wait().ok().and_then(|status| status.code());
+            audit.finish(code, None);
+            let state = app_handle_clone.state::<AgentSessionState>();
+            if let Some(updated) = state.mark_exited(&session_id, code) {
+                let _ = app_handle_clone.emit("agent-session-exit", updated);
+            }
+        });
+    }
+
+    Ok(session)
+}
+
+/// 列出已启动的会话，可按项目路径过滤，最新的在前
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_agent_sessions(
+    state: tauri::State<'_, AgentSessionState>,
+    project_path: Option<String>,
+) -> AppResult<Vec<AgentSession>> {
+    state.list(project_path.as_deref())
+}
+
+/// 将会话窗口切换到前台
+#[tauri::command(rename_all = "camelCase")]
+pub async fn focus_agent_session(
+    app_handle: AppHandle,
+    state: tauri::State<'_, AgentSessionState>,
+    session_id: String,
+) -> AppResult<String> {
+    let session = state.get(&session_id)?;
+    if !session.running {
+        return Err(AppError::new(ErrorCode::NotRunning, t("error.sessionEnded")));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        // WScript.Shell.AppActivate 支持直接按进程 ID 激活窗口
+        let script = format!(
+            "(New-Object -ComObject WScript.Shell).AppActivate({}) | Out-Null",
+            session.pid
+        );
+        let builder = ProcessBuilder::new("powershell").args(["-NoProfile", "-Command", &script]);
+        let output = output_audited(&app_handle, &SpawnContext::new("focus_agent_session"), &builder)
+            .map_err(|e| AppError::from(e).context(t("error.focusFailed")))?;
+        check_output(builder.program(), output)?;
+        Ok(t("session.focused"))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = app_handle;
+        Err(AppError::new(ErrorCode::Unsupported, t("error.focusUnsupported")))
+    }
+}
+
+/// 停止会话进程
+#[tauri::command(rename_all = "camelCase")]
+pub async fn stop_agent_session(
+    app_handle: AppHandle,
+    state: tauri::State<'_, AgentSessionState>,
+    session_id: String,
+) -> AppResult<String> {
+    let session = state.get(&session_id)?;
+    if !session.running {
+        return Err(AppError::new(ErrorCode::NotRunning, t("error.sessionEnded")));
+    }
+
+    kill_process(session.pid)?;
+
+    if let Some(updated) = state.mark_exited(&session_id, None) {
+        let _ = app_handle.emit("agent-session-exit", updated);
+    }
+    Ok(t("session.stopped"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, project: &str, started_at: u64) -> AgentSession {
+        AgentSession {
+            id: id.to_string(),
+            agent: "codex".to_string(),
+            project_path: project.to_string(),
+            pid: 1,
+            started_at,
+            ended_at: None,
+            running: true,
+            exit_code: None,
+        }
+    }
+
+    fn state_with(sessions: &[AgentSession]) -> AgentSessionState {
+        let state = AgentSessionState::default();
+        state
+            .sessions
+            .lock()
+            .unwrap()
+            .extend(sessions.iter().map(|s| (s.id.clone(), s.clone())));
+        state
+    }
+
+    #[test]
+    fn lists_newest_first_and_filters_by_project() {
+        let state = state_with(&[session("a", "/p1", 1), session("b", "/p2", 3), session("c", "/p1", 2)]);
+        let ids = |list: Vec<AgentSession>| list.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(state.list(None).unwrap()), ["b", "c", "a"]);
+        assert_eq!(ids(state.list(Some("/p1")).unwrap()), ["c", "a"]);
+        assert!(state.list(Some("/other")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn exit_is_recorded_once() {
+        let state = state_with(&[session("a", "/p", 1)]);
+        let exited = state.mark_exited("a", Some(3)).unwrap();
+        assert!(!exited.running);
+        assert_eq!(exited.exit_code, Some(3));
+        assert!(exited.ended_at.is_some());
+
+        // 停止后再收到退出事件时保留第一次的结果
+        assert_eq!(state.mark_exited("a", Some(0)).unwrap().exit_code, Some(3));
+        assert!(state.mark_exited("missing", None).is_none());
+        assert!(state.get("missing").is_err_and(|e| e.code == ErrorCode::NotFound));
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult};
+use crate::i18n::tf;
+use crate::process::{next_id, now_millis};
+
+/// 串行化对日志文件的追加，避免多个线程的记录交错
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 审计日志中的一行事件
+///
+/// 一次进程启动对应一条 `start` 和一条 `end` 事件，通过 `id` 关联；
+/// 被用户拒绝执行的命令只记录一条 `denied` 事件。
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum AuditEvent {
+    #[serde(rename_all = "camelCase")]
+    Start {
+        id: String,
+        timestamp: u64,
+        source: String,
+        program: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        env_keys: Vec<String>,
+        decision: Option<String>,
+        pid: Option<u32>,
+    },
+    #[serde(rename_all = "camelCase")]
+    End {
+        id: String,
+        timestamp: u64,
+        exit_code: Option<i32>,
+        error: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Denied {
+        id: String,
+        timestamp: u64,
+        source: String,
+        program: String,
+        args: Vec<String>,
+    },
+}
+
+/// 汇总后的一次进程启动记录，供前端展示
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpawnRecord {
+    pub id: String,
+    /// 触发来源，即发起启动的 Tauri 命令
+    pub source: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    pub env_keys: Vec<String>,
+    /// 策略判定：allowed / confirmed / denied，内部启动的进程为空
+    pub decision: Option<String>,
+    pub pid: Option<u32>,
+    /// running / exited / failed / denied
+    pub status: String,
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+    pub duration_ms: Option<u64>,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+}
+
+/// 审计日志查询条件
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQuery {
+    pub source: Option<String>,
+    /// 按程序名模糊匹配（不区分大小写）
+    pub program: Option<String>,
+    pub status: Option<String>,
+    /// 起始时间（Unix 毫秒，含）
+    pub since: Option<u64>,
+    /// 截止时间（Unix 毫秒，含）
+    pub until: Option<u64>,
+    /// 页码，从 1 开始
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+/// 分页查询结果，记录按启动时间倒序排列
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditPage {
+    pub records: Vec<SpawnRecord>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+const SECRET_HINTS: &[&str] = &[
+    "token", "secret", "password", "passwd", "apikey", "api-key", "api_key", "auth", "credential",
+];
+const SECRET_PREFIXES: &[&str] = &["sk-", "ghp_", "github_pat_", "xoxb-", "Bearer "];
+const REDACTED: &str = "***";
+
+fn looks_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    SECRET_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+/// 对参数列表做脱敏
+///
+/// 处理三种情况：`--token xxx` 形式的参数值、`API_KEY=xxx` 形式的赋值，
+/// 以及调用方明确给出的敏感值（例如注入的环境变量值）。
+fn redact_args(args: &[String], secrets: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(args.len());
+    let mut redact_next = false;
+    for arg in args {
+        let mut value = arg.clone();
+        for secret in secrets.iter().filter(|s| !s.is_empty()) {
+            value = value.replace(secret.as_str(), REDACTED);
+        }
+
+        if (redact_next && !value.starts_with('-')) || SECRET_PREFIXES.iter().any(|p| value.starts_with(p)) {
+            value = REDACTED.to_string();
+        } else if let Some((key, _)) = value.split_once('=') {
+            if looks_secret_name(key) {
+                value = format!("{}={}", key, REDACTED);
+            }
+        }
+
+        redact_next = arg.starts_with('-') && !arg.contains('=') && looks_secret_name(arg);
+        redacted.push(value);
+    }
+    redacted
+}
+
+/// 审计日志文件路径：`<app_data>/audit/process-audit.jsonl`
+///
+/// `<app_data>` 不在前端的文件系统作用域内，页面无法改写或删除日志。
+fn audit_log_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))?
+        .join("audit");
+    std::fs::create_dir_all(&dir).map_err(|e| AppError::io(tf("error.createAuditDir", &[("error", &e)]), &dir))?;
+    Ok(dir.join("process-audit.jsonl"))
+}
+
+fn write_event(app_handle: &AppHandle, event: &AuditEvent) -> AppResult<()> {
+    let path = audit_log_path(app_handle)?;
+    let line = serde_json::to_string(event)
+        .map_err(|e| AppError::internal(tf("error.serializeAudit", &[("error", &e)])))?;
+    let _guard = WRITE_LOCK.lock().map_err(|_| AppError::lock())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| AppError::io(tf("error.writeAudit", &[("error", &e)]), &path))?;
+    writeln!(file, "{}", line).map_err(|e| AppError::io(tf("error.writeAudit", &[("error", &e)]), &path))
+}
+
+/// 以 JSON Lines 形式追加一条审计事件；写入失败通过 `app-error` 事件提示，不影响进程启动
+fn append(app_handle: &AppHandle, event: &AuditEvent) {
+    if let Err(e) = write_event(app_handle, event) {
+        e.report(app_handle);
+    }
+}
+
+/// 记录一次被用户拒绝执行的命令
+pub fn record_denied(app_handle: &AppHandle, source: &str, program: &str, args: &[String]) {
+    append(
+        app_handle,
+        &AuditEvent::Denied {
+            id: next_id("spawn"),
+            timestamp: now_millis(),
+            source: source.to_string(),
+            program: program.to_string(),
+            args: redact_args(args, &[]),
+        },
+    );
+}
+
+/// 一次进程启动的审计句柄，进程结束时调用 `finish` 写入结束事件
+pub struct SpawnAudit {
+    app_handle: AppHandle,
+    id: String,
+}
+
+impl SpawnAudit {
+    /// 根据已启动（或启动失败）的命令写入 `start` 事件
+    ///
+    /// 环境变量只记录名称；`secrets` 中的值会在参数中被替换为 `***`。
+    pub fn start(
+        app_handle: &AppHandle,
+        source: &str,
+        cmd: &Command,
+        decision: Option<&str>,
+        secrets: &[String],
+        pid: Option<u32>,
+    ) -> Self {
+        let args: Vec<String> = cmd
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        let audit = Self { app_handle: app_handle.clone(), id: next_id("spawn") };
+        append(
+            app_handle,
+            &AuditEvent::Start {
+                id: audit.id.clone(),
+                timestamp: now_millis(),
+                source: source.to_string(),
+                program: cmd.get_program().to_string_lossy().to_string(),
+                args: redact_args(&args, secrets),
+                cwd: cmd.get_current_dir().map(|p| p.to_string_lossy().to_string()),
+                env_keys: cmd
+                    .get_envs()
+                    .map(|(k, _)| k.to_string_lossy().to_string())
+                    .collect(),
+                decision: decision.map(str::to_string),
+                pid,
+            },
+        );
+        audit
+    }
+
+    /// 写入结束事件
+    pub fn finish(self, exit_code: Option<i32>, error: Option<String>) {
+        append(
+            &self.app_handle,
+            &AuditEvent::End { id: self.id, timestamp: now_millis(), exit_code, error },
+        );
+    }
+}
+
+fn read_events(app_handle: &AppHandle) -> Vec<AuditEvent> {
+    let Ok(path) = audit_log_path(app_handle) else {
+        return Vec::new();
+    };
+    let Ok(file) = std::fs::File::open(path) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+/// 将事件流折叠为按启动顺序排列的记录
+fn fold_events(events: Vec<AuditEvent>) -> Vec<SpawnRecord> {
+    let mut records: Vec<SpawnRecord> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for event in events {
+        match event {
+            AuditEvent::Start { id, timestamp, source, program, args, cwd, env_keys, decision, pid } => {
+                index.insert(id.clone(), records.len());
+                records.push(SpawnRecord {
+                    id,
+                    source,
+                    program,
+                    args,
+                    cwd,
+                    env_keys,
+                    decision,
+                    pid,
+                    status: "running".to_string(),
+                    started_at: timestamp,
+                    ended_at: None,
+                    duration_ms: None,
+                    exit_code: None,
+                    error: None,
+                });
+            }
+            AuditEvent::End { id, timestamp, exit_code, error } => {
+                if let Some(&i) = index.get(&id) {
+                    let record = &mut records[i];
+                    record.status = if error.is_some() { "failed" } else { "exited" }.to_string();
+                    record.ended_at = Some(timestamp);
+                    record.duration_ms = Some(timestamp.saturating_sub(record.started_at));
+                    record.exit_code = exit_code;
+                    record.error = error;
+                }
+            }
+            AuditEvent::Denied { id, timestamp, source, program, args } => {
+                index.insert(id.clone(), records.len());
+                records.push(SpawnRecord {
+                    id,
+                    source,
+                    program,
+                    args,
+                    cwd: None,
+                    env_keys: Vec::new(),
+                    decision: Some("denied".to_string()),
+                    pid: None,
+                    status: "denied".to_string(),
+                    started_at: timestamp,
+                    ended_at: None,
+                    duration_ms: None,
+                    exit_code: None,
+                    error: None,
+                });
+            }
+        }
+    }
+    records
+}
+
+fn matches_query(record: &SpawnRecord, query: &AuditQuery) -> bool {
+    query.source.as_deref().is_none_or(|s| record.source == s)
+        && query.status.as_deref().is_none_or(|s| record.status == s)
+        && query
+            .program
+            .as_deref()
+            .is_none_or(|p| record.program.to_lowercase().contains(&p.to_lowercase()))
+        && query.since.is_none_or(|t| record.started_at >= t)
+        && query.until.is_none_or(|t| record.started_at <= t)
+}
+
+/// Tauri 命令：分页查询进程审计日志
+#[tauri::command]
+pub async fn query_audit_log(app_handle: AppHandle, query: Option<AuditQuery>) -> AppResult<AuditPage> {
+    let query = query.unwrap_or_default();
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(50).clamp(1, 500);
+
+    let mut records: Vec<SpawnRecord> = fold_events(read_events(&app_handle))
+        .into_iter()
+        .filter(|r| matches_query(r, &query))
+        .collect();
+    records.reverse();
+
+    let total = records.len();
+    let records = records
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .collect();
+
+    Ok(AuditPage { records, total, page, page_size })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn redacts_flag_values_assignments_and_known_secrets() {
+        let args = strings(&[
+            "--api-key",
+            "abc123",
+            "--verbose",
+            "GITHUB_TOKEN=ghp_x",
+            "sk-live",
+            "--url=https://host/?q=hunter2",
+            "plain",
+        ]);
+        assert_eq!(
+            redact_args(&args, &strings(&["hunter2", ""])),
+            strings(&["--api-key", "***", "--verbose", "GITHUB_TOKEN=***", "***", "--url=https://host/?q=***", "plain"])
+        );
+    }
+
+    #[test]
+    fn flag_followed_by_another_flag_is_kept() {
+        let args = strings(&["--token", "--dry-run", "value"]);
+        assert_eq!(redact_args(&args, &[]), strings(&["--token", "--dry-run", "value"]));
+    }
+
+    fn start(id: &str, timestamp: u64) -> AuditEvent {
+        AuditEvent::Start {
+            id: id.to_string(),
+            timestamp,
+            source: "execute_command".to_string(),
+            program: "npm".to_string(),
+            args: Vec::new(),
+            cwd: None,
+            env_keys: Vec::new(),
+            decision: Some("allowed".to_string()),
+            pid: Some(42),
+        }
+    }
+
+    fn end(id: &str, timestamp: u64, error: Option<&str>) -> AuditEvent {
+        AuditEvent::End { id: id.to_string(), timestamp, exit_code: Some(1), error: error.map(str::to_string) }
+    }
+
+    #[test]
+    fn folds_start_end_and_denied_events() {
+        let events = vec![
+            start("a", 100),
+            start("b", 150),
+            AuditEvent::Denied {
+                id: "c".to_string(),
+                timestamp: 160,
+                source: "execute_command".to_string(),
+                program: "rm".to_string(),
+                args: strings(&["-rf", "/"]),
+            },
+            end("a", 400, None),
+            end("b", 170, Some("timed out")),
+            // 没有对应 start 的结束事件被忽略
+            end("orphan", 500, None),
+        ];
+        let records = fold_events(events);
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.id.as_str(), r.status.as_str(), r.duration_ms))
+            .collect();
+        assert_eq!(summary, [("a", "exited", Some(300)), ("b", "failed", Some(20)), ("c", "denied", None)]);
+        assert_eq!(records[1].error.as_deref(), Some("timed out"));
+        assert_eq!(records[2].decision.as_deref(), Some("denied"));
+    }
+
+    #[test]
+    fn unfinished_spawns_stay_running() {
+        let records = fold_events(vec![start("a", 100)]);
+        assert_eq!(records[0].status, "running");
+        assert_eq!(records[0].ended_at, None);
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::tf;
+use crate::prompt_history::{HistoryStore, VersionSource};
+use crate::prompt_store::{check_unchanged, modified_millis, parse_document, render_document, validate_name, write_atomic, PromptScope};
+
+/// 把 `allowed-tools` 按逗号切分，括号内的逗号不切分，例如 `Bash(git add:*, git commit:*)`
+pub fn split_tools(text: &str) -> Vec<String> {
+    let mut tools = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            ',' if depth == 0 => tools.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    tools.push(current);
+    tools.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()
+}
+
+/// `allowed-tools` 兼容逗号分隔的字符串和 YAML 列表
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ToolList {
+    Text(String),
+    List(Vec<String>),
+}
+
+fn deserialize_tools<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(match <Option<ToolList> as serde::Deserialize>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(ToolList::Text(text)) => split_tools(&text),
+        Some(ToolList::List(list)) => list.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+    })
+}
+
+/// Claude Code 写回时使用逗号分隔的字符串，与官方文档的写法一致
+fn serialize_tools<S: serde::Serializer>(tools: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&tools.join(", "))
+}
+
+/// Claude Code 自定义命令的 YAML frontmatter
+///
+/// 未识别的键保存在 `extra` 中，写回时原样保留。
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+pub struct CommandFrontmatter {
+    #[serde(
+        default,
+        rename = "allowed-tools",
+        deserialize_with = "deserialize_tools",
+        serialize_with = "serialize_tools",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub allowed_tools: Vec<String>,
+    #[serde(default, rename = "argument-hint", skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// 一个 Claude Code 自定义命令
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeCommand {
+    /// 调用名称，子目录用 `:` 连接，例如 `frontend:review` 对应 `/frontend:review`
+    pub name: String,
+    /// 子目录部分，顶层命令为空
+    pub namespace: Option<String>,
+    pub scope: PromptScope,
+    pub description: Option<String>,
+    pub argument_hint: Option<String>,
+    pub model: Option<String>,
+    pub allowed_tools: Vec<String>,
+    pub content: String,
+    pub file_path: PathBuf,
+    pub updated_at: u64,
+}
+
+/// 创建或更新命令时前端提交的内容
+#[derive(serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeCommandInput {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub argument_hint: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(default)]
+    pub content: String,
+    /// 编辑开始时文件的修改时间；与磁盘上的不一致说明文件已被外部修改，拒绝覆盖
+    #[serde(default)]
+    pub expected_updated_at: Option<u64>,
+    /// 记录在版本历史中的说明
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+fn write_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.writeFile", &[("error", &e)]), path)
+}
+
+fn read_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.readFile", &[("error", &e)]), path)
+}
+
+/// 校验命令名称：每个 `:` 分隔的部分都必须是合法的文件名
+pub fn validate_command_name(name: &str) -> AppResult<()> {
+    let valid = !name.is_empty() && name.split(':').all(|segment| validate_name(segment).is_ok());
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::new(ErrorCode::InvalidArgument, tf("error.invalidCommandName", &[("name", &name)])))
+    }
+}
+
+/// 以 `.claude/commands` 目录中的 Markdown 文件为唯一数据来源的命令存储
+pub struct ClaudeCommandStore {
+    dir: PathBuf,
+    scope: PromptScope,
+    history: Option<HistoryStore>,
+}
+
+impl ClaudeCommandStore {
+    pub fn new(dir: impl Into<PathBuf>, scope: PromptScope) -> Self {
+        Self { dir: dir.into(), scope, history: None }
+    }
+
+    /// 写入和重命名时同时维护版本历史
+    pub fn with_history(mut self, history: HistoryStore) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// 给出项目路径时为 `<project>/.claude/commands`，否则为 `~/.claude/commands`
+    pub fn for_scope(app_handle: &AppHandle, project_path: Option<&str>) -> AppResult<Self> {
+        let store = match project_path {
+            Some(project) => Self::new(Path::new(project).join(".claude").join("commands"), PromptScope::Project),
+            None => app_handle
+                .path()
+                .home_dir()
+                .map(|home| Self::new(home.join(".claude").join("commands"), PromptScope::User))
+                .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))?,
+        };
+        Ok(match HistoryStore::for_app(app_handle) {
+            Ok(history) => store.with_history(history),
+            Err(_) => store,
+        })
+    }
+
+    /// `ns:cmd` -> `<dir>/ns/cmd.md`
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        let mut path = self.dir.clone();
+        let segments: Vec<&str> = name.split(':').collect();
+        for segment in &segments[..segments.len() - 1] {
+            path.push(segment);
+        }
+        path.push(format!("{}.md", segments[segments.len() - 1]));
+        path
+    }
+
+    /// `<dir>/ns/cmd.md` -> `ns:cmd`
+    pub fn name_for(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.dir).ok()?.with_extension("");
+        let segments: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+        (!segments.is_empty()).then(|| segments.join(":"))
+    }
+
+    fn existing_path(&self, name: &str) -> AppResult<PathBuf> {
+        validate_command_name(name)?;
+        let path = self.path_for(name);
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(AppError::new(ErrorCode::NotFound, tf("error.commandNotFound", &[("name", &name)])).with_path(&path))
+        }
+    }
+
+    /// 读取并解析一个命令文件
+    pub fn load(&self, path: &Path) -> AppResult<ClaudeCommand> {
+        let text = std::fs::read_to_string(path).map_err(|e| read_error(e, path))?;
+        let meta = std::fs::metadata(path).map_err(|e| read_error(e, path))?;
+        let (frontmatter, content): (CommandFrontmatter, String) = parse_document(&text).map_err(|e| {
+            AppError::new(ErrorCode::InvalidConfig, tf("error.invalidFrontmatter", &[("error", &e)])).with_path(path)
+        })?;
+        let name = self
+            .name_for(path)
+            .unwrap_or_else(|| path.file_stem().unwrap_or_default().to_string_lossy().to_string());
+        Ok(ClaudeCommand {
+            namespace: name.rsplit_once(':').map(|(ns, _)| ns.to_string()),
+            scope: self.scope,
+            description: frontmatter.description,
+            argument_hint: frontmatter.argument_hint,
+            model: frontmatter.model,
+            allowed_tools: frontmatter.allowed_tools,
+            file_path: path.to_path_buf(),
+            updated_at: modified_millis(&meta),
+            name,
+            content,
+        })
+    }
+
+    /// 递归列出所有命令，按名称排序；无法解析的文件跳过，其错误一并返回
+    pub fn list(&self) -> (Vec<ClaudeCommand>, Vec<AppError>) {
+        let (mut commands, mut errors) = (Vec::new(), Vec::new());
+        let mut pending = vec![self.dir.clone()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                if path.is_dir() {
+                    pending.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "md") {
+                    match self.load(&path) {
+                        Ok(command) => commands.push(command),
+                        Err(e) => errors.push(e),
+                    }
+                }
+            }
+        }
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        (commands, errors)
+    }
+
+    pub fn get(&self, name: &str) -> AppResult<ClaudeCommand> {
+        let path = self.existing_path(name)?;
+        self.load(&path)
+    }
+
+    /// 生成写入 `path` 的文件内容；已存在的文件保留 frontmatter 中未识别的键
+    pub fn render(&self, path: &Path, input: &ClaudeCommandInput) -> AppResult<String> {
+        let extra = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| parse_document::<CommandFrontmatter>(&text).ok())
+            .map(|(frontmatter, _)| frontmatter.extra)
+            .unwrap_or_default();
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+        let frontmatter = CommandFrontmatter {
+            allowed_tools: input.allowed_tools.iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+            argument_hint: non_empty(&input.argument_hint),
+            description: non_empty(&input.description),
+            model: non_empty(&input.model),
+            extra,
+        };
+        render_document(&frontmatter, &input.content).map_err(|e| write_error(e, path))
+    }
+
+    fn write(&self, path: &Path, input: &ClaudeCommandInput) -> AppResult<ClaudeCommand> {
+        let text = self.render(path, input)?;
+        if let Some(history) = &self.history {
+            history.snapshot_existing(path)?;
+        }
+        write_atomic(path, text.as_bytes()).map_err(|e| write_error(e, path))?;
+        if let Some(history) = &self.history {
+            history.record(path, text.as_bytes(), VersionSource::Ui, input.message.clone())?;
+        }
+        self.load(path)
+    }
+
+    pub fn create(&self, name: &str, input: &ClaudeCommandInput) -> AppResult<ClaudeCommand> {
+        validate_command_name(name)?;
+        let path = self.path_for(name);
+        if path.exists() {
+            return Err(AppError::new(ErrorCode::InvalidArgument, tf("error.commandExists", &[("name", &name)])).with_path(&path));
+        }
+        self.write(&path, input)
+    }
+
+    pub fn update(&self, name: &str, input: &ClaudeCommandInput) -> AppResult<ClaudeCommand> {
+        let path = self.existing_path(name)?;
+        if let Some(expected) = input.expected_updated_at {
+            check_unchanged(&path, expected)?;
+        }
+        self.write(&path, input)
+    }
+
+    /// 重命名命令，可以移动到其他命名空间；目标名称已存在时拒绝覆盖
+    pub fn rename(&self, name: &str, new_name: &str) -> AppResult<ClaudeCommand> {
+        let from = self.existing_path(name)?;
+        validate_command_name(new_name)?;
+        let to = self.path_for(new_name);
+        if to.exists() {
+            return Err(AppError::new(ErrorCode::InvalidArgument, tf("error.commandExists", &[("name", &new_name)])).with_path(&to));
+        }
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| write_error(e, parent))?;
+        }
+        std::fs::rename(&from, &to).map_err(|e| write_error(e, &to))?;
+        if let Some(history) = &self.history {
+            history.rename(&from, &to)?;
+        }
+        self.remove_empty_dirs(&from);
+        self.load(&to)
+    }
+
+    pub fn delete(&self, name: &str) -> AppResult<()> {
+        let path = self.existing_path(name)?;
+        std::fs::remove_file(&path).map_err(|e| write_error(e, &path))?;
+        self.remove_empty_dirs(&path);
+        Ok(())
+    }
+
+    /// 删除或移走命令后清理空的命名空间目录，不会删除命令根目录本身
+    fn remove_empty_dirs(&self, path: &Path) {
+        let mut dir = path.parent();
+        while let Some(current) = dir {
+            if current == self.dir || !current.starts_with(&self.dir) || std::fs::remove_dir(current).is_err() {
+                break;
+            }
+            dir = current.parent();
+        }
+    }
+}
+
+/// Tauri 命令：列出 Claude Code 自定义命令；给出 `projectPath` 时列出项目级命令
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_claude_commands(app_handle: AppHandle, project_path: Option<String>) -> AppResult<Vec<ClaudeCommand>> {
+    let (commands, errors) = ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?.list();
+    for error in &errors {
+        error.report(&app_handle);
+    }
+    Ok(commands)
+}
+
+/// Tauri 命令：读取单个命令
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_claude_command(app_handle: AppHandle, name: String, project_path: Option<String>) -> AppResult<ClaudeCommand> {
+    ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?.get(&name)
+}
+
+/// Tauri 命令：创建命令，同名文件已存在时报错
+#[tauri::command(rename_all = "camelCase")]
+pub async fn create_claude_command(
+    app_handle: AppHandle,
+    name: String,
+    command: ClaudeCommandInput,
+    project_path: Option<String>,
+) -> AppResult<ClaudeCommand> {
+    ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?.create(&name, &command)
+}
+
+/// Tauri 命令：更新已有命令
+#[tauri::command(rename_all = "camelCase")]
+pub async fn update_claude_command(
+    app_handle: AppHandle,
+    name: String,
+    command: ClaudeCommandInput,
+    project_path: Option<String>,
+) -> AppResult<ClaudeCommand> {
+    ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?.update(&name, &command)
+}
+
+/// Tauri 命令：重命名命令或移动到其他命名空间
+#[tauri::command(rename_all = "camelCase")]
+pub async fn rename_claude_command(
+    app_handle: AppHandle,
+    name: String,
+    new_name: String,
+    project_path: Option<String>,
+) -> AppResult<ClaudeCommand> {
+    ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?.rename(&name, &new_name)
+}
+
+/// Tauri 命令：删除命令
+#[tauri::command(rename_all = "camelCase")]
+pub async fn delete_claude_command(app_handle: AppHandle, name: String, project_path: Option<String>) -> AppResult<()> {
+    ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?.delete(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(content: &str) -> ClaudeCommandInput {
+        ClaudeCommandInput {
+            description: Some("Review a change".to_string()),
+            argument_hint: Some("[pr-number]".to_string()),
+            allowed_tools: vec!["Bash(git diff:*, git log:*)".to_string(), "Read".to_string()],
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn splits_tools_outside_parentheses() {
+        assert_eq!(
+            split_tools("Bash(git add:*, git commit:*), Read,Edit"),
+            ["Bash(git add:*, git commit:*)", "Read", "Edit"]
+        );
+    }
+
+    #[test]
+    fn namespaced_commands_map_to_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ClaudeCommandStore::new(dir.path(), PromptScope::Project);
+        store.create("frontend:review", &input("Review $ARGUMENTS")).unwrap();
+        store.create("deploy", &input("Deploy")).unwrap();
+
+        let path = dir.path().join("frontend").join("review.md");
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("allowed-tools: Bash(git diff:*, git log:*), Read"));
+        assert!(text.contains("argument-hint: '[pr-number]'"));
+
+        let (commands, errors) = store.list();
+        assert!(errors.is_empty());
+        let names: Vec<&str> = commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["deploy", "frontend:review"]);
+        assert_eq!(commands[1].namespace.as_deref(), Some("frontend"));
+        assert_eq!(commands[1].allowed_tools, ["Bash(git diff:*, git log:*)", "Read"]);
+    }
+
+    #[test]
+    fn list_returns_unparsable_files_as_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ClaudeCommandStore::new(dir.path(), PromptScope::User);
+        store.create("deploy", &input("Deploy")).unwrap();
+        std::fs::write(dir.path().join("broken.md"), "---\ndescription: [unclosed\n---\nbody").unwrap();
+
+        let (commands, errors) = store.list();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::InvalidConfig);
+    }
+
+    #[test]
+    fn rename_moves_between_namespaces_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ClaudeCommandStore::new(dir.path(), PromptScope::User);
+        store.create("old:ns:cmd", &input("body")).unwrap();
+
+        let renamed = store.rename("old:ns:cmd", "new:cmd").unwrap();
+        assert_eq!(renamed.name, "new:cmd");
+        assert!(!dir.path().join("old").exists());
+
+        store.delete("new:cmd").unwrap();
+        assert!(!dir.path().join("new").exists());
+        assert!(dir.path().exists());
+        assert!(store.create("../escape", &input("x")).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::command_policy;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::{t, tf};
+use crate::process::{kill_process, next_id, now_millis, spawn_audited, ProcessBuilder, SpawnContext};
+
+/// 流式命令的可选执行参数
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOptions {
+    /// 工作目录
+    pub cwd: Option<String>,
+    /// 额外注入的环境变量
+    pub env: Option<HashMap<String, String>>,
+    /// 写入子进程 stdin 的内容，写完后关闭 stdin
+    pub stdin: Option<String>,
+    /// 超时时间（毫秒），超时后强制结束进程
+    pub timeout_ms: Option<u64>,
+}
+
+struct JobEntry {
+    pid: u32,
+    cancelled: bool,
+    timed_out: bool,
+}
+
+/// 正在运行的流式命令，按任务 ID 索引
+#[derive(Default)]
+pub struct CommandJobState {
+    jobs: Mutex<HashMap<String, JobEntry>>,
+}
+
+impl CommandJobState {
+    /// 标记任务为取消或超时并结束进程；任务已结束时返回 false
+    fn terminate(&self, job_id: &str, timed_out: bool) -> AppResult<bool> {
+        let pid = {
+            let mut jobs = self.jobs.lock().map_err(|_| AppError::lock())?;
+            match jobs.get_mut(job_id) {
+                Some(job) => {
+                    if timed_out {
+                        job.timed_out = true;
+                    } else {
+                        job.cancelled = true;
+                    }
+                    job.pid
+                }
+                None => return Ok(false),
+            }
+        };
+        kill_process(pid)?;
+        Ok(true)
+    }
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct OutputPayload {
+    job_id: String,
+    stream: &'static str,
+    chunk: String,
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LinePayload {
+    job_id: String,
+    stream: &'static str,
+    line: String,
+}
+
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct JobExitPayload {
+    job_id: String,
+    code: Option<i32>,
+    cancelled: bool,
+    timed_out: bool,
+    duration_ms: u64,
+}
+
+/// 按块读取输出直到结束
+///
+/// 不按行切分，这样 npm 之类只刷新同一行的进度输出也能及时显示；
+/// 被块边界截断的 UTF-8 字符会留到下一块再解码。
+fn read_chunks<R: Read>(mut pipe: R, mut emit: impl FnMut(String)) {
+    let mut buf = [0u8; 4096];
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let n = match pipe.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        pending.extend_from_slice(&buf[..n]);
+
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(_) => pending.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => pending.len(),
+        };
+        if valid == 0 {
+            continue;
+        }
+        emit(String::from_utf8_lossy(&pending[..valid]).to_string());
+        pending.drain(..valid);
+    }
+    if !pending.is_empty() {
+        emit(String::from_utf8_lossy(&pending).to_string());
+    }
+}
+
+/// 按行读取输出直到结束，行尾的 `\r\n` 会被去掉
+fn read_lines<R: Read>(pipe: R, mut emit: impl FnMut(String)) {
+    for line in BufReader::new(pipe).split(b'\n').map_while(Result::ok) {
+        emit(String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(&line)).to_string());
+    }
+}
+
+/// 将输出按块转发为 `command-output` 事件
+fn forward_chunks<R: Read + Send + 'static>(
+    app_handle: AppHandle,
+    job_id: String,
+    pipe: R,
+    stream: &'static str,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        read_chunks(pipe, |chunk| {
+            let _ = app_handle.emit("command-output", OutputPayload { job_id: job_id.clone(), stream, chunk });
+        })
+    })
+}
+
+/// 将输出按行转发为 `tool-output` 事件
+fn forward_lines<R: Read + Send + 'static>(
+    app_handle: AppHandle,
+    job_id: String,
+    pipe: R,
+    stream: &'static str,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        read_lines(pipe, |line| {
+            let _ = app_handle.emit("tool-output", LinePayload { job_id: job_id.clone(), stream, line });
+        })
+    })
+}
+
+/// 流式任务的输出转发方式
+#[derive(Clone, Copy)]
+pub enum OutputMode {
+    /// 按块推送 `command-output` 事件
+    Chunks,
+    /// 按行推送 `tool-output` 事件
+    Lines,
+}
+
+/// 启动一个流式任务并登记到 `CommandJobState`，立即返回任务 ID
+///
+/// 可选地写入 stdin 并在超时后结束进程；进程退出且输出读完后推送 `command-exit` 事件，
+/// 因此任务可以统一通过 `cancel_command` 取消。
+pub fn spawn_job(
+    app_handle: &AppHandle,
+    ctx: &SpawnContext,
+    builder: &ProcessBuilder,
+    stdin: Option<Vec<u8>>,
+    timeout_ms: Option<u64>,
+    mode: OutputMode,
+) -> AppResult<String> {
+    let has_stdin = stdin.is_some();
+    let (mut child, audit) = spawn_audited(app_handle, ctx, builder, |cmd| {
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(if has_stdin { Stdio::piped() } else { Stdio::null() });
+    })?;
+    let job_id = next_id("job");
+    let started_at = now_millis();
+
+    {
+        let state = app_handle.state::<CommandJobState>();
+        let mut jobs = state.jobs.lock().map_err(|_| AppError::lock())?;
+        jobs.insert(
+            job_id.clone(),
+            JobEntry { pid: child.id(), cancelled: false, timed_out: false },
+        );
+    }
+
+    // 写入 stdin 后关闭，避免子进程一直等待输入
+    if let (Some(mut pipe), Some(input)) = (child.stdin.take(), stdin) {
+        std::thread::spawn(move || {
+            let _ = pipe.write_all(&input);
+        });
+    }
+
+    let forward = match mode {
+        OutputMode::Chunks => forward_chunks::<Box<dyn Read + Send>>,
+        OutputMode::Lines => forward_lines::<Box<dyn Read + Send>>,
+    };
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(forward(app_handle.clone(), job_id.clone(), Box::new(stdout), "stdout"));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(forward(app_handle.clone(), job_id.clone(), Box::new(stderr), "stderr"));
+    }
+
+    if let Some(timeout_ms) = timeout_ms {
+        let app_handle_clone = app_handle.clone();
+        let job_id = job_id.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(timeout_ms));
+            let _ = app_handle_clone.state::<CommandJobState>().terminate(&job_id, true);
+        });
+    }
+
+    // 等待进程退出和输出读完后再通知前端，保证 exit 事件在最后一块输出之后
+    {
+        let app_handle_clone = app_handle.clone();
+        let job_id = job_id.clone();
+        std::thread::spawn(move || {
+            let code = child.wait().ok().and_then(|status| status.code());
+            audit.finish(code, None);
+            for reader in readers {
+                let _ = reader.join();
+            }
+            let state_ref = app_handle_clone.state::<CommandJobState>();
+            let (cancelled, timed_out) = state_ref
+                .jobs
+                .lock()
+                .ok()
+                .and_then(|mut jobs| jobs.remove(&job_id))
+                .map(|job| (job.cancelled, job.timed_out))
+                .unwrap_or((false, false));
+            let _ = app_handle_clone.emit(
+                "command-exit",
+                JobExitPayload {
+                    job_id,
+                    code,
+                    cancelled,
+                    timed_out,
+                    duration_ms: now_millis().saturating_sub(started_at),
+                },
+            );
+        });
+    }
+
+    Ok(job_id)
+}
+
+/// 以流式方式执行命令，立即返回任务 ID
+///
+/// 输出通过 `command-output` 事件推送，进程结束时推送 `command-exit` 事件。
+#[tauri::command(rename_all = "camelCase")]
+pub async fn execute_command_stream(
+    app_handle: AppHandle,
+    command: String,
+    args: Vec<String>,
+    options: Option<CommandOptions>,
+) -> AppResult<String> {
+    let options = options.unwrap_or_default();
+    let exec_ctx = command_policy::ExecContext {
+        cwd: options.cwd.as_deref(),
+        env: options.env.as_ref(),
+    };
+    let decision = command_policy::authorize(&app_handle, "execute_command_stream", &command, &args, &exec_ctx)?;
+
+    let mut builder = ProcessBuilder::new(&command).args(&args);
+    if let Some(cwd) = &options.cwd {
+        builder = builder.cwd(cwd);
+    }
+    if let Some(env_map) = &options.env {
+        builder = builder.envs(env_map);
+    }
+
+    let ctx = SpawnContext::new("execute_command_stream").decision(decision);
+    spawn_job(
+        &app_handle,
+        &ctx,
+        &builder,
+        options.stdin.map(String::into_bytes),
+        options.timeout_ms,
+        OutputMode::Chunks,
+    )
+}
+
+/// 取消正在运行的流式命令
+#[tauri::command(rename_all = "camelCase")]
+pub async fn cancel_command(
+    state: tauri::State<'_, CommandJobState>,
+    job_id: String,
+) -> AppResult<String> {
+    if state.terminate(&job_id, false)? {
+        Ok(t("command.cancelled"))
+    } else {
+        Err(AppError::new(ErrorCode::NotFound, tf("error.jobNotFound", &[("id", &job_id)])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每次最多返回 `step` 字节的读取器，用于模拟管道分块到达
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.step.min(self.data.len()).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    fn collect(read: impl FnOnce(&mut dyn FnMut(String))) -> Vec<String> {
+        let mut out = Vec::new();
+        read(&mut |text| out.push(text));
+        out
+    }
+
+    #[test]
+    fn chunks_keep_split_utf8_characters_intact() {
+        let text = "进度 50%\r进度 100%\n";
+        let chunks = collect(|emit| read_chunks(Trickle { data: text.as_bytes(), step: 4 }, emit));
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), text);
+        assert!(!chunks.iter().any(|c| c.contains('\u{fffd}')));
+    }
+
+    #[test]
+    fn chunks_flush_trailing_invalid_bytes() {
+        let chunks = collect(|emit| read_chunks(Trickle { data: b"ok\xe8", step: 64 }, emit));
+        assert_eq!(chunks.concat(), "ok\u{fffd}");
+    }
+
+    #[test]
+    fn lines_strip_crlf_and_keep_last_partial_line() {
+        let lines = collect(|emit| read_lines(Trickle { data: b"one\r\ntwo\n\nthree", step: 3 }, emit));
+        assert_eq!(lines, ["one", "two", "", "three"]);
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::audit_log;
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::{t, tf};
+
+/// 允许列表中的一条命名命令
+///
+/// `args` 中形如 `{package}` 的参数是占位符，实际值必须出现在 `params` 对应的候选列表中。
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedCommand {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub program: String,
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub params: HashMap<String, Vec<String>>,
+}
+
+/// 命令执行策略，保存在 `<app_config>/command-policy.json`
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandPolicy {
+    pub commands: Vec<NamedCommand>,
+}
+
+fn placeholder(arg: &str) -> Option<&str> {
+    arg.strip_prefix('{').and_then(|s| s.strip_suffix('}'))
+}
+
+impl NamedCommand {
+    fn new(name: &str, description: &str, program: &str, args: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            program: program.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            params: HashMap::new(),
+        }
+    }
+
+    fn with_param(mut self, name: &str, values: &[&str]) -> Self {
+        self.params
+            .insert(name.to_string(), values.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    /// 判断一次具体调用是否与该命令模板匹配
+    pub fn matches(&self, program: &str, args: &[String]) -> bool {
+        if !self.program.eq_ignore_ascii_case(program) || self.args.len() != args.len() {
+            return false;
+        }
+        self.args.iter().zip(args).all(|(template, actual)| match placeholder(template) {
+            Some(param) => self
+                .params
+                .get(param)
+                .is_some_and(|allowed| allowed.iter().any(|v| v == actual)),
+            None => template == actual,
+        })
+    }
+
+    /// 用参数值填充占位符，生成实际参数列表
+    pub fn render(&self, values: &HashMap<String, String>) -> AppResult<Vec<String>> {
+        self.args
+            .iter()
+            .map(|template| match placeholder(template) {
+                Some(param) => {
+                    let value = values
+                        .get(param)
+                        .ok_or_else(|| AppError::new(ErrorCode::InvalidArgument, tf("error.missingParam", &[("param", &param)])))?;
+                    let allowed = self.params.get(param).map(Vec::as_slice).unwrap_or_default();
+                    if allowed.iter().any(|v| v == value) {
+                        Ok(value.clone())
+                    } else {
+                        Err(AppError::new(
+                            ErrorCode::InvalidArgument,
+                            tf("error.paramNotAllowed", &[("param", &param), ("value", value)]),
+                        ))
+                    }
+                }
+                None => Ok(template.clone()),
+            })
+            .collect()
+    }
+}
+
+const AGENT_PACKAGES: &[&str] = &[
+    "@openai/codex",
+    "@anthropic-ai/claude-code",
+];
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self {
+            commands: vec![
+                NamedCommand::new("codex-version", "查询 Codex 版本", "codex", &["--version"]),
+                NamedCommand::new("claude-version", "查询 Claude Code 版本", "claude", &["--version"]),
+                NamedCommand::new("npm-version", "查询 npm 版本", "npm", &["--version"]),
+                NamedCommand::new("npm-install-global", "全局安装 npm 包", "npm", &["install", "-g", "{package}"])
+                    .with_param("package", AGENT_PACKAGES),
+                NamedCommand::new("npm-update-global", "全局更新 npm 包", "npm", &["update", "-g", "{package}"])
+                    .with_param("package", AGENT_PACKAGES),
+            ],
+        }
+    }
+}
+
+impl CommandPolicy {
+    fn config_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+        app_handle
+            .path()
+            .app_config_dir()
+            .map(|dir| dir.join("command-policy.json"))
+            .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))
+    }
+
+    /// 读取策略配置；文件不存在时使用内置默认值
+    pub fn load(app_handle: &AppHandle) -> AppResult<Self> {
+        let path = Self::config_path(app_handle)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::io(tf("error.readPolicy", &[("error", &e)]), &path))?;
+        serde_json::from_str(&content)
+            .map_err(|e| AppError::new(ErrorCode::InvalidConfig, tf("error.invalidPolicy", &[("error", &e)])).with_path(&path))
+    }
+
+    pub fn find_match(&self, program: &str, args: &[String]) -> Option<&NamedCommand> {
+        self.commands.iter().find(|c| c.matches(program, args))
+    }
+
+    pub fn find_by_name(&self, name: &str) -> Option<&NamedCommand> {
+        self.commands.iter().find(|c| c.name == name)
+    }
+}
+
+/// 命令执行时附带的工作目录与环境变量
+///
+/// 它们会改变程序解析（`PATH`）和运行时行为（如 `NODE_OPTIONS`），因此参与授权判定。
+#[derive(Default, Clone, Copy)]
+pub struct ExecContext<'a> {
+    pub cwd: Option<&'a str>,
+    pub env: Option<&'a HashMap<String, String>>,
+}
+
+impl ExecContext<'_> {
+    fn is_empty(&self) -> bool {
+        self.cwd.is_none() && self.env.is_none_or(|env| env.is_empty())
+    }
+
+    /// 按键名排序的 `KEY=value` 列表，便于在对话框中完整展示
+    fn env_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .env
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        lines.sort();
+        lines
+    }
+}
+
+/// 判定命令是否可以直接放行
+///
+/// 只有命中允许列表且未指定工作目录和环境变量的命令才会自动放行；
+/// 允许列表按程序名匹配，附带 `PATH` 或 `cwd` 时实际运行的程序可能已经不同。
+fn is_preapproved(policy: &CommandPolicy, program: &str, args: &[String], ctx: &ExecContext) -> bool {
+    ctx.is_empty() && policy.find_match(program, args).is_some()
+}
+
+/// 弹出确认对话框，询问用户是否执行命令；工作目录和环境变量一并展示
+fn confirm_with_user(app_handle: &AppHandle, program: &str, args: &[String], ctx: &ExecContext) -> bool {
+    use std::sync::mpsc;
+    let (tx, rx) = mpsc::channel();
+
+    let mut command_line = std::iter::once(program.to_string())
+        .chain(args.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if let Some(cwd) = ctx.cwd {
+        command_line.push_str("\n\n");
+        command_line.push_str(&tf("dialog.confirmCommand.cwd", &[("cwd", &cwd)]));
+    }
+    let env_lines = ctx.env_lines();
+    if !env_lines.is_empty() {
+        command_line.push_str("\n\n");
+        command_line.push_str(&tf("dialog.confirmCommand.env", &[("env", &env_lines.join("\n"))]));
+    }
+    app_handle
+        .dialog()
+        .message(tf("dialog.confirmCommand.message", &[("command", &command_line)]))
+        .title(t("dialog.confirmCommand.title"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            t("dialog.confirmCommand.run"),
+            t("dialog.confirmCommand.cancel"),
+        ))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+
+    rx.recv().unwrap_or(false)
+}
+
+/// 检查一次命令执行是否被允许，返回判定结果（allowed / confirmed）
+///
+/// 命中允许列表且不带工作目录、环境变量的命令直接放行；其余命令需要用户在原生对话框中确认，
+/// 被拒绝的命令会写入审计日志。
+pub fn authorize(
+    app_handle: &AppHandle,
+    source: &str,
+    program: &str,
+    args: &[String],
+    ctx: &ExecContext,
+) -> AppResult<&'static str> {
+    let policy = CommandPolicy::load(app_handle)?;
+
+    if is_preapproved(&policy, program, args, ctx) {
+        Ok("allowed")
+    } else if confirm_with_user(app_handle, program, args, ctx) {
+        Ok("confirmed")
+    } else {
+        audit_log::record_denied(app_handle, source, program, args);
+        Err(AppError::new(ErrorCode::CommandDenied, tf("error.commandDenied", &[("program", &program)])).with_program(program))
+    }
+}
+
+/// Tauri 命令：获取当前生效的命令策略
+#[tauri::command]
+pub async fn get_command_policy(app_handle: AppHandle) -> AppResult<CommandPolicy> {
+    CommandPolicy::load(&app_handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn allow_listed_commands_without_context_are_preapproved() {
+        let policy = CommandPolicy::default();
+        let ctx = ExecContext::default();
+        assert!(is_preapproved(&policy, "codex", &args(&["--version"]), &ctx));
+        assert!(is_preapproved(&policy, "npm", &args(&["install", "-g", "@openai/codex"]), &ctx));
+        assert!(!is_preapproved(&policy, "npm", &args(&["install", "-g", "@anthropic-ai/codex"]), &ctx));
+        assert!(!is_preapproved(&policy, "codex", &args(&["exec"]), &ctx));
+    }
+
+    #[test]
+    fn cwd_or_env_always_requires_confirmation() {
+        let policy = CommandPolicy::default();
+        let version = args(&["--version"]);
+
+        let with_cwd = ExecContext { cwd: Some("/tmp"), env: None };
+        assert!(!is_preapproved(&policy, "codex", &version, &with_cwd));
+
+        let env = HashMap::from([("NODE_OPTIONS".to_string(), "--require ./x.js".to_string())]);
+        let with_env = ExecContext { cwd: None, env: Some(&env) };
+        assert!(!is_preapproved(&policy, "codex", &version, &with_env));
+
+        // 空的环境变量表等同于未指定
+        let empty = HashMap::new();
+        let with_empty_env = ExecContext { cwd: None, env: Some(&empty) };
+        assert!(is_preapproved(&policy, "codex", &version, &with_empty_env));
+    }
+
+    #[test]
+    fn env_lines_are_sorted_for_display() {
+        let env = HashMap::from([
+            ("PATH".to_string(), "/evil".to_string()),
+            ("NODE_OPTIONS".to_string(), "--inspect".to_string()),
+        ]);
+        let ctx = ExecContext { cwd: None, env: Some(&env) };
+        assert_eq!(ctx.env_lines(), ["NODE_OPTIONS=--inspect", "PATH=/evil"]);
+    }
+
+    #[test]
+    fn placeholders_only_accept_listed_values() {
+        let policy = CommandPolicy::default();
+        let install = policy.find_by_name("npm-install-global").unwrap();
+        let ok = HashMap::from([("package".to_string(), "@openai/codex".to_string())]);
+        assert_eq!(install.render(&ok).unwrap(), args(&["install", "-g", "@openai/codex"]));
+        let bad = HashMap::from([("package".to_string(), "left-pad".to_string())]);
+        assert!(install.render(&bad).is_err_and(|e| e.code == ErrorCode::InvalidArgument));
+    }
+}
@@ -0,0 +1,252 @@
+use crate::i18n::t;
+use crate::process::SpawnError;
+
+/// 稳定的错误码，前端可据此做程序化处理（例如找不到 Codex 时提示安装）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum ErrorCode {
+    /// 找不到可执行文件
+    ExecutableNotFound,
+    /// 没有执行或访问权限
+    PermissionDenied,
+    /// 进程以非零退出码结束
+    NonZeroExit,
+    /// 启动或等待进程失败
+    ProcessFailed,
+    /// 进程执行超时
+    TimedOut,
+    /// 用户拒绝执行命令
+    CommandDenied,
+    /// 内置工具的校验和与清单不一致
+    ChecksumMismatch,
+    /// 清单要求校验，但没有当前平台的校验和
+    ChecksumMissing,
+    /// 服务已在运行
+    AlreadyRunning,
+    /// 服务或会话未运行
+    NotRunning,
+    /// 请求的会话、任务或资源不存在
+    NotFound,
+    /// 参数不合法
+    InvalidArgument,
+    /// 配置文件格式错误
+    InvalidConfig,
+    /// 文件在编辑期间被外部修改
+    Conflict,
+    /// 当前平台不支持该操作
+    Unsupported,
+    /// 文件读写错误
+    Io,
+    /// 其他内部错误
+    Internal,
+}
+
+impl ErrorCode {
+    /// 对应的 i18n 键，例如 `error.executableNotFound`
+    pub fn i18n_key(self) -> &'static str {
+        match self {
+            ErrorCode::ExecutableNotFound => "error.executableNotFound",
+            ErrorCode::PermissionDenied => "error.permissionDenied",
+            ErrorCode::NonZeroExit => "error.nonZeroExit",
+            ErrorCode::ProcessFailed => "error.processFailed",
+            ErrorCode::TimedOut => "error.timedOut",
+            ErrorCode::CommandDenied => "error.commandDenied",
+            ErrorCode::ChecksumMismatch => "error.checksumMismatch",
+            ErrorCode::ChecksumMissing => "error.checksumMissing",
+            ErrorCode::AlreadyRunning => "error.alreadyRunning",
+            ErrorCode::NotRunning => "error.notRunning",
+            ErrorCode::NotFound => "error.notFound",
+            ErrorCode::InvalidArgument => "error.invalidArgument",
+            ErrorCode::InvalidConfig => "error.invalidConfig",
+            ErrorCode::Conflict => "error.conflict",
+            ErrorCode::Unsupported => "error.unsupported",
+            ErrorCode::Io => "error.io",
+            ErrorCode::Internal => "error.internal",
+        }
+    }
+}
+
+/// 错误的结构化附加信息
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+}
+
+/// 所有 Tauri 命令统一返回的错误类型
+///
+/// 序列化为 `{ code, message, i18nKey, details }`，`message` 仅用于直接展示，
+/// 前端应以 `code` 判断错误类型。
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub i18n_key: &'static str,
+    /// 装箱以保持 `Result` 的错误分支足够小
+    pub details: Box<ErrorDetails>,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            i18n_key: code.i18n_key(),
+            details: Box::default(),
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub fn io(message: impl Into<String>, path: impl AsRef<std::path::Path>) -> Self {
+        Self::new(ErrorCode::Io, message).with_path(path)
+    }
+
+    /// 锁中毒时的统一错误
+    pub fn lock() -> Self {
+        Self::internal(t("error.lockFailed"))
+    }
+
+    /// 在错误消息前追加上下文，保留错误码和附加信息
+    pub fn context(mut self, context: impl std::fmt::Display) -> Self {
+        self.message = format!("{}: {}", context, self.message);
+        self
+    }
+
+    pub fn with_program(mut self, program: impl Into<String>) -> Self {
+        self.details.program = Some(program.into());
+        self
+    }
+
+    pub fn with_path(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        self.details.path = Some(path.as_ref().to_string_lossy().to_string());
+        self
+    }
+
+    pub fn with_exit_code(mut self, exit_code: Option<i32>) -> Self {
+        self.details.exit_code = exit_code;
+        self
+    }
+
+    pub fn with_stderr(mut self, stderr: impl Into<String>) -> Self {
+        self.details.stderr = Some(stderr.into());
+        self
+    }
+
+    /// 后台任务中的错误没有调用方可以接收，通过 `app-error` 事件推送给前端展示
+    pub fn report(&self, app_handle: &tauri::AppHandle) {
+        use tauri::Emitter;
+        let _ = app_handle.emit("app-error", self);
+    }
+}
+
+impl std::fmt::Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<SpawnError> for AppError {
+    fn from(e: SpawnError) -> Self {
+        let message = e.to_string();
+        match e {
+            SpawnError::NotFound { program } => {
+                AppError::new(ErrorCode::ExecutableNotFound, message).with_program(program)
+            }
+            SpawnError::PermissionDenied { program } => {
+                AppError::new(ErrorCode::PermissionDenied, message).with_program(program)
+            }
+            SpawnError::NonZeroExit { program, code, stderr } => AppError::new(ErrorCode::NonZeroExit, message)
+                .with_program(program)
+                .with_exit_code(code)
+                .with_stderr(stderr),
+            SpawnError::Io { program, .. } => {
+                AppError::new(ErrorCode::ProcessFailed, message).with_program(program)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 全部错误码；新增变体时 `exhaustive` 中的 match 会提示同步更新此列表
+    const ALL: [ErrorCode; 17] = [
+        ErrorCode::ExecutableNotFound,
+        ErrorCode::PermissionDenied,
+        ErrorCode::NonZeroExit,
+        ErrorCode::ProcessFailed,
+        ErrorCode::TimedOut,
+        ErrorCode::CommandDenied,
+        ErrorCode::ChecksumMismatch,
+        ErrorCode::ChecksumMissing,
+        ErrorCode::AlreadyRunning,
+        ErrorCode::NotRunning,
+        ErrorCode::NotFound,
+        ErrorCode::InvalidArgument,
+        ErrorCode::InvalidConfig,
+        ErrorCode::Conflict,
+        ErrorCode::Unsupported,
+        ErrorCode::Io,
+        ErrorCode::Internal,
+    ];
+
+    #[allow(dead_code)]
+    fn exhaustive(code: ErrorCode) {
+        match code {
+            ErrorCode::ExecutableNotFound
+            | ErrorCode::PermissionDenied
+            | ErrorCode::NonZeroExit
+            | ErrorCode::ProcessFailed
+            | ErrorCode::TimedOut
+            | ErrorCode::CommandDenied
+            | ErrorCode::ChecksumMismatch
+            | ErrorCode::ChecksumMissing
+            | ErrorCode::AlreadyRunning
+            | ErrorCode::NotRunning
+            | ErrorCode::NotFound
+            | ErrorCode::InvalidArgument
+            | ErrorCode::InvalidConfig
+            | ErrorCode::Conflict
+            | ErrorCode::Unsupported
+            | ErrorCode::Io
+            | ErrorCode::Internal => {}
+        }
+    }
+
+    #[test]
+    fn every_error_code_has_a_catalog_entry() {
+        for code in ALL {
+            let key = code.i18n_key();
+            assert_ne!(t(key), key, "{} is missing from the i18n catalog", key);
+        }
+    }
+
+    #[test]
+    fn spawn_errors_keep_their_details() {
+        let error = AppError::from(SpawnError::NonZeroExit {
+            program: "npm".to_string(),
+            code: Some(2),
+            stderr: "boom".to_string(),
+        });
+        assert_eq!(error.code, ErrorCode::NonZeroExit);
+        assert_eq!(error.i18n_key, "error.nonZeroExit");
+        assert_eq!(error.details.program.as_deref(), Some("npm"));
+        assert_eq!(error.details.exit_code, Some(2));
+        assert_eq!(error.details.stderr.as_deref(), Some("boom"));
+    }
+}
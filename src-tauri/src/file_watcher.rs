@@ -0,0 +1,656 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::error::{AppError, AppResult};
+use crate::i18n::{t, tf};
+use crate::prompt_store::{modified_millis, PromptScope};
+
+/// 合并连续变更的时间窗口
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// 智能体说明文件的文件名
+pub const AGENT_FILE_NAMES: &[&str] = &["AGENTS.md", "AGENTS.override.md", "AGENT.md", "CLAUDE.md"];
+
+/// 用户目录下会被监听的配置文件
+pub const CONFIG_FILE_NAMES: &[&str] = &["config.toml", "config.json", "settings.json"];
+
+/// 被监听目录的用途，决定其中文件的分类方式
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RootKind {
+    /// `.codex/prompts`，递归监听
+    Prompts,
+    /// `.claude/commands`，递归监听
+    Commands,
+    /// `~/.codex`、`~/.claude` 或项目根目录，只监听其中的说明和配置文件
+    Base,
+}
+
+struct WatchRoot {
+    dir: PathBuf,
+    kind: RootKind,
+    scope: PromptScope,
+    project: Option<PathBuf>,
+    /// 目录不存在时无法监听，出现后再补上
+    watched: bool,
+}
+
+/// 变更类型
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+/// 变更来源：应用自身写入，或外部编辑器等
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeOrigin {
+    App,
+    External,
+}
+
+/// 变更的文件类别，对应推送给前端的事件名
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeTarget {
+    /// Codex 提示词，事件 `prompt-changed`
+    Prompt,
+    /// Claude Code 自定义命令，事件 `claude-command-changed`
+    ClaudeCommand,
+    /// AGENTS.md / CLAUDE.md 等说明文件，事件 `agent-file-changed`
+    AgentFile,
+    /// config.toml 等配置文件，事件 `config-changed`
+    Config,
+}
+
+impl ChangeTarget {
+    fn event_name(self) -> &'static str {
+        match self {
+            ChangeTarget::Prompt => "prompt-changed",
+            ChangeTarget::ClaudeCommand => "claude-command-changed",
+            ChangeTarget::AgentFile => "agent-file-changed",
+            ChangeTarget::Config => "config-changed",
+        }
+    }
+}
+
+/// 一次经过去抖和分类的文件变更
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    pub target: ChangeTarget,
+    pub change: ChangeKind,
+    pub origin: ChangeOrigin,
+    pub scope: PromptScope,
+    /// 提示词或命令的名称；命令子目录用 `:` 连接，例如 `frontend:review`
+    pub name: Option<String>,
+    pub path: PathBuf,
+    pub project_path: Option<PathBuf>,
+    /// 变更后的修改时间（Unix 毫秒），删除时为空
+    pub updated_at: Option<u64>,
+}
+
+/// 自身写入记录的保留时间；变更事件在去抖窗口后就会到达，过期的记录不再有用
+const OWN_WRITE_TTL: Duration = Duration::from_secs(10);
+
+/// 应用自身最近写入的文件及写入后的修改时间，用于区分变更来源
+///
+/// `write_atomic` 也用于不在监听范围内的文件（例如版本历史），这些记录不会被事件取走，
+/// 因此每次访问时清除过期记录，保证表的大小只取决于最近一段时间的写入。
+#[derive(Default)]
+struct OwnWrites {
+    entries: HashMap<PathBuf, (u64, Instant)>,
+}
+
+impl OwnWrites {
+    fn prune(&mut self, now: Instant) {
+        self.entries.retain(|_, (_, recorded)| now.saturating_duration_since(*recorded) < OWN_WRITE_TTL);
+    }
+
+    fn record(&mut self, path: &Path, modified: u64, now: Instant) {
+        self.prune(now);
+        self.entries.insert(path.to_path_buf(), (modified, now));
+    }
+
+    /// 判断变更是否来自应用自身，命中后移除记录
+    fn take(&mut self, path: &Path, updated_at: Option<u64>, now: Instant) -> ChangeOrigin {
+        self.prune(now);
+        match (self.entries.get(path).map(|(modified, _)| *modified), updated_at) {
+            (Some(recorded), Some(current)) if recorded == current => {
+                self.entries.remove(path);
+                ChangeOrigin::App
+            }
+            // 删除或重命名后源文件消失
+            (Some(_), None) => {
+                self.entries.remove(path);
+                ChangeOrigin::App
+            }
+            _ => ChangeOrigin::External,
+        }
+    }
+}
+
+static OWN_WRITES: Mutex<Option<OwnWrites>> = Mutex::new(None);
+
+/// 记录一次由应用发起的写入；`write_atomic` 写完后调用
+pub fn record_own_write(path: &Path) {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return;
+    };
+    if let Ok(mut writes) = OWN_WRITES.lock() {
+        writes.get_or_insert_with(OwnWrites::default).record(path, modified_millis(&meta), Instant::now());
+    }
+}
+
+fn take_origin(path: &Path, updated_at: Option<u64>) -> ChangeOrigin {
+    match OWN_WRITES.lock() {
+        Ok(mut writes) => writes.get_or_insert_with(OwnWrites::default).take(path, updated_at, Instant::now()),
+        Err(_) => ChangeOrigin::External,
+    }
+}
+
+struct WatcherInner {
+    debouncer: Debouncer<RecommendedWatcher>,
+    roots: Vec<WatchRoot>,
+    /// 已知文件及其修改时间，用于区分新增、修改和删除
+    known: HashMap<PathBuf, u64>,
+    /// 为尚不存在的目录额外监听的最近上级目录（非递归）
+    probes: HashSet<PathBuf>,
+}
+
+/// 文件监听状态
+#[derive(Default)]
+pub struct FileWatcherState {
+    inner: Mutex<Option<WatcherInner>>,
+}
+
+fn is_temp_file(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy())
+        .is_some_and(|name| name.starts_with('.') || name.ends_with('~') || name.ends_with(".tmp") || name.ends_with(".swp"))
+}
+
+impl WatchRoot {
+    fn new(dir: PathBuf, kind: RootKind, scope: PromptScope, project: Option<&Path>) -> Self {
+        Self { dir, kind, scope, project: project.map(Path::to_path_buf), watched: false }
+    }
+
+    fn mode(&self) -> RecursiveMode {
+        match self.kind {
+            RootKind::Base => RecursiveMode::NonRecursive,
+            RootKind::Prompts | RootKind::Commands => RecursiveMode::Recursive,
+        }
+    }
+
+    /// 判断路径属于哪类文件，返回类别和名称；无关文件返回 `None`
+    fn classify(&self, path: &Path) -> Option<(ChangeTarget, Option<String>)> {
+        let rel = path.strip_prefix(&self.dir).ok()?;
+        let file_name = rel.file_name()?.to_string_lossy().to_string();
+        let is_markdown = rel.extension().is_some_and(|ext| ext == "md");
+        match self.kind {
+            // Codex 只读取 prompts 目录第一层的文件
+            RootKind::Prompts if is_markdown && rel.components().count() == 1 => {
+                Some((ChangeTarget::Prompt, Some(file_name.trim_end_matches(".md").to_string())))
+            }
+            RootKind::Commands if is_markdown => {
+                let name = rel
+                    .with_extension("")
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join(":");
+                Some((ChangeTarget::ClaudeCommand, Some(name)))
+            }
+            RootKind::Base if rel.components().count() == 1 => {
+                if AGENT_FILE_NAMES.contains(&file_name.as_str()) {
+                    Some((ChangeTarget::AgentFile, None))
+                } else if self.project.is_none() && CONFIG_FILE_NAMES.contains(&file_name.as_str()) {
+                    Some((ChangeTarget::Config, None))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// 当前目录下所有会被分类的文件及修改时间
+    fn scan(&self, known: &mut HashMap<PathBuf, u64>) {
+        let mut pending = vec![self.dir.clone()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    if self.kind != RootKind::Base {
+                        pending.push(path);
+                    }
+                } else if !is_temp_file(&path) && self.classify(&path).is_some() {
+                    if let Ok(meta) = entry.metadata() {
+                        known.insert(path, modified_millis(&meta));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 用户目录和项目目录下需要监听的位置
+fn roots_for(home: Option<&Path>, project: Option<&Path>) -> Vec<WatchRoot> {
+    let mut roots = Vec::new();
+    if let Some(home) = home {
+        let codex = home.join(".codex");
+        let claude = home.join(".claude");
+        roots.push(WatchRoot::new(codex.join("prompts"), RootKind::Prompts, PromptScope::User, None));
+        roots.push(WatchRoot::new(claude.join("commands"), RootKind::Commands, PromptScope::User, None));
+        roots.push(WatchRoot::new(codex, RootKind::Base, PromptScope::User, None));
+        roots.push(WatchRoot::new(claude, RootKind::Base, PromptScope::User, None));
+    }
+    if let Some(project) = project {
+        let scope = PromptScope::Project;
+        roots.push(WatchRoot::new(project.join(".codex").join("prompts"), RootKind::Prompts, scope, Some(project)));
+        roots.push(WatchRoot::new(project.join(".claude").join("commands"), RootKind::Commands, scope, Some(project)));
+        roots.push(WatchRoot::new(project.to_path_buf(), RootKind::Base, scope, Some(project)));
+    }
+    roots
+}
+
+impl WatcherInner {
+    fn new(debouncer: Debouncer<RecommendedWatcher>) -> Self {
+        Self { debouncer, roots: Vec::new(), known: HashMap::new(), probes: HashSet::new() }
+    }
+
+    /// 开始监听一组目录；不存在的目录先记录下来，出现后由 `watch_pending` 补上
+    fn add_roots(&mut self, roots: Vec<WatchRoot>) -> Vec<AppError> {
+        for root in roots {
+            if !self.roots.iter().any(|r| r.dir == root.dir && r.kind == root.kind) {
+                self.roots.push(root);
+            }
+        }
+        self.watch_pending()
+    }
+
+    /// 为已经存在但尚未监听的目录（例如首次创建的 `.codex/prompts`）补上监听，
+    /// 返回无法监听的目录；它们保持未监听状态，下次有目录出现时重试
+    fn watch_pending(&mut self) -> Vec<AppError> {
+        let mut errors = Vec::new();
+        for root in self.roots.iter_mut().filter(|r| !r.watched && r.dir.is_dir()) {
+            match self.debouncer.watcher().watch(&root.dir, root.mode()) {
+                Ok(()) => {
+                    root.watched = true;
+                    root.scan(&mut self.known);
+                }
+                Err(e) => errors.push(
+                    AppError::io(tf("watcher.watchFailed", &[("path", &root.dir.display()), ("error", &e)]), &root.dir),
+                ),
+            }
+        }
+        errors.extend(self.update_probes());
+        errors
+    }
+
+    /// 监听每个尚不存在的目录最近的已存在上级目录
+    ///
+    /// `Base` 目录不递归监听，`.codex` 和 `.codex/prompts` 分两次创建时，
+    /// 只有监听了 `.codex` 才能收到 `prompts` 出现的事件。已被监听的目录和不再需要的上级目录不重复监听。
+    fn update_probes(&mut self) -> Vec<AppError> {
+        let wanted: HashSet<PathBuf> = {
+            let watched: HashSet<&Path> = self.roots.iter().filter(|r| r.watched).map(|r| r.dir.as_path()).collect();
+            self.roots
+                .iter()
+                .filter(|r| !r.watched)
+                .filter_map(|r| r.dir.ancestors().skip(1).find(|dir| dir.is_dir()))
+                .filter(|dir| !watched.contains(dir))
+                .map(Path::to_path_buf)
+                .collect()
+        };
+        for dir in self.probes.difference(&wanted) {
+            let _ = self.debouncer.watcher().unwatch(dir);
+        }
+        let mut errors = Vec::new();
+        let mut probes = HashSet::new();
+        for dir in wanted {
+            if self.probes.contains(&dir) {
+                probes.insert(dir);
+                continue;
+            }
+            match self.debouncer.watcher().watch(&dir, RecursiveMode::NonRecursive) {
+                Ok(()) => {
+                    probes.insert(dir);
+                }
+                Err(e) => errors.push(AppError::io(tf("watcher.watchFailed", &[("path", &dir.display()), ("error", &e)]), &dir)),
+            }
+        }
+        self.probes = probes;
+        errors
+    }
+
+    fn remove_project(&mut self, project: &Path) {
+        let (removed, kept): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.roots).into_iter().partition(|r| r.project.as_deref() == Some(project));
+        self.roots = kept;
+        for root in removed.iter().filter(|r| r.watched) {
+            let _ = self.debouncer.watcher().unwatch(&root.dir);
+        }
+        self.known.retain(|path, _| !removed.iter().any(|root| root.classify(path).is_some()));
+        // 只为被移除项目监听的上级目录不再需要
+        let _ = self.update_probes();
+    }
+
+    /// 将一批去抖后的路径转换为分类后的变更，并更新已知文件表
+    ///
+    /// 同时返回为新出现的目录补上监听时遇到的错误。
+    fn classify_paths(&mut self, paths: Vec<PathBuf>) -> (Vec<FileChange>, Vec<AppError>) {
+        let mut changes = Vec::new();
+        let mut new_dir = false;
+        for path in paths {
+            // 先判断目录：`.codex` 这样以 `.` 开头的目录不是临时文件
+            if path.is_dir() {
+                new_dir = true;
+                continue;
+            }
+            if is_temp_file(&path) {
+                continue;
+            }
+            // 同一文件可能同时落在用户目录和项目目录下，取最具体的一个
+            let Some((root, (target, name))) = self
+                .roots
+                .iter()
+                .filter_map(|root| root.classify(&path).map(|c| (root, c)))
+                .max_by_key(|(root, _)| root.dir.components().count())
+            else {
+                continue;
+            };
+
+            let updated_at = std::fs::metadata(&path).ok().map(|meta| modified_millis(&meta));
+            let change = match (self.known.get(&path).copied(), updated_at) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(before), Some(after)) if before != after => ChangeKind::Modified,
+                (Some(_), None) => ChangeKind::Removed,
+                _ => continue,
+            };
+            match updated_at {
+                Some(t) => self.known.insert(path.clone(), t),
+                None => self.known.remove(&path),
+            };
+
+            changes.push(FileChange {
+                target,
+                change,
+                origin: take_origin(&path, updated_at),
+                scope: root.scope,
+                name,
+                project_path: root.project.clone(),
+                path,
+                updated_at,
+            });
+        }
+        let errors = if new_dir { self.watch_pending() } else { Vec::new() };
+        (changes, errors)
+    }
+}
+
+fn emit_changes(app_handle: &AppHandle, changes: &[FileChange]) {
+    for change in changes {
+        let _ = app_handle.emit(change.target.event_name(), change);
+    }
+}
+
+/// 启动监听 `~/.codex` 和 `~/.claude`；在 setup 中调用
+///
+/// 个别目录无法监听时通过 `app-error` 事件提示，不影响其他目录。
+pub fn start(app_handle: &AppHandle) -> AppResult<()> {
+    let handle = app_handle.clone();
+    let debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+        let Ok(events) = result else {
+            return;
+        };
+        let state = handle.state::<FileWatcherState>();
+        let (changes, errors) = match state.inner.lock() {
+            Ok(mut inner) => match inner.as_mut() {
+                Some(inner) => inner.classify_paths(events.into_iter().map(|e| e.path).collect()),
+                None => return,
+            },
+            Err(_) => return,
+        };
+        for error in &errors {
+            error.report(&handle);
+        }
+        crate::prompt_history::record_changes(&handle, &changes);
+        crate::search_index::apply_changes(&handle, &changes);
+        emit_changes(&handle, &changes);
+    })
+    .map_err(|e| AppError::internal(tf("watcher.startFailed", &[("error", &e)])))?;
+
+    let home = app_handle.path().home_dir().ok();
+    let mut inner = WatcherInner::new(debouncer);
+    for error in inner.add_roots(roots_for(home.as_deref(), None)) {
+        error.report(app_handle);
+    }
+
+    let state = app_handle.state::<FileWatcherState>();
+    *state.inner.lock().map_err(|_| AppError::lock())? = Some(inner);
+    Ok(())
+}
+
+/// Tauri 命令：开始监听项目目录下的 AGENTS.md、CLAUDE.md、`.codex/prompts` 和 `.claude/commands`
+///
+/// 有目录无法监听时返回第一个错误，其余目录仍会被监听。
+#[tauri::command(rename_all = "camelCase")]
+pub async fn watch_project(app_handle: AppHandle, project_path: String) -> AppResult<()> {
+    let errors = {
+        let state = app_handle.state::<FileWatcherState>();
+        let mut inner = state.inner.lock().map_err(|_| AppError::lock())?;
+        let inner = inner.as_mut().ok_or_else(|| AppError::internal(t("watcher.notStarted")))?;
+        inner.add_roots(roots_for(None, Some(Path::new(&project_path))))
+    };
+    crate::search_index::add_project(&app_handle, Path::new(&project_path));
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Tauri 命令：停止监听项目目录
+#[tauri::command(rename_all = "camelCase")]
+pub async fn unwatch_project(app_handle: AppHandle, project_path: String) -> AppResult<()> {
+    let state = app_handle.state::<FileWatcherState>();
+    let mut inner = state.inner.lock().map_err(|_| AppError::lock())?;
+    if let Some(inner) = inner.as_mut() {
+        inner.remove_project(Path::new(&project_path));
+    }
+    drop(inner);
+    crate::search_index::remove_project(&app_handle, Path::new(&project_path));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn watcher() -> WatcherInner {
+        WatcherInner::new(new_debouncer(DEBOUNCE, |_: DebounceEventResult| {}).unwrap())
+    }
+
+    fn set_modified(path: &Path, time: SystemTime) {
+        std::fs::File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn temp_files_are_ignored() {
+        assert!(is_temp_file(Path::new("/p/.review.md.swp")));
+        assert!(is_temp_file(Path::new("/p/review.md~")));
+        assert!(is_temp_file(Path::new("/p/review.md.tmp")));
+        assert!(!is_temp_file(Path::new("/p/review.md")));
+    }
+
+    #[test]
+    fn roots_cover_home_and_project() {
+        let roots = roots_for(Some(Path::new("/home/u")), Some(Path::new("/work/app")));
+        let dirs: Vec<_> = roots.iter().map(|r| r.dir.clone()).collect();
+        assert!(dirs.contains(&PathBuf::from("/home/u/.codex/prompts")));
+        assert!(dirs.contains(&PathBuf::from("/home/u/.claude/commands")));
+        assert!(dirs.contains(&PathBuf::from("/work/app/.codex/prompts")));
+        assert!(dirs.contains(&PathBuf::from("/work/app")));
+        assert!(roots.iter().filter(|r| r.project.is_some()).all(|r| r.scope == PromptScope::Project));
+        assert!(roots_for(None, None).is_empty());
+    }
+
+    #[test]
+    fn classify_by_root_kind() {
+        let roots = roots_for(Some(Path::new("/home/u")), Some(Path::new("/work/app")));
+        let classify = |path: &str| roots.iter().find_map(|r| r.classify(Path::new(path)));
+
+        let (target, name) = classify("/home/u/.codex/prompts/review.md").unwrap();
+        assert!(target == ChangeTarget::Prompt);
+        assert_eq!(name.as_deref(), Some("review"));
+        // Codex 不读取 prompts 子目录
+        assert!(classify("/home/u/.codex/prompts/sub/review.md").is_none());
+
+        let (target, name) = classify("/home/u/.claude/commands/frontend/review.md").unwrap();
+        assert!(target == ChangeTarget::ClaudeCommand);
+        assert_eq!(name.as_deref(), Some("frontend:review"));
+
+        assert!(classify("/home/u/.codex/config.toml").is_some_and(|(t, _)| t == ChangeTarget::Config));
+        assert!(classify("/work/app/AGENTS.md").is_some_and(|(t, _)| t == ChangeTarget::AgentFile));
+        // 项目根目录的配置文件和其他文件不属于监听范围
+        assert!(classify("/work/app/config.toml").is_none());
+        assert!(classify("/work/app/src/main.rs").is_none());
+    }
+
+    #[test]
+    fn own_writes_are_matched_once() {
+        let now = Instant::now();
+        let path = Path::new("/p/review.md");
+        let mut writes = OwnWrites::default();
+        writes.record(path, 100, now);
+
+        assert!(writes.take(path, Some(200), now) == ChangeOrigin::External);
+        assert!(writes.take(path, Some(100), now) == ChangeOrigin::App);
+        assert!(writes.take(path, Some(100), now) == ChangeOrigin::External);
+
+        writes.record(path, 100, now);
+        assert!(writes.take(path, None, now) == ChangeOrigin::App);
+    }
+
+    #[test]
+    fn own_writes_expire() {
+        let now = Instant::now();
+        let mut writes = OwnWrites::default();
+        for i in 0..5 {
+            writes.record(&PathBuf::from(format!("/history/{i}.md")), 1, now);
+        }
+        let later = now + OWN_WRITE_TTL;
+        writes.record(Path::new("/p/review.md"), 1, later);
+        assert_eq!(writes.entries.len(), 1);
+        assert!(writes.take(Path::new("/history/0.md"), Some(1), later) == ChangeOrigin::External);
+    }
+
+    #[test]
+    fn classify_paths_tracks_change_kinds() {
+        let project = tempfile::tempdir().unwrap();
+        let prompts = project.path().join(".codex").join("prompts");
+        std::fs::create_dir_all(&prompts).unwrap();
+        let existing = prompts.join("existing.md");
+        std::fs::write(&existing, "old").unwrap();
+
+        let mut inner = watcher();
+        assert!(inner.add_roots(roots_for(None, Some(project.path()))).is_empty());
+
+        let added = prompts.join("new.md");
+        std::fs::write(&added, "new").unwrap();
+        set_modified(&existing, SystemTime::now() + Duration::from_secs(5));
+        let (changes, errors) = inner.classify_paths(vec![added.clone(), existing.clone()]);
+        assert!(errors.is_empty());
+        assert_eq!(changes.len(), 2);
+        assert!(changes[0].change == ChangeKind::Added && changes[0].name.as_deref() == Some("new"));
+        assert!(changes[1].change == ChangeKind::Modified);
+        assert!(changes.iter().all(|c| c.scope == PromptScope::Project && c.origin == ChangeOrigin::External));
+
+        // 修改时间未变的重复事件不再报告
+        assert!(inner.classify_paths(vec![added.clone()]).0.is_empty());
+
+        std::fs::remove_file(&added).unwrap();
+        let (changes, _) = inner.classify_paths(vec![added, project.path().join("notes.txt")]);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].change == ChangeKind::Removed);
+    }
+
+    #[test]
+    fn own_write_is_reported_as_app_origin() {
+        let project = tempfile::tempdir().unwrap();
+        let mut inner = watcher();
+        inner.add_roots(roots_for(None, Some(project.path())));
+
+        let agents = project.path().join("AGENTS.md");
+        std::fs::write(&agents, "rules").unwrap();
+        record_own_write(&agents);
+        let (changes, _) = inner.classify_paths(vec![agents]);
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].target == ChangeTarget::AgentFile && changes[0].origin == ChangeOrigin::App);
+    }
+
+    #[test]
+    fn removed_projects_are_forgotten() {
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join("AGENTS.md"), "rules").unwrap();
+        let mut inner = watcher();
+        inner.add_roots(roots_for(None, Some(project.path())));
+        assert_eq!(inner.known.len(), 1);
+
+        inner.remove_project(project.path());
+        assert!(inner.roots.is_empty());
+        assert!(inner.known.is_empty());
+    }
+
+    #[test]
+    fn directories_appearing_later_are_watched() {
+        let project = tempfile::tempdir().unwrap();
+        let mut inner = watcher();
+        inner.add_roots(roots_for(None, Some(project.path())));
+        let prompts = project.path().join(".codex").join("prompts");
+        assert!(inner.roots.iter().any(|r| r.dir == prompts && !r.watched));
+
+        std::fs::create_dir_all(&prompts).unwrap();
+        let (_, errors) = inner.classify_paths(vec![prompts.clone()]);
+        assert!(errors.is_empty());
+        assert!(inner.roots.iter().any(|r| r.dir == prompts && r.watched));
+    }
+
+    #[test]
+    fn nested_directories_created_in_separate_steps_are_watched() {
+        let project = tempfile::tempdir().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+            if let Ok(events) = result {
+                let _ = tx.send(events.into_iter().map(|e| e.path).collect::<Vec<_>>());
+            }
+        })
+        .unwrap();
+        let mut inner = WatcherInner::new(debouncer);
+        inner.add_roots(roots_for(None, Some(project.path())));
+        let codex = project.path().join(".codex");
+        let prompts = codex.join("prompts");
+
+        // 先创建 `.codex`，处理完这一批事件后再创建 `prompts`；第二次创建只能通过监听 `.codex` 发现
+        for dir in [&codex, &prompts] {
+            std::fs::create_dir(dir).unwrap();
+            let paths = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert!(paths.contains(dir));
+            let (_, errors) = inner.classify_paths(paths);
+            assert!(errors.is_empty());
+        }
+        assert!(inner.roots.iter().any(|r| r.dir == prompts && r.watched));
+        assert!(!inner.probes.contains(&codex));
+    }
+}
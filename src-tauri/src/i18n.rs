@@ -0,0 +1,403 @@
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+
+/// 后端支持的界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Locale {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    /// 按语言前缀匹配 BCP 47 标签，例如 `zh-Hans-CN`、`en_GB.UTF-8`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let lang = tag
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match lang.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+}
+
+/// 当前语言；在 `init` 之前默认为中文，与应用原有文案保持一致
+static CURRENT: RwLock<Locale> = RwLock::new(Locale::ZhCn);
+
+/// 文案目录：(键, zh-CN, en-US)
+///
+/// 占位符写作 `{name}`，由 `tf` 替换。
+const CATALOG: &[(&str, &str, &str)] = &[
+    // 托盘菜单
+    ("tray.show", "显示窗口", "Show Window"),
+    ("tray.hide", "隐藏窗口", "Hide Window"),
+    ("tray.quit", "退出", "Quit"),
+    // 对话框
+    ("dialog.selectFolder.title", "选择项目文件夹", "Select Project Folder"),
+    ("dialog.confirmCommand.title", "确认执行命令", "Confirm Command"),
+    (
+        "dialog.confirmCommand.message",
+        "应用请求执行一个不在允许列表中的命令：\n\n{command}\n\n仅在你确认该操作来自自己时才允许执行。",
+        "The app is requesting to run a command that is not on the allow-list:\n\n{command}\n\nOnly allow it if you initiated this action yourself.",
+    ),
+    ("dialog.confirmCommand.cwd", "工作目录：{cwd}", "Working directory: {cwd}"),
+    ("dialog.confirmCommand.env", "环境变量：\n{env}", "Environment variables:\n{env}"),
+    ("dialog.confirmCommand.run", "执行", "Run"),
+    ("dialog.confirmCommand.cancel", "取消", "Cancel"),
+    // 命令结果
+    ("mcp.started", "MCP 服务已启动并托管 (隐藏控制台)", "MCP service started in the background (console hidden)"),
+    ("mcp.stopped", "MCP 服务已停止", "MCP service stopped"),
+    ("session.focused", "已切换到会话窗口", "Switched to the session window"),
+    ("session.stopped", "会话已停止", "Session stopped"),
+    ("command.cancelled", "命令已取消", "Command cancelled"),
+    ("terminal.opened", "已打开终端并执行 {command}", "Opened a terminal and ran {command}"),
+    // 错误
+    ("error.lockFailed", "内部状态锁定失败", "Failed to lock internal state"),
+    ("error.resolveDir", "无法解析目录: {error}", "Failed to resolve directory: {error}"),
+    ("error.receiveFolder", "接收文件夹路径失败: {error}", "Failed to receive folder path: {error}"),
+    ("error.executableNotFound", "找不到可执行文件: {program}", "Executable not found: {program}"),
+    ("error.permissionDenied", "没有执行权限: {program}", "Permission denied: {program}"),
+    ("error.exitCode", "{program} 退出码: {code}", "{program} exited with code: {code}"),
+    ("error.processFailed", "执行 {program} 失败: {error}", "Failed to run {program}: {error}"),
+    ("error.nonZeroExit", "进程以非零退出码结束", "The process exited with a non-zero code"),
+    ("error.alreadyRunning", "服务已在运行", "The service is already running"),
+    ("error.notRunning", "服务或会话未运行", "The service or session is not running"),
+    ("error.notFound", "请求的资源不存在", "The requested resource was not found"),
+    ("error.invalidArgument", "参数不合法", "Invalid argument"),
+    ("error.invalidConfig", "配置文件格式错误", "Invalid configuration file"),
+    ("error.unsupported", "当前平台不支持该操作", "This operation is not supported on this platform"),
+    ("error.io", "文件读写失败", "File read or write failed"),
+    ("error.internal", "内部错误", "Internal error"),
+    ("error.openCodex", "在 Codex 中打开文件夹失败", "Failed to open folder in Codex"),
+    ("error.sessionNotFound", "会话不存在: {id}", "Session not found: {id}"),
+    ("error.sessionEnded", "会话已结束", "Session has already ended"),
+    ("error.focusFailed", "切换窗口失败", "Failed to switch window"),
+    (
+        "error.focusUnsupported",
+        "当前平台的会话以托管方式运行，没有可前置的窗口",
+        "Sessions on this platform run in the background and have no window to focus",
+    ),
+    ("error.jobNotFound", "任务不存在或已结束: {id}", "Job not found or already finished: {id}"),
+    ("error.missingParam", "缺少参数: {param}", "Missing parameter: {param}"),
+    ("error.paramNotAllowed", "参数 {param} 的值不在允许列表中: {value}", "Value for parameter {param} is not allowed: {value}"),
+    ("error.readPolicy", "读取命令策略失败: {error}", "Failed to read command policy: {error}"),
+    ("error.invalidPolicy", "命令策略格式错误: {error}", "Invalid command policy: {error}"),
+    ("error.commandDenied", "用户拒绝执行命令: {program}", "Command rejected by user: {program}"),
+    ("error.unknownCommand", "未声明的命令: {name}", "Undeclared command: {name}"),
+    ("error.mcpAlreadyRunning", "MCP 服务已在运行", "MCP service is already running"),
+    ("error.mcpNotRunning", "MCP 服务未运行", "MCP service is not running"),
+    ("error.resolveExecutable", "获取可执行文件路径失败", "Failed to resolve executable path"),
+    ("error.startProcess", "启动进程失败", "Failed to start process"),
+    ("error.openTerminal", "打开终端失败", "Failed to open terminal"),
+    ("error.noTerminal", "找不到可用的终端模拟器", "No supported terminal emulator was found"),
+    ("error.executeTool", "执行 {program} 失败", "Failed to run {program}"),
+    ("error.toolNotFound", "清单中没有该工具: {name}", "Tool is not declared in the manifest: {name}"),
+    ("error.readFile", "读取文件失败: {error}", "Failed to read file: {error}"),
+    (
+        "error.checksumMismatch",
+        "{program} 的校验和与清单不一致，已拒绝执行",
+        "Checksum of {program} does not match the manifest; refusing to run it",
+    ),
+    (
+        "error.checksumMissing",
+        "清单中没有 {program} 在 {platform} 上的校验和，已拒绝执行",
+        "No checksum for {program} on {platform} in the manifest; refusing to run it",
+    ),
+    (
+        "warning.checksumMismatch",
+        "{program} 的校验和与清单不一致，清单设置为仅警告，仍然执行",
+        "Checksum of {program} does not match the manifest; running it because the manifest only warns",
+    ),
+    (
+        "warning.checksumMissing",
+        "清单中没有 {program} 在 {platform} 上的校验和，跳过校验",
+        "No checksum for {program} on {platform} in the manifest; skipping verification",
+    ),
+    ("error.manifestMissing", "找不到内置工具清单 {path}", "Bundled tool manifest not found: {path}"),
+    ("error.invalidManifest", "内置工具清单格式错误: {error}", "Invalid bundled tool manifest: {error}"),
+    ("error.notExecutable", "无法使 {path} 可执行: {error}", "Unable to make {path} executable: {error}"),
+    ("resource.envNotSet", "未设置环境变量 {name}", "Environment variable {name} is not set"),
+    ("resource.releaseBuild", "release 构建不包含源码资源目录", "Release builds do not record the source resource directory"),
+    ("resource.dirMissing", "目录不存在: {dir}", "Directory does not exist: {dir}"),
+    ("resource.fileMissing", "文件不存在", "File does not exist"),
+    ("resource.notAFile", "路径存在但不是文件", "Path exists but is not a file"),
+    ("error.timedOut", "{program} 执行超时，已结束进程", "{program} timed out and was terminated"),
+    ("error.invalidToolName", "无效的工具名或版本号: {name}", "Invalid tool name or version: {name}"),
+    ("error.download", "下载 {url} 失败: {error}", "Failed to download {url}: {error}"),
+    ("error.toolNotInIndex", "工具索引中没有该工具: {name}", "Tool is not in the index: {name}"),
+    ("error.downloadChecksum", "下载的 {name} 校验和与索引不一致", "Checksum of the downloaded {name} does not match the index"),
+    ("error.unsupportedUrl", "不支持的地址，只能使用 https: {url}", "Unsupported URL; only https is allowed: {url}"),
+    ("error.noPublisherKey", "此构建未配置工具发布者公钥，无法安装工具", "This build has no tool publisher key configured; tools cannot be installed"),
+    ("error.invalidPublisherKey", "工具发布者公钥格式错误", "The tool publisher key is malformed"),
+    ("error.indexSignature", "工具索引签名校验失败", "The tool index signature is invalid"),
+    ("error.invalidIndex", "工具索引格式错误: {error}", "Invalid tool index: {error}"),
+    ("error.noIndexUrl", "未配置工具索引地址", "No tool index URL is configured"),
+    ("error.unpack", "解压失败: {error}", "Failed to unpack archive: {error}"),
+    ("error.unsupportedArchive", "不支持的压缩格式: {format}", "Unsupported archive format: {format}"),
+    ("error.versionNotFound", "索引中没有 {name} 的版本 {version}", "Version {version} of {name} is not in the index"),
+    ("error.versionNotInstalled", "{name} 的版本 {version} 未安装", "Version {version} of {name} is not installed"),
+    ("error.noArtifact", "{name} {version} 没有适用于当前平台的安装包", "{name} {version} has no package for this platform"),
+    ("error.writeFile", "写入文件失败: {error}", "Failed to write file: {error}"),
+    (
+        "error.invalidPromptName",
+        "无效的提示词名称: {name}（只能包含字母、数字、-、_ 和 .，且不能以 . 开头）",
+        "Invalid prompt name: {name} (only letters, digits, -, _ and . are allowed, and it must not start with .)",
+    ),
+    ("error.promptNotFound", "提示词不存在: {name}", "Prompt not found: {name}"),
+    ("error.promptExists", "同名提示词已存在: {name}", "A prompt with this name already exists: {name}"),
+    ("error.invalidFrontmatter", "frontmatter 格式错误: {error}", "Invalid frontmatter: {error}"),
+    (
+        "error.conflict",
+        "{path} 在编辑期间已被其他程序修改，请重新加载后再保存",
+        "{path} was modified by another program while editing; reload it before saving",
+    ),
+    ("watcher.startFailed", "启动文件监听失败: {error}", "Failed to start file watcher: {error}"),
+    ("watcher.watchFailed", "无法监听 {path}: {error}", "Unable to watch {path}: {error}"),
+    ("watcher.notStarted", "文件监听未启动", "The file watcher is not running"),
+    ("error.notAgentFile", "{name} 不是 AGENTS.md、CLAUDE.md 等说明文件", "{name} is not an agent instruction file such as AGENTS.md or CLAUDE.md"),
+    ("error.notConfigFile", "{name} 不是 config.toml 等配置文件", "{name} is not a configuration file such as config.toml"),
+    ("error.historyVersionNotFound", "找不到历史版本 {version}", "History version {version} not found"),
+    ("history.restoredFrom", "恢复到版本 {version}", "Restored from version {version}"),
+    ("history.recordFailed", "无法记录 {path} 的历史版本", "Failed to record history for {path}"),
+    ("template.missingArgument", "缺少必填参数 {name}", "Missing required argument {name}"),
+    ("template.invalidNumber", "参数 {name} 需要数字，实际为 \"{value}\"", "Argument {name} must be a number, got \"{value}\""),
+    (
+        "template.invalidBoolean",
+        "参数 {name} 需要布尔值（true/false），实际为 \"{value}\"",
+        "Argument {name} must be a boolean (true/false), got \"{value}\"",
+    ),
+    ("template.unknownArgument", "提示词没有声明参数 {name}", "The prompt does not declare an argument named {name}"),
+    ("template.undefinedPlaceholder", "占位符 {placeholder} 没有对应的参数", "Placeholder {placeholder} has no matching argument"),
+    ("template.unclosedPlaceholder", "占位符缺少结尾的 }}", "Placeholder is missing its closing }}"),
+    (
+        "error.invalidCommandName",
+        "无效的命令名称: {name}（每一段只能包含字母、数字、-、_ 和 .，命名空间用 : 分隔）",
+        "Invalid command name: {name} (each segment may only contain letters, digits, -, _ and ., with : separating namespaces)",
+    ),
+    ("error.commandNotFound", "命令不存在: {name}", "Command not found: {name}"),
+    ("error.commandExists", "同名命令已存在: {name}", "A command with this name already exists: {name}"),
+    ("convert.argumentType", "Claude 命令的参数没有类型，参数 {name} 按字符串处理", "Claude command arguments are untyped; {name} is treated as a string"),
+    ("convert.argumentDefault", "Claude 命令不支持默认值，参数 {name} 的默认值 \"{value}\" 被丢弃", "Claude commands have no default values; the default \"{value}\" of {name} is dropped"),
+    ("convert.argumentDescription", "参数 {name} 的说明只保留名称到 argument-hint 中", "Only the name of argument {name} is kept in argument-hint"),
+    ("convert.unmatchedPlaceholder", "正文中有未声明的 {{name}} 占位符，已原样保留", "The content has {{name}} placeholders without a declared argument; they are kept as is"),
+    ("convert.model", "Codex 提示词不能指定模型，model: {model} 被丢弃", "Codex prompts cannot select a model; model: {model} is dropped"),
+    ("convert.shellLine", "Codex 不会执行以 ! 开头的 shell 行，这些行会作为普通文本保留", "Codex does not run !-prefixed shell lines; they are kept as plain text"),
+    ("convert.literalDollar", "Claude 命令没有转义写法，$$ 改为 $ 后紧跟的数字或 ARGUMENTS 会被当作占位符展开", "Claude commands have no escape for $; after $$ becomes $, a following digit or ARGUMENTS is expanded as a placeholder"),
+    ("convert.argumentsExpanded", "$ARGUMENTS 改写为按顺序以空格连接的各个参数", "$ARGUMENTS is rewritten as all arguments joined by spaces"),
+    ("convert.unmatchedPositional", "正文中有没有对应参数的 $N 或 $ARGUMENTS 占位符，已原样保留", "The content has $N or $ARGUMENTS placeholders without a matching argument; they are kept as is"),
+    ("convert.overwritten", "目标文件中已有的 {field} 被覆盖，原值：{value}", "The existing {field} of the target is overwritten; it was: {value}"),
+    ("convert.argumentRemoved", "目标提示词中的参数 {name} 在转换结果中不存在，将被删除", "Argument {name} of the target prompt is not in the conversion and is removed"),
+    ("convert.argumentHint", "参数由 argument-hint 推断，类型均为字符串", "Arguments are inferred from argument-hint and typed as strings"),
+    ("error.invalidPack", "无效的提示词包: {error}", "Invalid prompt pack: {error}"),
+    ("error.packVersion", "不支持的提示词包格式: {version}", "Unsupported prompt pack format: {version}"),
+    ("error.packChecksum", "提示词包中的 {path} 校验和不匹配，文件可能已损坏或被篡改", "Checksum mismatch for {path} in the prompt pack; it may be corrupted or tampered with"),
+    ("error.packEntryTooLarge", "提示词包中的 {path} 过大", "{path} in the prompt pack is too large"),
+    ("error.packSignatureInvalid", "提示词包签名无效，请确认公钥是否正确", "The prompt pack signature is invalid; check that the public key is correct"),
+    ("error.invalidPackKey", "签名密钥格式错误，应为 64 位十六进制字符", "The signing key is malformed; expected 64 hexadecimal characters"),
+    ("error.generateKey", "无法生成密钥: {error}", "Failed to generate a key: {error}"),
+    ("error.packSignatureRequired", "只允许导入已签名并通过验证的提示词包", "Only signed and verified prompt packs can be imported"),
+    ("error.mcpServerNotFound", "config.toml 中没有 MCP 服务器 {name}", "MCP server {name} not found in config.toml"),
+    ("error.invalidMcpConfig", "config.toml 格式错误: {error}", "Invalid config.toml: {error}"),
+    ("pack.importMessage", "从提示词包 {name} {version} 导入", "Imported from prompt pack {name} {version}"),
+    ("error.git", "Git 操作失败: {error}", "Git operation failed: {error}"),
+    ("error.gitAuth", "{url} 认证失败，请检查 ssh-agent 或 git 凭据助手中的凭据", "Authentication to {url} failed; check the credentials in ssh-agent or the git credential helper"),
+    ("error.syncNotConfigured", "尚未配置同步仓库目录", "Sync repository directory is not configured"),
+    ("error.syncPushRejected", "远程仓库拒绝推送，请重新同步: {error}", "Push rejected by remote, sync again: {error}"),
+    ("sync.commitMessage", "同步本地提示词", "Sync local prompts"),
+    ("sync.mergeMessage", "合并远程分支 {branch}", "Merge remote branch {branch}"),
+    ("sync.historyMessage", "从 git 仓库同步", "Synced from git repository"),
+    ("error.lintFixUnavailable", "没有可以自动应用的修复: {rule}", "No automatic fix is available for {rule}"),
+    ("lint.invalidName", "名称 {name} 不能作为文件名", "{name} is not a valid file name"),
+    ("lint.builtinCollision", "名称与内置命令 /{name} 相同，调用时会执行内置命令", "The name matches the built-in /{name} command, which takes precedence"),
+    ("lint.duplicateName", "名称 {name} 与 {path} 重复", "The name {name} duplicates {path}"),
+    ("lint.unclosedFrontmatter", "frontmatter 缺少结尾的 ---，整段会被当作正文", "Frontmatter is missing its closing ---, so it is treated as content"),
+    ("lint.invalidFrontmatter", "frontmatter 解析失败: {error}", "Failed to parse frontmatter: {error}"),
+    ("lint.missingDescription", "没有填写描述", "No description is set"),
+    ("lint.duplicateArgument", "参数 {name} 被重复声明", "Argument {name} is declared more than once"),
+    ("lint.invalidDefault", "参数 {name} 的默认值 \"{value}\" 不是合法的 {type}", "The default \"{value}\" of argument {name} is not a valid {type}"),
+    ("lint.unusedArgument", "参数 {name} 没有在正文中使用", "Argument {name} is not used in the content"),
+    ("lint.commandPlaceholder", "Claude 命令不会展开 {placeholder}，请改用 $1 或 $ARGUMENTS", "Claude commands do not expand {placeholder}; use $1 or $ARGUMENTS instead"),
+    ("lint.emptyContent", "正文为空", "The content is empty"),
+    ("lint.oversizedPrompt", "正文约 {size} KB，超过建议的 {limit} KB，考虑拆分", "The content is about {size} KB, over the recommended {limit} KB; consider splitting it"),
+    ("lint.unreadableFile", "无法读取文件，已跳过检查: {error}", "The file could not be read and was not checked: {error}"),
+    ("lint.fix.rename", "重命名为 {name}", "Rename to {name}"),
+    ("lint.fix.quoteValues", "重新转义 frontmatter 中的引号", "Re-escape quotes in the frontmatter"),
+    ("lint.fix.removeDuplicate", "删除重复的参数 {name}", "Remove the duplicate argument {name}"),
+    ("lint.fix.removeDefault", "删除参数 {name} 的默认值", "Remove the default of argument {name}"),
+    ("lint.fix.removeArgument", "删除参数 {name}", "Remove argument {name}"),
+    ("lint.fix.declareArgument", "声明参数 {name}", "Declare argument {name}"),
+    ("lint.fixMessage", "自动修复 {rule}", "Automatic fix for {rule}"),
+    ("error.createAuditDir", "创建审计日志目录失败: {error}", "Failed to create audit log directory: {error}"),
+    ("error.serializeAudit", "序列化审计记录失败: {error}", "Failed to serialize audit record: {error}"),
+    ("error.writeAudit", "写入审计日志失败: {error}", "Failed to write audit log: {error}"),
+    ("error.toolNameNotPlain", "工具名不合法，只能是清单中的名称或单个文件名: {name}", "Invalid tool name; use a manifest name or a single file name: {name}"),
+    ("error.unsupportedLocale", "不支持的语言: {locale}", "Unsupported locale: {locale}"),
+    ("error.saveSettings", "保存设置失败: {error}", "Failed to save settings: {error}"),
+];
+
+pub fn current() -> Locale {
+    CURRENT.read().map(|l| *l).unwrap_or(Locale::ZhCn)
+}
+
+fn set_current(locale: Locale) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = locale;
+    }
+}
+
+fn lookup(key: &str, locale: Locale) -> Option<&'static str> {
+    CATALOG.iter().find(|(k, _, _)| *k == key).map(|(_, zh, en)| match locale {
+        Locale::ZhCn => *zh,
+        Locale::EnUs => *en,
+    })
+}
+
+fn format_with(template: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// 按当前语言查找文案，缺失时返回键本身
+pub fn t(key: &str) -> String {
+    lookup(key, current()).unwrap_or(key).to_string()
+}
+
+/// 查找文案并替换 `{name}` 形式的占位符
+pub fn tf(key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    format_with(&t(key), args)
+}
+
+/// 持久化的应用设置，保存在 `<app_config>/settings.json`
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    #[serde(default)]
+    locale: Option<Locale>,
+}
+
+fn settings_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join("settings.json"))
+        .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))
+}
+
+fn load_settings(app_handle: &AppHandle) -> Settings {
+    settings_path(app_handle)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(app_handle: &AppHandle, settings: &Settings) -> AppResult<()> {
+    let path = settings_path(app_handle)?;
+    let io_error = |e: &dyn std::fmt::Display| AppError::io(tf("error.saveSettings", &[("error", e)]), &path);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| io_error(&e))?;
+    }
+    let content = serde_json::to_string_pretty(settings).map_err(|e| io_error(&e))?;
+    std::fs::write(&path, content).map_err(|e| io_error(&e))
+}
+
+/// 启动时确定语言：优先使用已保存的设置，其次是系统语言，最后回退到中文
+pub fn init(app_handle: &AppHandle) {
+    let locale = load_settings(app_handle)
+        .locale
+        .or_else(|| sys_locale::get_locale().as_deref().and_then(Locale::from_tag))
+        .unwrap_or(Locale::ZhCn);
+    set_current(locale);
+}
+
+/// 切换语言并写入设置文件
+pub fn change_locale(app_handle: &AppHandle, tag: &str) -> AppResult<Locale> {
+    let locale = Locale::from_tag(tag).ok_or_else(|| {
+        AppError::new(ErrorCode::InvalidArgument, tf("error.unsupportedLocale", &[("locale", &tag)]))
+    })?;
+    let mut settings = load_settings(app_handle);
+    settings.locale = Some(locale);
+    save_settings(app_handle, &settings)?;
+    set_current(locale);
+    Ok(locale)
+}
+
+/// 当前语言及可选语言列表
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    pub locale: Locale,
+    pub available: Vec<Locale>,
+}
+
+/// Tauri 命令：获取当前后端语言
+#[tauri::command]
+pub async fn get_locale() -> AppResult<LocaleInfo> {
+    Ok(LocaleInfo { locale: current(), available: Locale::ALL.to_vec() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 文案中出现的 `{name}` 占位符，按名称排序
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    #[test]
+    fn from_tag_matches_language_prefix() {
+        assert_eq!(Locale::from_tag("zh-Hans-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_tag("zh_TW"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_tag("en_GB.UTF-8"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_tag("EN"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_tag("fr-FR"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn lookup_uses_the_requested_locale() {
+        assert_eq!(lookup("tray.quit", Locale::ZhCn), Some("退出"));
+        assert_eq!(lookup("tray.quit", Locale::EnUs), Some("Quit"));
+        assert_eq!(lookup("missing.key", Locale::EnUs), None);
+        assert_eq!(t("missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn placeholders_are_replaced_by_name() {
+        let template = lookup("error.exitCode", Locale::EnUs).unwrap();
+        assert_eq!(
+            format_with(template, &[("program", &"npm"), ("code", &1)]),
+            "npm exited with code: 1"
+        );
+        // 未提供的占位符保持原样，多余的参数被忽略
+        assert_eq!(format_with("{a} {b}", &[("a", &"x"), ("c", &"y")]), "x {b}");
+    }
+
+    #[test]
+    fn catalog_keys_are_unique_and_translations_agree_on_placeholders() {
+        let mut seen = std::collections::HashSet::new();
+        for (key, zh, en) in CATALOG {
+            assert!(seen.insert(*key), "duplicate key {}", key);
+            assert_eq!(placeholders(zh), placeholders(en), "placeholder mismatch in {}", key);
+        }
+    }
+}
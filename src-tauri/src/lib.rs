@@ -1,13 +1,49 @@
-use std::process::{Command, Stdio};
+use std::process::Stdio;
 use std::io::{BufRead, BufReader};
 use std::sync::Mutex;
 use tauri::{Manager, Emitter};
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
 use tauri_plugin_dialog::DialogExt;
 
-mod resource_manager_fixed;
-use resource_manager_fixed::{get_executable_path, check_executable_exists, execute_external_tool};
+mod agent_session;
+mod audit_log;
+mod claude_commands;
+mod command_job;
+mod command_policy;
+mod error;
+mod file_watcher;
+mod i18n;
+mod process;
+mod prompt_convert;
+mod prompt_history;
+mod prompt_lint;
+mod prompt_pack;
+mod prompt_store;
+mod prompt_sync;
+mod prompt_template;
+mod resource_manager;
+mod search_index;
+mod token_budget;
+mod tool_registry;
+use agent_session::{AgentSession, AgentSessionState, list_agent_sessions, focus_agent_session, stop_agent_session};
+use command_job::{CommandJobState, execute_command_stream, cancel_command};
+use audit_log::query_audit_log;
+use command_policy::{CommandPolicy, get_command_policy};
+use error::{AppError, AppResult, ErrorCode};
+use claude_commands::{list_claude_commands, get_claude_command, create_claude_command, update_claude_command, rename_claude_command, delete_claude_command};
+use file_watcher::{FileWatcherState, watch_project, unwatch_project};
+use prompt_convert::{convert_prompt, sync_prompt_category};
+use prompt_history::{list_versions, diff_versions, restore_version, read_agent_file, save_agent_file, read_config_file, save_config_file};
+use prompt_lint::{lint_prompts, apply_lint_fix};
+use prompt_pack::{export_prompt_pack, preview_prompt_pack, import_prompt_pack, generate_pack_key_pair};
+use prompt_sync::{get_prompt_sync_config, set_prompt_sync_config, sync_prompt_library};
+use prompt_template::{render_prompt, render_template};
+use search_index::{SearchIndexState, search_prompts, rebuild_search_index};
+use token_budget::{count_tokens, count_prompt_tokens, estimate_instruction_tokens};
+use i18n::{get_locale, t, tf};
+use process::ProcessBuilder;
+use prompt_store::{list_prompts, get_prompt, create_prompt, update_prompt, rename_prompt, delete_prompt};
+use resource_manager::{get_executable_path, check_executable_exists, execute_external_tool, list_bundled_tools, get_tool_metadata, prepare_bundled_tool, diagnose_resource_paths};
+use tool_registry::{get_tool_registry_config, fetch_tool_index, list_installed_tools, install_tool, activate_tool_version, remove_tool_version};
 
 #[derive(Default)]
 struct McpState {
@@ -32,21 +68,21 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-async fn select_folder(app_handle: tauri::AppHandle) -> Result<Option<String>, String> {
+async fn select_folder(app_handle: tauri::AppHandle) -> AppResult<Option<String>> {
     use std::sync::mpsc;
     let (tx, rx) = mpsc::channel();
 
     app_handle
         .dialog()
         .file()
-        .set_title("选择项目文件夹")
+        .set_title(t("dialog.selectFolder.title"))
         .pick_folder(move |folder_path| {
             tx.send(folder_path).unwrap();
         });
 
     let folder_path = rx
         .recv()
-        .map_err(|e| format!("Failed to receive folder path: {}", e))?;
+        .map_err(|e| AppError::internal(tf("error.receiveFolder", &[("error", &e)])))?;
 
     match folder_path {
         Some(path) => Ok(Some(path.to_string())),
@@ -54,110 +90,97 @@ async fn select_folder(app_handle: tauri::AppHandle) -> Result<Option<String>, S
     }
 }
 
+/// 在后台启动 Codex 打开项目文件夹，立即返回会话信息，不等待 Codex 退出
 #[tauri::command]
-async fn open_folder_in_codex(path: String) -> Result<String, String> {
-    // 使用 Codex 打开项目文件夹
-    let mut cmd = if cfg!(target_os = "windows") {
-        let mut cmd = Command::new("cmd");
-        cmd.args(&["/C", "codex", &path]);
-        cmd
-    } else {
-        let mut cmd = Command::new("codex");
-        cmd.arg(&path);
-        cmd
-    };
-
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to open folder in Codex: {}", e))?;
+async fn open_folder_in_codex(app_handle: tauri::AppHandle, path: String) -> AppResult<AgentSession> {
+    agent_session::launch_codex_session(&app_handle, &path)
+}
 
-    if output.status.success() {
-        Ok("Successfully opened folder in Codex".to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+/// 执行命令并返回 stdout；非零退出码时返回 stderr
+fn run_command(
+    app_handle: &tauri::AppHandle,
+    ctx: &process::SpawnContext,
+    command: &str,
+    args: &[String],
+) -> AppResult<String> {
+    // 按 PATH/PATHEXT 解析程序，npm 的 .cmd 脚本也无需经过 cmd /C
+    let builder = ProcessBuilder::new(command).args(args);
+    let output = process::output_audited(app_handle, ctx, &builder)?;
+    Ok(process::check_output(command, output)?)
 }
 
+/// 执行任意命令；未在允许列表中的命令需要用户确认
 #[tauri::command]
-async fn execute_command(command: String, args: Vec<String>) -> Result<String, String> {
-    // 在Windows上使用cmd.exe来执行命令，确保能找到PATH中的程序
-    let mut cmd = if cfg!(target_os = "windows") {
-        let mut cmd = Command::new("cmd");
-        cmd.args(&["/C", &command]);
-        cmd.args(&args);
-        // Windows 下隐藏控制台窗口
-        #[cfg(target_os = "windows")]
-        {
-            // CREATE_NO_WINDOW = 0x08000000
-            cmd.creation_flags(0x08000000);
-        }
-        cmd
-    } else {
-        let mut cmd = Command::new(&command);
-        cmd.args(&args);
-        cmd
-    };
-
-    let output = cmd
-        .output()
-        .map_err(|e| format!("Failed to execute command: {}", e))?;
+async fn execute_command(app_handle: tauri::AppHandle, command: String, args: Vec<String>) -> AppResult<String> {
+    let decision = command_policy::authorize(&app_handle, "execute_command", &command, &args, &Default::default())?;
+    let ctx = process::SpawnContext::new("execute_command").decision(decision);
+    run_command(&app_handle, &ctx, &command, &args)
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+/// 按名称执行允许列表中声明的命令
+#[tauri::command]
+async fn execute_named_command(
+    app_handle: tauri::AppHandle,
+    name: String,
+    params: Option<std::collections::HashMap<String, String>>,
+) -> AppResult<String> {
+    let policy = CommandPolicy::load(&app_handle)?;
+    let named = policy
+        .find_by_name(&name)
+        .ok_or_else(|| AppError::new(ErrorCode::NotFound, tf("error.unknownCommand", &[("name", &name)])))?;
+    let args = named.render(&params.unwrap_or_default())?;
+    let decision = command_policy::authorize(&app_handle, "execute_named_command", &named.program, &args, &Default::default())?;
+    let ctx = process::SpawnContext::new("execute_named_command").decision(decision);
+    run_command(&app_handle, &ctx, &named.program, &args)
 }
 
-/// 托管启动 MakingMcp.Web.exe，隐藏窗口并流式输出日志到前端
+/// 托管启动内置的 MakingMcp.Web（清单中的 `making-mcp`），隐藏窗口并流式输出日志到前端
 #[tauri::command]
 async fn start_mcp_service(
     app_handle: tauri::AppHandle,
     state: tauri::State<'_, McpState>,
     env: Option<std::collections::HashMap<String, String>>,
-) -> Result<String, String> {
+) -> AppResult<String> {
     // 防止重复启动
     {
-        let pid_guard = state.pid.lock().map_err(|_| "Failed to lock state".to_string())?;
+        let pid_guard = state.pid.lock().map_err(|_| AppError::lock())?;
         if pid_guard.is_some() {
-            return Err("MCP 服务已在运行".to_string());
+            return Err(AppError::new(ErrorCode::AlreadyRunning, t("error.mcpAlreadyRunning")));
         }
     }
 
     // 解析可执行文件路径
-    let exe_name = "MakingMcp.Web.exe".to_string();
-    let exe_path = get_executable_path(app_handle.clone(), exe_name)
-        .await
-        .map_err(|e| format!("获取可执行文件路径失败: {}", e))?;
-
-    // 构建命令：隐藏窗口并捕获 stdout/stderr
-    let mut cmd = Command::new(&exe_path);
-    cmd.stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .stdin(Stdio::null());
-
-    // 注入环境变量（如果提供）
+    let exe_path = resource_manager::ResourceManager::new(app_handle.clone())
+        .verified_executable_path("making-mcp")
+        .map_err(|e| e.context(t("error.resolveExecutable")))?;
+
+    // 构建命令（隐藏窗口由 ProcessBuilder 统一处理）
+    let mut builder = ProcessBuilder::new(&exe_path);
+
+    // 注入环境变量（如果提供），其值不写入审计日志
+    let mut secrets = Vec::new();
     if let Some(env_map) = env {
         for (k, v) in env_map {
             // 过滤空值，避免设置为空字符串的变量
             if !v.trim().is_empty() {
-                cmd.env(k, v);
+                secrets.push(v.clone());
+                builder = builder.env(k, v);
             }
         }
     }
 
-    // Windows 下确保不显示控制台窗口
-    #[cfg(target_os = "windows")]
-    {
-        // CREATE_NO_WINDOW = 0x08000000
-        cmd.creation_flags(0x08000000);
-    }
-
-    let mut child = cmd.spawn().map_err(|e| format!("启动进程失败: {}", e))?;
+    // 捕获 stdout/stderr
+    let ctx = process::SpawnContext::new("start_mcp_service").secrets(secrets);
+    let (mut child, audit) = process::spawn_audited(&app_handle, &ctx, &builder, |cmd| {
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null());
+    })
+    .map_err(|e| AppError::from(e).context(t("error.startProcess")))?;
 
     // 保存 PID 到状态
     {
-        let mut pid_guard = state.pid.lock().map_err(|_| "Failed to lock state".to_string())?;
+        let mut pid_guard = state.pid.lock().map_err(|_| AppError::lock())?;
         *pid_guard = Some(child.id());
     }
 
@@ -193,6 +216,7 @@ async fn start_mcp_service(
         std::thread::spawn(move || {
             match child.wait() {
                 Ok(status) => {
+                    audit.finish(status.code(), None);
                     // 清除 PID（在线程中重新获取 State，避免生命周期问题）
                     let state_ref = app_handle_clone.state::<McpState>();
                     if let Ok(mut pid_guard) = state_ref.pid.lock() {
@@ -200,7 +224,8 @@ async fn start_mcp_service(
                     }
                     let _ = app_handle_clone.emit("mcp-exit", ExitPayload { code: status.code() });
                 }
-                Err(_) => {
+                Err(e) => {
+                    audit.finish(None, Some(e.to_string()));
                     let state_ref = app_handle_clone.state::<McpState>();
                     if let Ok(mut pid_guard) = state_ref.pid.lock() {
                         *pid_guard = None;
@@ -211,56 +236,52 @@ async fn start_mcp_service(
         });
     }
 
-    Ok("MCP 服务已启动并托管 (隐藏控制台)".to_string())
+    Ok(t("mcp.started"))
+}
+
+/// 托管的 MCP 服务状态
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct McpStatus {
+    running: bool,
+    pid: Option<u32>,
+}
+
+/// 查询托管的 MCP 服务是否在运行；进程退出时监听线程会清除 PID
+#[tauri::command]
+async fn get_mcp_service_status(state: tauri::State<'_, McpState>) -> AppResult<McpStatus> {
+    let pid = *state.pid.lock().map_err(|_| AppError::lock())?;
+    Ok(McpStatus { running: pid.is_some(), pid })
 }
 
 /// 停止托管的 MCP 服务
 #[tauri::command]
-async fn stop_mcp_service(state: tauri::State<'_, McpState>) -> Result<String, String> {
+async fn stop_mcp_service(state: tauri::State<'_, McpState>) -> AppResult<String> {
     // 读取 PID
     let pid_opt = {
-        let pid_guard = state.pid.lock().map_err(|_| "Failed to lock state".to_string())?;
+        let pid_guard = state.pid.lock().map_err(|_| AppError::lock())?;
         *pid_guard
     };
 
     if let Some(pid) = pid_opt {
-        // Windows 使用 taskkill；其他平台使用 kill
-        #[cfg(target_os = "windows")]
-        {
-            let output = Command::new("taskkill")
-                .args(["/F", "/PID", &pid.to_string()])
-                .output()
-                .map_err(|e| format!("停止进程失败: {}", e))?;
-            if !output.status.success() {
-                return Err(String::from_utf8_lossy(&output.stderr).to_string());
-            }
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            let output = Command::new("kill")
-                .args(["-9", &pid.to_string()])
-                .output()
-                .map_err(|e| format!("停止进程失败: {}", e))?;
-            if !output.status.success() {
-                return Err(String::from_utf8_lossy(&output.stderr).to_string());
-            }
-        }
+        process::kill_process(pid)?;
 
         // 清除 PID
-        let mut pid_guard = state.pid.lock().map_err(|_| "Failed to lock state".to_string())?;
+        let mut pid_guard = state.pid.lock().map_err(|_| AppError::lock())?;
         *pid_guard = None;
-        Ok("MCP 服务已停止".to_string())
+        Ok(t("mcp.stopped"))
     } else {
-        Err("MCP 服务未运行".to_string())
+        Err(AppError::new(ErrorCode::NotRunning, t("error.mcpNotRunning")))
     }
 }
 
 #[tauri::command(rename_all = "camelCase")]
 async fn open_project_in_terminal(
+    app_handle: tauri::AppHandle,
     path: String, 
     launch_mode: Option<String>, 
     environment_variables: Option<String>
-) -> Result<String, String> {
+) -> AppResult<String> {
     // 确定启动命令
     let codex_command = match launch_mode.as_deref() {
         Some("bypass") => "codex --dangerously-bypass-approvals-and-sandbox",
@@ -286,6 +307,10 @@ async fn open_project_in_terminal(
         String::new()
     };
 
+    // 审计日志中隐藏环境变量的值
+    let ctx = process::SpawnContext::new("open_project_in_terminal")
+        .secrets(env_vars.iter().map(|(_, value)| value.clone()).collect());
+
     // 根据操作系统打开终端并在项目目录中执行codex
     let result = if cfg!(target_os = "windows") {
         // Windows: 打开新的PowerShell窗口并执行命令
@@ -301,11 +326,11 @@ async fn open_project_in_terminal(
             full_command.replace("'", "''")  // 转义单引号
         );
         
-        Command::new("powershell")
-            .args(&["-Command", &start_process_command])
-            .spawn()
-            .map_err(|e| format!("Failed to open terminal on Windows: {}", e))?;
-        format!("Successfully opened terminal and executed {} on Windows", codex_command)
+        let builder = ProcessBuilder::new("powershell").args(["-Command", &start_process_command]);
+        let (child, audit) = process::spawn_audited(&app_handle, &ctx, &builder, |_| {})
+            .map_err(|e| AppError::from(e).context(t("error.openTerminal")))?;
+        process::finish_audit_on_exit(child, audit);
+        tf("terminal.opened", &[("command", &codex_command)])
     } else if cfg!(target_os = "macos") {
         // macOS: 使用Terminal.app
         let full_command = if !env_vars.is_empty() {
@@ -318,14 +343,12 @@ async fn open_project_in_terminal(
             format!("cd '{}' && {}", path, codex_command)
         };
         
-        Command::new("osascript")
-            .args(&[
-                "-e",
-                &format!("tell application \"Terminal\" to do script \"{}\"", full_command)
-            ])
-            .spawn()
-            .map_err(|e| format!("Failed to open terminal on macOS: {}", e))?;
-        format!("Successfully opened terminal and executed {} on macOS", codex_command)
+        let script = format!("tell application \"Terminal\" to do script \"{}\"", full_command);
+        let builder = ProcessBuilder::new("osascript").args(["-e", &script]);
+        let (child, audit) = process::spawn_audited(&app_handle, &ctx, &builder, |_| {})
+            .map_err(|e| AppError::from(e).context(t("error.openTerminal")))?;
+        process::finish_audit_on_exit(child, audit);
+        tf("terminal.opened", &[("command", &codex_command)])
     } else {
         // Linux: 尝试使用常见的终端模拟器
         let full_command = if !env_vars.is_empty() {
@@ -342,30 +365,22 @@ async fn open_project_in_terminal(
         let mut success = false;
         
         for terminal in &terminals {
-            let result = if *terminal == "gnome-terminal" {
-                Command::new(terminal)
-                    .args(&["--", "bash", "-c", &full_command])
-                    .spawn()
-            } else if *terminal == "konsole" {
-                Command::new(terminal)
-                    .args(&["-e", "bash", "-c", &full_command])
-                    .spawn()
-            } else {
-                Command::new(terminal)
-                    .args(&["-e", "bash", "-c", &full_command])
-                    .spawn()
-            };
-            
-            if result.is_ok() {
+            // gnome-terminal 使用 `--` 分隔命令，其余终端使用 `-e`
+            let exec_flag = if *terminal == "gnome-terminal" { "--" } else { "-e" };
+            let builder = ProcessBuilder::new(terminal).args([exec_flag, "bash", "-c", &full_command]);
+            let result = process::spawn_audited(&app_handle, &ctx, &builder, |_| {});
+
+            if let Ok((child, audit)) = result {
+                process::finish_audit_on_exit(child, audit);
                 success = true;
                 break;
             }
         }
         
         if success {
-            format!("Successfully opened terminal and executed {} on Linux", codex_command)
+            tf("terminal.opened", &[("command", &codex_command)])
         } else {
-            return Err("Failed to find a suitable terminal emulator on Linux".to_string());
+            return Err(AppError::new(ErrorCode::ExecutableNotFound, t("error.noTerminal")));
         }
     };
 
@@ -397,6 +412,39 @@ fn parse_environment_variables(env_str: &str) -> Vec<(String, String)> {
         .collect()
 }
 
+const TRAY_ID: &str = "main";
+
+/// 按当前语言构建托盘菜单
+fn build_tray_menu(app_handle: &tauri::AppHandle) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
+    let show_item = tauri::menu::MenuItem::with_id(app_handle, "show", t("tray.show"), true, None::<&str>)?;
+    let hide_item = tauri::menu::MenuItem::with_id(app_handle, "hide", t("tray.hide"), true, None::<&str>)?;
+    let separator = tauri::menu::PredefinedMenuItem::separator(app_handle)?;
+    let quit_item = tauri::menu::MenuItem::with_id(app_handle, "quit", t("tray.quit"), true, None::<&str>)?;
+
+    tauri::menu::Menu::with_items(
+        app_handle,
+        &[
+            &show_item,
+            &hide_item,
+            &separator,
+            &quit_item,
+        ],
+    )
+}
+
+/// 运行时切换后端语言，保存设置并重建托盘菜单
+#[tauri::command]
+async fn set_locale(app_handle: tauri::AppHandle, locale: String) -> AppResult<i18n::Locale> {
+    let locale = i18n::change_locale(&app_handle, &locale)?;
+
+    if let Some(tray) = app_handle.tray_by_id(TRAY_ID) {
+        let menu = build_tray_menu(&app_handle).map_err(|e| AppError::internal(e.to_string()))?;
+        tray.set_menu(Some(menu)).map_err(|e| AppError::internal(e.to_string()))?;
+    }
+    let _ = app_handle.emit("locale-changed", locale);
+    Ok(locale)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     use tauri::tray::{TrayIconBuilder, MouseButton, MouseButtonState};
@@ -407,25 +455,24 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .manage(McpState::default())
+        .manage(AgentSessionState::default())
+        .manage(CommandJobState::default())
+        .manage(FileWatcherState::default())
+        .manage(SearchIndexState::default())
         .setup(|app| {
+            // 确定界面语言，托盘菜单和对话框文案依赖它
+            i18n::init(app.handle());
+
+            // 监听提示词和配置文件的外部修改；失败时只影响自动刷新
+            if let Err(e) = file_watcher::start(app.handle()) {
+                e.report(app.handle());
+            }
+
             // 创建托盘菜单
-            let show_item = tauri::menu::MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
-            let hide_item = tauri::menu::MenuItem::with_id(app, "hide", "隐藏窗口", true, None::<&str>)?;
-            let separator = tauri::menu::PredefinedMenuItem::separator(app)?;
-            let quit_item = tauri::menu::MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-
-            let menu = tauri::menu::Menu::with_items(
-                app,
-                &[
-                    &show_item,
-                    &hide_item,
-                    &separator,
-                    &quit_item,
-                ],
-            )?;
+            let menu = build_tray_menu(app.handle())?;
 
             // 创建系统托盘图标
-            let _tray = TrayIconBuilder::new()
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
                 .menu(&menu)
                 .on_tray_icon_event(|tray, event| {
@@ -488,13 +535,73 @@ pub fn run() {
             greet,
             select_folder,
             open_folder_in_codex,
+            list_agent_sessions,
+            focus_agent_session,
+            stop_agent_session,
             execute_command,
+            execute_named_command,
+            get_command_policy,
+            query_audit_log,
+            get_locale,
+            set_locale,
+            execute_command_stream,
+            cancel_command,
             start_mcp_service,
             stop_mcp_service,
+            get_mcp_service_status,
             open_project_in_terminal,
             get_executable_path,
             check_executable_exists,
-            execute_external_tool
+            execute_external_tool,
+            list_bundled_tools,
+            get_tool_metadata,
+            prepare_bundled_tool,
+            diagnose_resource_paths,
+            get_tool_registry_config,
+            fetch_tool_index,
+            list_installed_tools,
+            install_tool,
+            activate_tool_version,
+            remove_tool_version,
+            list_prompts,
+            get_prompt,
+            create_prompt,
+            update_prompt,
+            rename_prompt,
+            delete_prompt,
+            list_claude_commands,
+            get_claude_command,
+            create_claude_command,
+            update_claude_command,
+            rename_claude_command,
+            delete_claude_command,
+            watch_project,
+            unwatch_project,
+            search_prompts,
+            rebuild_search_index,
+            list_versions,
+            diff_versions,
+            restore_version,
+            read_agent_file,
+            save_agent_file,
+            read_config_file,
+            save_config_file,
+            render_prompt,
+            render_template,
+            convert_prompt,
+            sync_prompt_category,
+            export_prompt_pack,
+            preview_prompt_pack,
+            import_prompt_pack,
+            generate_pack_key_pair,
+            get_prompt_sync_config,
+            set_prompt_sync_config,
+            sync_prompt_library,
+            lint_prompts,
+            apply_lint_fix,
+            count_tokens,
+            count_prompt_tokens,
+            estimate_instruction_tokens
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+use crate::audit_log::SpawnAudit;
+use crate::i18n::tf;
+
+/// CREATE_NO_WINDOW：Windows 下启动子进程时不创建控制台窗口
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// CREATE_NEW_CONSOLE：Windows 下为交互式子进程创建独立的控制台窗口
+#[cfg(target_os = "windows")]
+const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+
+static ID_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+/// 当前 Unix 时间戳（毫秒）
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 生成进程内唯一的 ID，例如 `codex-1718000000000-3`
+pub fn next_id(prefix: &str) -> String {
+    let seq = ID_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{}-{}-{}", prefix, now_millis(), seq)
+}
+
+/// 启动或执行进程时的错误
+#[derive(Debug)]
+pub enum SpawnError {
+    /// 在 PATH 中找不到程序，或给定路径不存在
+    NotFound { program: String },
+    /// 程序存在但没有执行权限
+    PermissionDenied { program: String },
+    /// 进程正常结束但返回了非零退出码
+    NonZeroExit { program: String, code: Option<i32>, stderr: String },
+    /// 其他 IO 错误
+    Io { program: String, message: String },
+}
+
+impl SpawnError {
+    fn from_io(program: &str, e: std::io::Error) -> Self {
+        let program = program.to_string();
+        match e.kind() {
+            std::io::ErrorKind::NotFound => SpawnError::NotFound { program },
+            std::io::ErrorKind::PermissionDenied => SpawnError::PermissionDenied { program },
+            _ => SpawnError::Io { program, message: e.to_string() },
+        }
+    }
+}
+
+impl std::fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpawnError::NotFound { program } => {
+                write!(f, "{}", tf("error.executableNotFound", &[("program", program)]))
+            }
+            SpawnError::PermissionDenied { program } => {
+                write!(f, "{}", tf("error.permissionDenied", &[("program", program)]))
+            }
+            SpawnError::NonZeroExit { program, code, stderr } => {
+                if stderr.trim().is_empty() {
+                    let code = code.map_or_else(|| "-".to_string(), |c| c.to_string());
+                    write!(f, "{}", tf("error.exitCode", &[("program", program), ("code", &code)]))
+                } else {
+                    write!(f, "{}", stderr)
+                }
+            }
+            SpawnError::Io { program, message } => {
+                write!(f, "{}", tf("error.processFailed", &[("program", program), ("error", message)]))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpawnError {}
+
+/// Windows 下可直接执行的扩展名，优先取 PATHEXT
+#[cfg(target_os = "windows")]
+fn executable_extensions() -> Vec<String> {
+    std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| ext.to_ascii_lowercase())
+        .collect()
+}
+
+/// 在 `path_var`（默认取当前进程的 PATH）描述的目录中查找可执行文件
+///
+/// 含路径分隔符的程序名按路径直接检查；Windows 下没有扩展名时按 PATHEXT
+/// 依次尝试，因此 npm 安装的 `codex.cmd` 之类的脚本无需经过 `cmd /C` 也能找到。
+pub fn resolve_executable(program: &str, path_var: Option<&OsString>) -> Result<PathBuf, SpawnError> {
+    let not_found = || SpawnError::NotFound { program: program.to_string() };
+    let candidate = Path::new(program);
+
+    #[cfg(target_os = "windows")]
+    let names: Vec<String> = if candidate.extension().is_some() {
+        vec![program.to_string()]
+    } else {
+        executable_extensions()
+            .iter()
+            .map(|ext| format!("{}{}", program, ext))
+            .collect()
+    };
+    #[cfg(not(target_os = "windows"))]
+    let names: Vec<String> = vec![program.to_string()];
+
+    if candidate.is_absolute() || candidate.components().count() > 1 {
+        let dir = candidate.parent().unwrap_or(Path::new(""));
+        return names
+            .iter()
+            .map(|name| dir.join(Path::new(name).file_name().unwrap_or_default()))
+            .find(|p| p.is_file())
+            .ok_or_else(not_found);
+    }
+
+    let path_var = path_var
+        .cloned()
+        .or_else(|| std::env::var_os("PATH"))
+        .ok_or_else(not_found)?;
+    std::env::split_paths(&path_var)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|p| p.is_file())
+        .ok_or_else(not_found)
+}
+
+/// 统一的进程构建器
+///
+/// 负责程序路径解析、工作目录、环境变量以及 Windows 下的窗口隐藏，
+/// 所有启动外部进程的地方都应通过它构建 `Command`。
+pub struct ProcessBuilder {
+    program: String,
+    args: Vec<OsString>,
+    cwd: Option<PathBuf>,
+    env: HashMap<String, String>,
+    new_console: bool,
+}
+
+impl ProcessBuilder {
+    pub fn new(program: impl AsRef<Path>) -> Self {
+        Self {
+            program: program.as_ref().to_string_lossy().to_string(),
+            args: Vec::new(),
+            cwd: None,
+            env: HashMap::new(),
+            new_console: false,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn cwd(mut self, cwd: impl AsRef<Path>) -> Self {
+        self.cwd = Some(cwd.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn envs(mut self, vars: &HashMap<String, String>) -> Self {
+        self.env.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self
+    }
+
+    /// Windows 下为进程创建独立的可见控制台（用于交互式程序），其他平台无影响
+    pub fn new_console(mut self) -> Self {
+        self.new_console = true;
+        self
+    }
+
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
+    /// 解析程序路径并生成配置好的 `Command`
+    ///
+    /// Windows 下默认附加 CREATE_NO_WINDOW，调用 `new_console` 后改为 CREATE_NEW_CONSOLE。
+    pub fn build(&self) -> Result<Command, SpawnError> {
+        let path_override = self.env.get("PATH").map(OsString::from);
+        let resolved = resolve_executable(&self.program, path_override.as_ref())?;
+
+        let mut cmd = Command::new(resolved);
+        cmd.args(&self.args);
+        cmd.envs(&self.env);
+        if let Some(cwd) = &self.cwd {
+            cmd.current_dir(cwd);
+        }
+
+        #[cfg(target_os = "windows")]
+        cmd.creation_flags(if self.new_console { CREATE_NEW_CONSOLE } else { CREATE_NO_WINDOW });
+
+        Ok(cmd)
+    }
+}
+
+/// 将非零退出码转换为 `NonZeroExit` 错误，成功时返回 stdout
+pub fn check_output(program: &str, output: Output) -> Result<String, SpawnError> {
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(SpawnError::NonZeroExit {
+            program: program.to_string(),
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+/// 强制结束指定 PID 的进程（Windows 下连同子进程树一起结束）
+pub fn kill_process(pid: u32) -> Result<(), SpawnError> {
+    #[cfg(target_os = "windows")]
+    let builder = ProcessBuilder::new("taskkill").args(["/F", "/T", "/PID", &pid.to_string()]);
+    #[cfg(not(target_os = "windows"))]
+    let builder = ProcessBuilder::new("kill").args(["-9", &pid.to_string()]);
+
+    let output = builder
+        .build()?
+        .output()
+        .map_err(|e| SpawnError::from_io(builder.program(), e))?;
+    check_output(builder.program(), output).map(|_| ())
+}
+
+/// 启动进程时附带的审计信息
+pub struct SpawnContext<'a> {
+    /// 触发启动的 Tauri 命令名
+    pub source: &'a str,
+    /// 命令策略的判定结果，内部启动的进程为空
+    pub decision: Option<&'a str>,
+    /// 需要在审计日志中隐藏的值，例如注入的环境变量值
+    pub secrets: Vec<String>,
+}
+
+impl<'a> SpawnContext<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self { source, decision: None, secrets: Vec::new() }
+    }
+
+    pub fn decision(mut self, decision: &'a str) -> Self {
+        self.decision = Some(decision);
+        self
+    }
+
+    pub fn secrets(mut self, secrets: Vec<String>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+}
+
+/// 解析并启动进程，同时写入审计日志；返回子进程和用于记录结束事件的句柄
+///
+/// `configure` 用于在启动前设置 stdio 等。所有由应用启动的进程都应经过这里
+/// （或 `output_audited`），程序找不到、启动失败也会留下记录。
+pub fn spawn_audited(
+    app_handle: &AppHandle,
+    ctx: &SpawnContext,
+    builder: &ProcessBuilder,
+    configure: impl FnOnce(&mut Command),
+) -> Result<(Child, SpawnAudit), SpawnError> {
+    let mut cmd = match builder.build() {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            let mut attempted = Command::new(builder.program());
+            attempted.args(&builder.args);
+            SpawnAudit::start(app_handle, ctx.source, &attempted, ctx.decision, &ctx.secrets, None)
+                .finish(None, Some(e.to_string()));
+            return Err(e);
+        }
+    };
+    configure(&mut cmd);
+
+    match cmd.spawn() {
+        Ok(child) => {
+            let audit = SpawnAudit::start(app_handle, ctx.source, &cmd, ctx.decision, &ctx.secrets, Some(child.id()));
+            Ok((child, audit))
+        }
+        Err(e) => {
+            SpawnAudit::start(app_handle, ctx.source, &cmd, ctx.decision, &ctx.secrets, None)
+                .finish(None, Some(e.to_string()));
+            Err(SpawnError::from_io(builder.program(), e))
+        }
+    }
+}
+
+/// 执行进程并等待其结束，审计日志中同时记录启动和退出信息
+///
+/// 非零退出码不视为错误，调用方可通过 `check_output` 转换。
+pub fn output_audited(
+    app_handle: &AppHandle,
+    ctx: &SpawnContext,
+    builder: &ProcessBuilder,
+) -> Result<Output, SpawnError> {
+    let (child, audit) = spawn_audited(app_handle, ctx, builder, |cmd| {
+        cmd.stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped());
+    })?;
+    match child.wait_with_output() {
+        Ok(output) => {
+            audit.finish(output.status.code(), None);
+            Ok(output)
+        }
+        Err(e) => {
+            audit.finish(None, Some(e.to_string()));
+            Err(SpawnError::from_io(builder.program(), e))
+        }
+    }
+}
+
+/// 在后台线程等待进程结束并写入审计结束事件，用于不关心退出结果的启动器进程
+pub fn finish_audit_on_exit(mut child: Child, audit: SpawnAudit) {
+    std::thread::spawn(move || match child.wait() {
+        Ok(status) => audit.finish(status.code(), None),
+        Err(e) => audit.finish(None, Some(e.to_string())),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 当前平台上 `resolve_executable` 会匹配的文件名
+    fn exe(name: &str) -> String {
+        if cfg!(target_os = "windows") {
+            format!("{}.exe", name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    #[test]
+    fn next_id_is_unique_and_prefixed() {
+        let (a, b) = (next_id("job"), next_id("job"));
+        assert!(a.starts_with("job-"));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn path_override_is_searched_in_order() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+        std::fs::write(second.path().join(exe("mytool")), b"").unwrap();
+        let path_var = std::env::join_paths([first.path(), second.path()]).unwrap();
+        assert_eq!(resolve_executable("mytool", Some(&path_var)).unwrap(), second.path().join(exe("mytool")));
+
+        std::fs::write(first.path().join(exe("mytool")), b"").unwrap();
+        assert_eq!(resolve_executable("mytool", Some(&path_var)).unwrap(), first.path().join(exe("mytool")));
+        assert!(matches!(resolve_executable("missing", Some(&path_var)), Err(SpawnError::NotFound { .. })));
+    }
+
+    #[test]
+    fn paths_with_separators_skip_the_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join(exe("direct"));
+        std::fs::write(&tool, b"").unwrap();
+        let empty = OsString::new();
+        assert_eq!(resolve_executable(&dir.path().join("direct").to_string_lossy(), Some(&empty)).unwrap(), tool);
+    }
+
+    #[test]
+    fn build_uses_the_path_from_the_child_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(exe("only-here")), b"").unwrap();
+        let builder = ProcessBuilder::new("only-here")
+            .arg("--flag")
+            .cwd(dir.path())
+            .env("PATH", dir.path().to_string_lossy());
+        let cmd = builder.build().unwrap();
+        assert_eq!(Path::new(cmd.get_program()), dir.path().join(exe("only-here")));
+        assert_eq!(cmd.get_args().collect::<Vec<_>>(), ["--flag"]);
+        assert_eq!(cmd.get_current_dir(), Some(dir.path()));
+
+        let missing = ProcessBuilder::new("only-here").env("PATH", "");
+        assert!(matches!(missing.build(), Err(SpawnError::NotFound { .. })));
+    }
+
+    #[test]
+    fn kill_process_ends_a_running_child() {
+        #[cfg(target_os = "windows")]
+        let builder = ProcessBuilder::new("ping").args(["-n", "30", "127.0.0.1"]);
+        #[cfg(not(target_os = "windows"))]
+        let builder = ProcessBuilder::new("sleep").arg("30");
+
+        let mut child = builder
+            .build()
+            .unwrap()
+            .stdout(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        kill_process(child.id()).unwrap();
+        assert!(!child.wait().unwrap().success());
+    }
+
+    #[test]
+    fn non_zero_exit_keeps_stderr() {
+        #[cfg(target_os = "windows")]
+        let output = ProcessBuilder::new("cmd").args(["/C", "echo oops 1>&2 & exit 3"]).build().unwrap().output().unwrap();
+        #[cfg(not(target_os = "windows"))]
+        let output = ProcessBuilder::new("sh").args(["-c", "echo oops >&2; exit 3"]).build().unwrap().output().unwrap();
+
+        match check_output("tool", output) {
+            Err(SpawnError::NonZeroExit { code, stderr, .. }) => {
+                assert_eq!(code, Some(3));
+                assert_eq!(stderr.trim(), "oops");
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,605 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::claude_commands::{ClaudeCommand, ClaudeCommandInput, ClaudeCommandStore};
+use crate::error::{AppError, AppResult};
+use crate::i18n::tf;
+use crate::prompt_history::unified_diff;
+use crate::prompt_store::{ArgumentType, Prompt, PromptArgument, PromptInput, PromptStore};
+use crate::prompt_template::{tokenize, Span, Token};
+
+/// 没有分类的 Codex 提示词使用的分类，对应 Claude 的顶层命令
+const DEFAULT_CATEGORY: &str = "general";
+
+/// 转换方向
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConversionDirection {
+    CodexToClaude,
+    ClaudeToCodex,
+}
+
+/// 转换说明的类型
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum NoteKind {
+    /// 目标格式无法表示，转换后丢失
+    Dropped,
+    /// 以近似的方式表示
+    Approximated,
+}
+
+/// 一条转换说明
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionNote {
+    pub kind: NoteKind,
+    /// 相关的字段，例如 `model`、`arguments.depth`
+    pub field: String,
+    pub message: String,
+}
+
+fn note(kind: NoteKind, field: impl Into<String>, key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> ConversionNote {
+    ConversionNote { kind, field: field.into(), message: tf(key, args) }
+}
+
+/// 同步一个文件时的操作
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// 一个文件的转换计划；`dryRun` 为假时已写入
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionPlan {
+    pub source_name: String,
+    pub source_path: PathBuf,
+    pub target_name: String,
+    pub target_path: PathBuf,
+    pub action: SyncAction,
+    /// 目标文件当前内容与转换结果之间的 unified diff
+    pub diff: String,
+    pub notes: Vec<ConversionNote>,
+    pub applied: bool,
+}
+
+/// 分类同步中一个文件的结果；单个文件失败不影响其余文件，已写入的文件在 `plan.applied` 中体现
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncItemResult {
+    pub source_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<ConversionPlan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<AppError>,
+}
+
+impl SyncItemResult {
+    fn new(source_name: &str, result: AppResult<ConversionPlan>) -> Self {
+        let (plan, error) = match result {
+            Ok(plan) => (Some(plan), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self { source_name: source_name.to_string(), plan, error }
+    }
+}
+
+/// 按 `prompt_template::tokenize` 的切分结果改写正文；`replace` 返回 `None` 的片段保留原文
+fn rewrite_placeholders(content: &str, mut replace: impl FnMut(&Span) -> Option<String>) -> String {
+    tokenize(content)
+        .iter()
+        .map(|span| replace(span).unwrap_or_else(|| span.source.to_string()))
+        .collect()
+}
+
+/// 由参数生成 Claude 的 `argument-hint`：必填为 `<name>`，可选为 `[name]`
+fn argument_hint(arguments: &[PromptArgument]) -> Option<String> {
+    let hint: Vec<String> = arguments
+        .iter()
+        .map(|a| if a.required { format!("<{}>", a.name) } else { format!("[{}]", a.name) })
+        .collect();
+    (!hint.is_empty()).then(|| hint.join(" "))
+}
+
+/// 从 `argument-hint` 推断参数：`<name>` 为必填，`[name]` 和裸词为可选
+fn parse_argument_hint(hint: &str) -> Vec<PromptArgument> {
+    hint.split_whitespace()
+        .filter_map(|token| {
+            let required = token.starts_with('<');
+            let name: String = token
+                .trim_matches(|c| matches!(c, '<' | '>' | '[' | ']'))
+                .chars()
+                .map(|c| if c.is_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+                .collect();
+            (!name.is_empty()).then_some(PromptArgument {
+                name,
+                arg_type: ArgumentType::String,
+                required,
+                description: None,
+                default_value: None,
+            })
+        })
+        .collect()
+}
+
+/// Codex 提示词 -> Claude 命令
+///
+/// 分类映射为命名空间（`general` 为顶层），`{{name}}` 改写为对应位置的 `$N`；
+/// 只有一个参数时 `$1` 改写为 `$ARGUMENTS`，以便 Claude 把整段输入作为该参数。
+pub fn prompt_to_command(prompt: &Prompt) -> (String, ClaudeCommandInput, Vec<ConversionNote>) {
+    let mut notes = Vec::new();
+    let spans = tokenize(&prompt.content);
+    let single = prompt.arguments.len() == 1 && !spans.iter().any(|span| span.token == Token::All);
+    let position_of = |name: &str| prompt.arguments.iter().position(|a| a.name == name).map(|i| i + 1);
+    let placeholder = |n: usize| if single && n == 1 { "$ARGUMENTS".to_string() } else { format!("${}", n) };
+    let content = rewrite_placeholders(&prompt.content, |span| match span.token {
+        Token::Positional(n) => Some(placeholder(n)),
+        Token::Named(name) => position_of(name).map(placeholder),
+        // Claude 命令没有转义，`$$` 只能写成 `$`
+        Token::Dollar => Some("$".to_string()),
+        _ => None,
+    });
+    // `$$1`、`$$ARGUMENTS` 去掉转义后会被 Claude 当作占位符展开
+    let ambiguous_dollar = spans.windows(2).any(|pair| {
+        pair[0].token == Token::Dollar
+            && matches!(pair[1].token, Token::Text(text) if text.starts_with(|c: char| ('1'..='9').contains(&c)) || text.starts_with("ARGUMENTS"))
+    });
+    if ambiguous_dollar {
+        notes.push(note(NoteKind::Approximated, "content", "convert.literalDollar", &[]));
+    }
+
+    for argument in &prompt.arguments {
+        let field = format!("arguments.{}", argument.name);
+        if argument.arg_type != ArgumentType::String {
+            notes.push(note(NoteKind::Dropped, &field, "convert.argumentType", &[("name", &argument.name)]));
+        }
+        if let Some(default) = &argument.default_value {
+            notes.push(note(NoteKind::Dropped, &field, "convert.argumentDefault", &[("name", &argument.name), ("value", default)]));
+        }
+        if argument.description.is_some() {
+            notes.push(note(NoteKind::Approximated, &field, "convert.argumentDescription", &[("name", &argument.name)]));
+        }
+    }
+    if content.contains("{{") {
+        notes.push(note(NoteKind::Approximated, "content", "convert.unmatchedPlaceholder", &[]));
+    }
+
+    let name = match prompt.category.trim() {
+        "" | DEFAULT_CATEGORY => prompt.name.clone(),
+        category => format!("{}:{}", category, prompt.name),
+    };
+    let input = ClaudeCommandInput {
+        description: Some(prompt.description.clone()),
+        argument_hint: argument_hint(&prompt.arguments),
+        allowed_tools: prompt.allowed_tools.clone(),
+        content,
+        ..Default::default()
+    };
+    (name, input, notes)
+}
+
+/// Claude 命令 -> Codex 提示词
+///
+/// 命名空间映射为分类，`argument-hint` 推断为参数，`$N` 改写为对应参数的 `{{name}}`；
+/// `$ARGUMENTS` 在只有一个参数时改写为该参数，多个参数时按顺序以空格连接。
+/// `model` 和 `!` 开头的 shell 行无法表示。
+pub fn command_to_prompt(command: &ClaudeCommand) -> (String, PromptInput, Vec<ConversionNote>) {
+    let mut notes = Vec::new();
+    if let Some(model) = &command.model {
+        notes.push(note(NoteKind::Dropped, "model", "convert.model", &[("model", model)]));
+    }
+    if command.content.lines().any(|line| line.trim_start().starts_with("!`")) {
+        notes.push(note(NoteKind::Dropped, "content", "convert.shellLine", &[]));
+    }
+    let arguments = command.argument_hint.as_deref().map(parse_argument_hint).unwrap_or_default();
+    if command.argument_hint.is_some() {
+        notes.push(note(NoteKind::Approximated, "argument-hint", "convert.argumentHint", &[]));
+    }
+
+    let placeholder = |argument: &PromptArgument| format!("{{{{{}}}}}", argument.name);
+    let (mut unmatched, mut expanded) = (false, false);
+    let content = rewrite_placeholders(&command.content, |span| {
+        let replacement = match span.token {
+            Token::Positional(n) => arguments.get(n - 1).map(placeholder),
+            Token::All if !arguments.is_empty() => {
+                expanded |= arguments.len() > 1;
+                Some(arguments.iter().map(placeholder).collect::<Vec<_>>().join(" "))
+            }
+            Token::All => None,
+            // Claude 命令中的 `$$` 是两个字面量 `$`，在 Codex 模板中需要转义
+            Token::Dollar => return Some("$$$$".to_string()),
+            _ => return None,
+        };
+        unmatched |= replacement.is_none();
+        replacement
+    });
+    if expanded {
+        notes.push(note(NoteKind::Approximated, "content", "convert.argumentsExpanded", &[]));
+    }
+    if unmatched {
+        notes.push(note(NoteKind::Approximated, "content", "convert.unmatchedPositional", &[]));
+    }
+
+    let name = command.name.rsplit(':').next().unwrap_or(&command.name).to_string();
+    let input = PromptInput {
+        description: command.description.clone(),
+        category: command.namespace.clone(),
+        allowed_tools: command.allowed_tools.clone(),
+        arguments,
+        content,
+        ..Default::default()
+    };
+    (name, input, notes)
+}
+
+fn plan_action(current: Option<&str>, rendered: &str) -> SyncAction {
+    match current {
+        None => SyncAction::Create,
+        Some(text) if text == rendered => SyncAction::Unchanged,
+        Some(_) => SyncAction::Update,
+    }
+}
+
+/// 目标文件中已有且与转换结果不同的字段会被覆盖，记为丢弃
+fn overwritten(field: &str, existing: &str, converted: &str) -> Option<ConversionNote> {
+    (!existing.is_empty() && existing != converted)
+        .then(|| note(NoteKind::Dropped, field, "convert.overwritten", &[("field", &field), ("value", &existing)]))
+}
+
+/// 把转换结果合并到已有的 Claude 命令上
+///
+/// Codex 提示词无法表示的 `model` 保留目标原有的值，其余被覆盖的字段记为丢弃；
+/// 写入时以读取目标时的修改时间做冲突检查。
+fn merge_into_command(existing: &ClaudeCommand, input: &mut ClaudeCommandInput, notes: &mut Vec<ConversionNote>) {
+    input.model = existing.model.clone();
+    notes.extend(overwritten("description", existing.description.as_deref().unwrap_or(""), input.description.as_deref().unwrap_or("")));
+    notes.extend(overwritten("argument-hint", existing.argument_hint.as_deref().unwrap_or(""), input.argument_hint.as_deref().unwrap_or("")));
+    notes.extend(overwritten("allowed-tools", &existing.allowed_tools.join(", "), &input.allowed_tools.join(", ")));
+    input.expected_updated_at = Some(existing.updated_at);
+}
+
+/// 把转换结果合并到已有的 Codex 提示词上
+///
+/// 同名参数保留目标原有的类型、说明和默认值，转换结果中没有的参数和被覆盖的字段记为丢弃；
+/// Claude 命令没有说明时保留目标原有的说明。写入时以读取目标时的修改时间做冲突检查。
+fn merge_into_prompt(existing: &Prompt, input: &mut PromptInput, notes: &mut Vec<ConversionNote>) {
+    for argument in &mut input.arguments {
+        if let Some(current) = existing.arguments.iter().find(|a| a.name == argument.name) {
+            argument.arg_type = current.arg_type;
+            argument.description = current.description.clone();
+            argument.default_value = current.default_value.clone();
+        }
+    }
+    for current in &existing.arguments {
+        if !input.arguments.iter().any(|a| a.name == current.name) {
+            let field = format!("arguments.{}", current.name);
+            notes.push(note(NoteKind::Dropped, field, "convert.argumentRemoved", &[("name", &current.name)]));
+        }
+    }
+    match &input.description {
+        Some(description) => notes.extend(overwritten("description", &existing.description, description)),
+        None => input.description = Some(existing.description.clone()),
+    }
+    notes.extend(overwritten("tools", &existing.allowed_tools.join(", "), &input.allowed_tools.join(", ")));
+    input.expected_updated_at = Some(existing.updated_at);
+}
+
+/// 转换一个 Codex 提示词并与目标命令比较；`apply` 时写入
+fn sync_prompt(prompt: &Prompt, commands: &ClaudeCommandStore, apply: bool) -> AppResult<ConversionPlan> {
+    let (target_name, mut input, mut notes) = prompt_to_command(prompt);
+    let target_path = commands.path_for(&target_name);
+    let existing = target_path.exists().then(|| commands.get(&target_name)).transpose()?;
+    if let Some(existing) = &existing {
+        merge_into_command(existing, &mut input, &mut notes);
+    }
+    let current = std::fs::read_to_string(&target_path).ok();
+    let rendered = commands.render(&target_path, &input)?;
+    let action = plan_action(current.as_deref(), &rendered);
+    let (diff, _, _) = unified_diff(current.as_deref().unwrap_or(""), &rendered, &target_name, &target_name);
+
+    let applied = apply && action != SyncAction::Unchanged;
+    if applied {
+        match action {
+            SyncAction::Create => commands.create(&target_name, &input)?,
+            _ => commands.update(&target_name, &input)?,
+        };
+    }
+    Ok(ConversionPlan {
+        source_name: prompt.name.clone(),
+        source_path: prompt.file_path.clone(),
+        target_name,
+        target_path,
+        action,
+        diff,
+        notes,
+        applied,
+    })
+}
+
+/// 转换一个 Claude 命令并与目标提示词比较；`apply` 时写入
+fn sync_command(command: &ClaudeCommand, prompts: &PromptStore, apply: bool) -> AppResult<ConversionPlan> {
+    let (target_name, mut input, mut notes) = command_to_prompt(command);
+    let target_path = prompts.path_for(&target_name);
+    let existing = target_path.exists().then(|| prompts.get(&target_name)).transpose()?;
+    if let Some(existing) = &existing {
+        merge_into_prompt(existing, &mut input, &mut notes);
+    }
+    let current = std::fs::read_to_string(&target_path).ok();
+    let rendered = prompts.render(&target_path, &input)?;
+    let action = plan_action(current.as_deref(), &rendered);
+    let (diff, _, _) = unified_diff(current.as_deref().unwrap_or(""), &rendered, &target_name, &target_name);
+
+    let applied = apply && action != SyncAction::Unchanged;
+    if applied {
+        match action {
+            SyncAction::Create => prompts.create(&target_name, &input)?,
+            _ => prompts.update(&target_name, &input)?,
+        };
+    }
+    Ok(ConversionPlan {
+        source_name: command.name.clone(),
+        source_path: command.file_path.clone(),
+        target_name,
+        target_path,
+        action,
+        diff,
+        notes,
+        applied,
+    })
+}
+
+/// Tauri 命令：把一个提示词或命令转换为另一种格式；`dryRun` 时只返回差异
+#[tauri::command(rename_all = "camelCase")]
+pub async fn convert_prompt(
+    app_handle: AppHandle,
+    direction: ConversionDirection,
+    name: String,
+    project_path: Option<String>,
+    dry_run: bool,
+) -> AppResult<ConversionPlan> {
+    let prompts = PromptStore::for_scope(&app_handle, project_path.as_deref())?;
+    let commands = ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?;
+    match direction {
+        ConversionDirection::CodexToClaude => sync_prompt(&prompts.get(&name)?, &commands, !dry_run),
+        ConversionDirection::ClaudeToCodex => sync_command(&commands.get(&name)?, &prompts, !dry_run),
+    }
+}
+
+/// Tauri 命令：把一个分类下的所有提示词同步到另一种格式
+///
+/// Codex 一侧按 `category` 选择，Claude 一侧按命名空间选择；未给出分类时同步全部。
+/// `dryRun` 时只返回每个文件的操作和差异，不写入任何文件；每个文件单独返回结果，
+/// 某个文件失败时其余文件照常处理。
+#[tauri::command(rename_all = "camelCase")]
+pub async fn sync_prompt_category(
+    app_handle: AppHandle,
+    direction: ConversionDirection,
+    category: Option<String>,
+    project_path: Option<String>,
+    dry_run: bool,
+) -> AppResult<Vec<SyncItemResult>> {
+    let prompts = PromptStore::for_scope(&app_handle, project_path.as_deref())?;
+    let commands = ClaudeCommandStore::for_scope(&app_handle, project_path.as_deref())?;
+    let category = category.filter(|c| !c.trim().is_empty());
+    match direction {
+        ConversionDirection::CodexToClaude => {
+            let (items, errors) = prompts.list();
+            for error in &errors {
+                error.report(&app_handle);
+            }
+            Ok(items
+                .iter()
+                .filter(|p| category.as_deref().is_none_or(|c| p.category == c))
+                .map(|p| SyncItemResult::new(&p.name, sync_prompt(p, &commands, !dry_run)))
+                .collect())
+        }
+        ConversionDirection::ClaudeToCodex => {
+            let (items, errors) = commands.list();
+            for error in &errors {
+                error.report(&app_handle);
+            }
+            Ok(items
+                .iter()
+                .filter(|c| {
+                    category
+                        .as_deref()
+                        .is_none_or(|wanted| c.namespace.as_deref().unwrap_or(DEFAULT_CATEGORY) == wanted)
+                })
+                .map(|c| SyncItemResult::new(&c.name, sync_command(c, &prompts, !dry_run)))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompt_store::PromptScope;
+
+    fn argument(name: &str, arg_type: ArgumentType, required: bool) -> PromptArgument {
+        PromptArgument { name: name.to_string(), arg_type, required, description: None, default_value: None }
+    }
+
+    fn prompt(category: &str, arguments: Vec<PromptArgument>, content: &str) -> Prompt {
+        Prompt {
+            name: "review".to_string(),
+            scope: PromptScope::User,
+            description: "Review code".to_string(),
+            category: category.to_string(),
+            allowed_tools: vec!["Read".to_string()],
+            arguments,
+            content: content.to_string(),
+            file_path: PathBuf::from("review.md"),
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn single_argument_becomes_arguments_placeholder() {
+        let source = prompt("general", vec![argument("file", ArgumentType::String, true)], "Review $1 and {{file}}, cost $5");
+        let (name, input, notes) = prompt_to_command(&source);
+        assert_eq!(name, "review");
+        assert_eq!(input.content, "Review $ARGUMENTS and $ARGUMENTS, cost $5");
+        assert_eq!(input.argument_hint.as_deref(), Some("<file>"));
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn escaped_dollars_are_not_converted_to_placeholders() {
+        let source = prompt("general", vec![argument("file", ArgumentType::String, true)], "Review $1, costs $$1 or $$");
+        let (_, input, notes) = prompt_to_command(&source);
+        // `$$1` 是字面量 `$1`，不能变成 `$$ARGUMENTS`；Claude 没有转义，只能提示
+        assert_eq!(input.content, "Review $ARGUMENTS, costs $1 or $");
+        let fields: Vec<(&str, NoteKind)> = notes.iter().map(|n| (n.field.as_str(), n.kind)).collect();
+        assert_eq!(fields, [("content", NoteKind::Approximated)]);
+
+        let command = ClaudeCommand {
+            name: "price".to_string(),
+            namespace: None,
+            scope: PromptScope::User,
+            description: None,
+            argument_hint: None,
+            model: None,
+            allowed_tools: Vec::new(),
+            content: "costs $$ total".to_string(),
+            file_path: PathBuf::from("price.md"),
+            updated_at: 0,
+        };
+        assert_eq!(command_to_prompt(&command).1.content, "costs $$$$ total");
+    }
+
+    #[test]
+    fn category_maps_to_namespace_and_lossy_fields_are_reported() {
+        let source = prompt(
+            "backend",
+            vec![argument("path", ArgumentType::String, true), argument("depth", ArgumentType::Number, false)],
+            "{{path}} to depth {{depth}} ({{unknown}})",
+        );
+        let (name, input, notes) = prompt_to_command(&source);
+        assert_eq!(name, "backend:review");
+        assert_eq!(input.content, "$1 to depth $2 ({{unknown}})");
+        assert_eq!(input.argument_hint.as_deref(), Some("<path> [depth]"));
+        let fields: Vec<(&str, NoteKind)> = notes.iter().map(|n| (n.field.as_str(), n.kind)).collect();
+        assert_eq!(fields, [("arguments.depth", NoteKind::Dropped), ("content", NoteKind::Approximated)]);
+    }
+
+    #[test]
+    fn command_converts_back_with_hint_arguments() {
+        let command = ClaudeCommand {
+            name: "git:commit".to_string(),
+            namespace: Some("git".to_string()),
+            scope: PromptScope::User,
+            description: Some("Commit".to_string()),
+            argument_hint: Some("<message> [scope]".to_string()),
+            model: Some("haiku".to_string()),
+            allowed_tools: vec!["Bash(git commit:*)".to_string()],
+            content: "!`git status`\nCommit with $ARGUMENTS".to_string(),
+            file_path: PathBuf::from("commit.md"),
+            updated_at: 0,
+        };
+        let (name, input, notes) = command_to_prompt(&command);
+        assert_eq!(name, "commit");
+        assert_eq!(input.category.as_deref(), Some("git"));
+        let arguments: Vec<(&str, bool)> = input.arguments.iter().map(|a| (a.name.as_str(), a.required)).collect();
+        assert_eq!(arguments, [("message", true), ("scope", false)]);
+        assert_eq!(input.content, "!`git status`\nCommit with {{message}} {{scope}}");
+        let fields: Vec<&str> = notes.iter().map(|n| n.field.as_str()).collect();
+        assert_eq!(fields, ["model", "content", "argument-hint", "content"]);
+    }
+
+    #[test]
+    fn positional_placeholders_become_named_arguments() {
+        let command = ClaudeCommand {
+            name: "review".to_string(),
+            namespace: None,
+            scope: PromptScope::User,
+            description: None,
+            argument_hint: Some("<file>".to_string()),
+            model: None,
+            allowed_tools: Vec::new(),
+            content: "Review $ARGUMENTS ($1), not $2, cost $5".to_string(),
+            file_path: PathBuf::from("review.md"),
+            updated_at: 0,
+        };
+        let (_, input, notes) = command_to_prompt(&command);
+        assert_eq!(input.content, "Review {{file}} ({{file}}), not $2, cost $5");
+        let fields: Vec<(&str, NoteKind)> = notes.iter().map(|n| (n.field.as_str(), n.kind)).collect();
+        assert_eq!(fields, [("argument-hint", NoteKind::Approximated), ("content", NoteKind::Approximated)]);
+
+        // 转换回 Claude 命令后占位符与原文一致
+        let source = prompt("general", input.arguments.clone(), &input.content);
+        assert_eq!(prompt_to_command(&source).1.content, "Review $ARGUMENTS ($ARGUMENTS), not $2, cost $5");
+    }
+
+    #[test]
+    fn dry_run_reports_diff_without_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let commands = ClaudeCommandStore::new(dir.path().join("commands"), PromptScope::Project);
+        let source = prompt("general", Vec::new(), "Do the review");
+
+        let plan = sync_prompt(&source, &commands, false).unwrap();
+        assert_eq!(plan.action, SyncAction::Create);
+        assert!(plan.diff.contains("+Do the review"));
+        assert!(!plan.target_path.exists());
+
+        assert!(sync_prompt(&source, &commands, true).unwrap().applied);
+        let again = sync_prompt(&source, &commands, false).unwrap();
+        assert_eq!(again.action, SyncAction::Unchanged);
+        assert_eq!(again.diff, "");
+    }
+
+    #[test]
+    fn update_keeps_target_only_fields_and_reports_overwrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let commands = ClaudeCommandStore::new(dir.path().join("commands"), PromptScope::Project);
+        let existing = ClaudeCommandInput {
+            description: Some("Old description".to_string()),
+            model: Some("haiku".to_string()),
+            allowed_tools: vec!["Bash".to_string()],
+            content: "Old body".to_string(),
+            ..Default::default()
+        };
+        commands.create("review", &existing).unwrap();
+
+        let plan = sync_prompt(&prompt("general", Vec::new(), "New body"), &commands, true).unwrap();
+        assert_eq!(plan.action, SyncAction::Update);
+        let fields: Vec<(&str, NoteKind)> = plan.notes.iter().map(|n| (n.field.as_str(), n.kind)).collect();
+        assert_eq!(fields, [("description", NoteKind::Dropped), ("allowed-tools", NoteKind::Dropped)]);
+        let written = commands.get("review").unwrap();
+        assert_eq!(written.model.as_deref(), Some("haiku"));
+        assert_eq!(written.content, "New body");
+
+        let prompts = PromptStore::new(dir.path().join("prompts"), PromptScope::Project);
+        let mut depth = argument("depth", ArgumentType::Number, false);
+        depth.default_value = Some("2".to_string());
+        let typed = PromptInput {
+            arguments: vec![depth, argument("extra", ArgumentType::String, false)],
+            content: "{{depth}} {{extra}}".to_string(),
+            ..Default::default()
+        };
+        prompts.create("scan", &typed).unwrap();
+        let command = ClaudeCommand {
+            name: "scan".to_string(),
+            namespace: None,
+            scope: PromptScope::User,
+            description: None,
+            argument_hint: Some("[depth]".to_string()),
+            model: None,
+            allowed_tools: Vec::new(),
+            content: "Scan to $1".to_string(),
+            file_path: PathBuf::from("scan.md"),
+            updated_at: 0,
+        };
+        let plan = sync_command(&command, &prompts, true).unwrap();
+        assert!(plan.notes.iter().any(|n| n.field == "arguments.extra" && n.kind == NoteKind::Dropped));
+        let written = prompts.get("scan").unwrap();
+        assert_eq!(written.arguments.len(), 1);
+        assert!(written.arguments[0].arg_type == ArgumentType::Number);
+        assert_eq!(written.arguments[0].default_value.as_deref(), Some("2"));
+    }
+}
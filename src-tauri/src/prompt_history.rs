@@ -0,0 +1,463 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+use similar::TextDiff;
+use tauri::AppHandle;
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::file_watcher::{ChangeKind, ChangeOrigin, ChangeTarget, FileChange, AGENT_FILE_NAMES, CONFIG_FILE_NAMES};
+use crate::i18n::tf;
+use crate::process::now_millis;
+use crate::prompt_store::{check_unchanged, modified_millis, write_atomic};
+use crate::resource_manager::PathProvider;
+
+/// 同一时刻只允许一个线程读写历史日志（命令线程和文件监听线程都会记录版本）
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// diff 中变更行前后保留的上下文行数
+const DIFF_CONTEXT: usize = 3;
+
+/// 版本的来源
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionSource {
+    /// 在应用内保存或恢复
+    Ui,
+    /// 在应用外被编辑
+    External,
+}
+
+/// 文件的一个历史版本；内容按 SHA-256 存放在 `objects` 目录中
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    /// 文件内递增的版本号，从 1 开始
+    pub id: u64,
+    pub hash: String,
+    /// Unix 毫秒
+    pub timestamp: u64,
+    pub source: VersionSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub size: u64,
+}
+
+/// 单个文件的历史日志
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct HistoryLog {
+    path: PathBuf,
+    versions: Vec<Version>,
+}
+
+/// 两个版本之间的 unified diff
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionDiff {
+    /// 为空表示磁盘上的当前内容
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    pub unified: String,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn io_error(key: &str, e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf(key, &[("error", &e)]), path)
+}
+
+/// 按行比较两段文本，返回 unified diff 以及新增和删除的行数
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> (String, usize, usize) {
+    let diff = TextDiff::from_lines(old, new);
+    let (mut additions, mut deletions) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => additions += 1,
+            similar::ChangeTag::Delete => deletions += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+    let unified = diff
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT)
+        .header(old_label, new_label)
+        .to_string();
+    (unified, additions, deletions)
+}
+
+/// 内容寻址的版本历史
+///
+/// 布局为 `objects/<hash 前两位>/<hash>` 存放内容，`logs/<路径的 hash>.json` 存放每个文件的版本列表；
+/// 相同内容只保存一份。
+pub struct HistoryStore {
+    root: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// `<app_data>/history`
+    pub fn for_app(paths: &impl PathProvider) -> AppResult<Self> {
+        paths
+            .app_data_dir()
+            .map(|dir| Self::new(dir.join("history")))
+            .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))
+    }
+
+    fn log_path(&self, file: &Path) -> PathBuf {
+        let key = sha256_hex(file.to_string_lossy().as_bytes());
+        self.root.join("logs").join(format!("{}.json", key))
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.root.join("objects").join(&hash[..2]).join(hash)
+    }
+
+    fn read_log(&self, file: &Path) -> AppResult<HistoryLog> {
+        let path = self.log_path(file);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| io_error("error.readFile", e, &path)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(HistoryLog { path: file.to_path_buf(), versions: Vec::new() })
+            }
+            Err(e) => Err(io_error("error.readFile", e, &path)),
+        }
+    }
+
+    fn write_log(&self, log: &HistoryLog) -> AppResult<()> {
+        let path = self.log_path(&log.path);
+        let text = serde_json::to_string_pretty(log).map_err(|e| io_error("error.writeFile", e, &path))?;
+        write_atomic(&path, text.as_bytes()).map_err(|e| io_error("error.writeFile", e, &path))
+    }
+
+    /// 文件的所有版本，最新的在前
+    pub fn versions(&self, file: &Path) -> AppResult<Vec<Version>> {
+        let _guard = HISTORY_LOCK.lock().map_err(|_| AppError::lock())?;
+        let mut versions = self.read_log(file)?.versions;
+        versions.reverse();
+        Ok(versions)
+    }
+
+    /// 记录一个新版本；内容与最新版本相同时不记录并返回 `None`
+    pub fn record(&self, file: &Path, content: &[u8], source: VersionSource, message: Option<String>) -> AppResult<Option<Version>> {
+        let _guard = HISTORY_LOCK.lock().map_err(|_| AppError::lock())?;
+        self.record_locked(file, content, source, message)
+    }
+
+    /// `record` 的实现，调用方需持有 `HISTORY_LOCK`
+    fn record_locked(&self, file: &Path, content: &[u8], source: VersionSource, message: Option<String>) -> AppResult<Option<Version>> {
+        let mut log = self.read_log(file)?;
+        let hash = sha256_hex(content);
+        if log.versions.last().is_some_and(|v| v.hash == hash) {
+            return Ok(None);
+        }
+
+        let object = self.object_path(&hash);
+        if !object.exists() {
+            write_atomic(&object, content).map_err(|e| io_error("error.writeFile", e, &object))?;
+        }
+        let version = Version {
+            id: log.versions.last().map_or(1, |v| v.id + 1),
+            hash,
+            timestamp: now_millis(),
+            source,
+            message: message.filter(|m| !m.trim().is_empty()),
+            size: content.len() as u64,
+        };
+        log.versions.push(version.clone());
+        self.write_log(&log)?;
+        Ok(Some(version))
+    }
+
+    /// 在应用覆盖文件前调用：文件还没有历史时先把磁盘上的原始内容记为外部版本，避免第一次覆盖丢失内容
+    pub fn snapshot_existing(&self, file: &Path) -> AppResult<()> {
+        let Ok(content) = std::fs::read(file) else {
+            return Ok(());
+        };
+        // 检查和记录在同一把锁内完成，避免与监听线程的记录交错而重复记录
+        let _guard = HISTORY_LOCK.lock().map_err(|_| AppError::lock())?;
+        if self.read_log(file)?.versions.is_empty() {
+            self.record_locked(file, &content, VersionSource::External, None)?;
+        }
+        Ok(())
+    }
+
+    /// 在应用内保存文件：先保留磁盘上的原始内容，再原子写入并记为应用内版本
+    pub fn save(&self, file: &Path, content: &[u8], message: Option<String>) -> AppResult<Option<Version>> {
+        self.snapshot_existing(file)?;
+        write_atomic(file, content).map_err(|e| io_error("error.writeFile", e, file))?;
+        self.record(file, content, VersionSource::Ui, message)
+    }
+
+    /// 文件重命名后把历史一起迁移到新路径
+    pub fn rename(&self, from: &Path, to: &Path) -> AppResult<()> {
+        let _guard = HISTORY_LOCK.lock().map_err(|_| AppError::lock())?;
+        let mut log = self.read_log(from)?;
+        if log.versions.is_empty() {
+            return Ok(());
+        }
+        log.path = to.to_path_buf();
+        self.write_log(&log)?;
+        let old = self.log_path(from);
+        std::fs::remove_file(&old).map_err(|e| io_error("error.writeFile", e, &old))
+    }
+
+    /// 读取某个版本的内容
+    pub fn content(&self, file: &Path, id: u64) -> AppResult<Vec<u8>> {
+        let version = self
+            .versions(file)?
+            .into_iter()
+            .find(|v| v.id == id)
+            .ok_or_else(|| {
+                AppError::new(ErrorCode::NotFound, tf("error.historyVersionNotFound", &[("version", &id)])).with_path(file)
+            })?;
+        let object = self.object_path(&version.hash);
+        std::fs::read(&object).map_err(|e| io_error("error.readFile", e, &object))
+    }
+
+    fn text_of(&self, file: &Path, id: Option<u64>) -> AppResult<String> {
+        let bytes = match id {
+            Some(id) => self.content(file, id)?,
+            None => std::fs::read(file).map_err(|e| io_error("error.readFile", e, file))?,
+        };
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// 两个版本之间的 unified diff；版本为空时使用磁盘上的当前内容
+    pub fn diff(&self, file: &Path, from: Option<u64>, to: Option<u64>) -> AppResult<VersionDiff> {
+        let old = self.text_of(file, from)?;
+        let new = self.text_of(file, to)?;
+        let label = |id: Option<u64>| id.map_or_else(|| "current".to_string(), |id| format!("v{}", id));
+
+        let (unified, additions, deletions) = unified_diff(&old, &new, &label(from), &label(to));
+        Ok(VersionDiff { from, to, unified, additions, deletions })
+    }
+
+    /// 把文件恢复到某个版本，并把恢复后的内容记为新版本
+    pub fn restore(&self, file: &Path, id: u64) -> AppResult<Version> {
+        let content = self.content(file, id)?;
+        if let Ok(current) = std::fs::read(file) {
+            // 当前内容与最新版本不同（例如外部修改尚未被记录）时先保存，恢复不应丢失任何内容
+            self.record(file, &current, VersionSource::External, None)?;
+        }
+        write_atomic(file, &content).map_err(|e| io_error("error.writeFile", e, file))?;
+        let message = tf("history.restoredFrom", &[("version", &id)]);
+        let recorded = self.record(file, &content, VersionSource::Ui, Some(message))?;
+        match recorded {
+            Some(version) => Ok(version),
+            // 内容与最新版本相同，返回最新版本
+            None => self.versions(file)?.into_iter().next().ok_or_else(|| {
+                AppError::new(ErrorCode::NotFound, tf("error.historyVersionNotFound", &[("version", &id)]))
+            }),
+        }
+    }
+}
+
+/// 记录文件监听发现的外部修改；删除不产生版本，历史会保留以便恢复
+pub fn record_changes(app_handle: &AppHandle, changes: &[FileChange]) {
+    let external = changes.iter().filter(|c| {
+        c.origin == ChangeOrigin::External
+            && c.change != ChangeKind::Removed
+            && matches!(c.target, ChangeTarget::Prompt | ChangeTarget::ClaudeCommand | ChangeTarget::AgentFile)
+    });
+    let store = match HistoryStore::for_app(app_handle) {
+        Ok(store) => store,
+        Err(e) => return e.report(app_handle),
+    };
+    for change in external {
+        let Ok(content) = std::fs::read(&change.path) else {
+            continue;
+        };
+        if let Err(e) = store.record(&change.path, &content, VersionSource::External, None) {
+            e.context(tf("history.recordFailed", &[("path", &change.path.display())])).report(app_handle);
+        }
+    }
+}
+
+/// 应用内编辑的文件内容和读取时的修改时间；保存时把 `updatedAt` 作为 `expectedUpdatedAt` 传回
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContent {
+    pub content: String,
+    pub updated_at: u64,
+}
+
+/// 只允许读写 `allowed` 中的文件名，这些命令不能用来访问任意文件
+fn check_file_name(path: &Path, allowed: &[&str], key: &str) -> AppResult<()> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    if allowed.contains(&file_name.as_str()) {
+        Ok(())
+    } else {
+        Err(AppError::new(ErrorCode::InvalidArgument, tf(key, &[("name", &file_name)])).with_path(path))
+    }
+}
+
+fn current_modified(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|meta| modified_millis(&meta)).unwrap_or(0)
+}
+
+/// 先取修改时间再读内容：两者之间发生的修改会使保存时的冲突检查失败，而不是被覆盖
+fn read_file_content(path: &Path) -> AppResult<FileContent> {
+    let updated_at = current_modified(path);
+    let content = std::fs::read_to_string(path).map_err(|e| io_error("error.readFile", e, path))?;
+    Ok(FileContent { content, updated_at })
+}
+
+/// Tauri 命令：读取 AGENTS.md、CLAUDE.md 等说明文件
+#[tauri::command]
+pub async fn read_agent_file(path: String) -> AppResult<FileContent> {
+    let path = PathBuf::from(path);
+    check_file_name(&path, AGENT_FILE_NAMES, "error.notAgentFile")?;
+    read_file_content(&path)
+}
+
+/// Tauri 命令：保存 AGENTS.md、CLAUDE.md 等说明文件，覆盖前后的内容都记入版本历史，返回保存后的修改时间
+///
+/// 给出 `expectedUpdatedAt` 时，文件在读取后被外部修改（或在新建前被创建，此时传 0）会拒绝覆盖。
+#[tauri::command(rename_all = "camelCase")]
+pub async fn save_agent_file(app_handle: AppHandle, path: String, content: String, expected_updated_at: Option<u64>) -> AppResult<u64> {
+    let path = PathBuf::from(path);
+    check_file_name(&path, AGENT_FILE_NAMES, "error.notAgentFile")?;
+    if let Some(expected) = expected_updated_at {
+        check_unchanged(&path, expected)?;
+    }
+    HistoryStore::for_app(&app_handle)?.save(&path, content.as_bytes(), None)?;
+    Ok(current_modified(&path))
+}
+
+/// Tauri 命令：读取 config.toml 等配置文件
+#[tauri::command]
+pub async fn read_config_file(path: String) -> AppResult<FileContent> {
+    let path = PathBuf::from(path);
+    check_file_name(&path, CONFIG_FILE_NAMES, "error.notConfigFile")?;
+    read_file_content(&path)
+}
+
+/// Tauri 命令：保存 config.toml 等配置文件，返回保存后的修改时间；冲突检查与 `save_agent_file` 相同
+#[tauri::command(rename_all = "camelCase")]
+pub async fn save_config_file(path: String, content: String, expected_updated_at: Option<u64>) -> AppResult<u64> {
+    let path = PathBuf::from(path);
+    check_file_name(&path, CONFIG_FILE_NAMES, "error.notConfigFile")?;
+    if let Some(expected) = expected_updated_at {
+        check_unchanged(&path, expected)?;
+    }
+    write_atomic(&path, content.as_bytes()).map_err(|e| io_error("error.writeFile", e, &path))?;
+    Ok(current_modified(&path))
+}
+
+/// Tauri 命令：列出文件的历史版本，最新的在前
+#[tauri::command]
+pub async fn list_versions(app_handle: AppHandle, path: String) -> AppResult<Vec<Version>> {
+    HistoryStore::for_app(&app_handle)?.versions(Path::new(&path))
+}
+
+/// Tauri 命令：比较两个版本；`from` 或 `to` 为空时表示当前文件内容
+#[tauri::command]
+pub async fn diff_versions(app_handle: AppHandle, path: String, from: Option<u64>, to: Option<u64>) -> AppResult<VersionDiff> {
+    HistoryStore::for_app(&app_handle)?.diff(Path::new(&path), from, to)
+}
+
+/// Tauri 命令：把文件恢复到指定版本
+#[tauri::command]
+pub async fn restore_version(app_handle: AppHandle, path: String, version: u64) -> AppResult<Version> {
+    HistoryStore::for_app(&app_handle)?.restore(Path::new(&path), version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> (tempfile::TempDir, HistoryStore, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().join("history"));
+        let file = dir.path().join("prompts").join("review.md");
+        (dir, store, file)
+    }
+
+    fn save(store: &HistoryStore, file: &Path, text: &str) -> Option<Version> {
+        write_atomic(file, text.as_bytes()).unwrap();
+        store.record(file, text.as_bytes(), VersionSource::Ui, None).unwrap()
+    }
+
+    #[test]
+    fn records_versions_and_skips_unchanged_content() {
+        let (_dir, store, file) = fixture();
+        assert_eq!(save(&store, &file, "one\n").map(|v| v.id), Some(1));
+        assert!(save(&store, &file, "one\n").is_none());
+        assert_eq!(save(&store, &file, "two\n").map(|v| v.id), Some(2));
+
+        let ids: Vec<u64> = store.versions(&file).unwrap().iter().map(|v| v.id).collect();
+        assert_eq!(ids, [2, 1]);
+        assert_eq!(store.content(&file, 1).unwrap(), b"one\n");
+    }
+
+    #[test]
+    fn snapshots_existing_file_before_first_overwrite() {
+        let (_dir, store, file) = fixture();
+        write_atomic(&file, b"hand written\n").unwrap();
+        store.save(&file, b"from ui\n", None).unwrap();
+
+        let versions = store.versions(&file).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions[0].source == VersionSource::Ui);
+        assert_eq!(std::fs::read(&file).unwrap(), b"from ui\n");
+        assert!(versions[1].source == VersionSource::External);
+        assert_eq!(store.content(&file, versions[1].id).unwrap(), b"hand written\n");
+    }
+
+    #[test]
+    fn diffs_versions_against_each_other_and_current_file() {
+        let (_dir, store, file) = fixture();
+        save(&store, &file, "a\nb\nc\n");
+        save(&store, &file, "a\nB\nc\nd\n");
+
+        let diff = store.diff(&file, Some(1), Some(2)).unwrap();
+        assert_eq!((diff.additions, diff.deletions), (2, 1));
+        assert!(diff.unified.contains("--- v1"));
+        assert!(diff.unified.contains("-b\n+B\n"));
+
+        write_atomic(&file, b"a\nB\nc\nd\n").unwrap();
+        assert_eq!(store.diff(&file, Some(2), None).unwrap().unified, "");
+    }
+
+    #[test]
+    fn restore_keeps_unrecorded_external_edits() {
+        let (_dir, store, file) = fixture();
+        save(&store, &file, "first\n");
+        save(&store, &file, "second\n");
+        write_atomic(&file, b"edited elsewhere\n").unwrap();
+
+        let restored = store.restore(&file, 1).unwrap();
+        assert_eq!(std::fs::read(&file).unwrap(), b"first\n");
+        assert_eq!(restored.id, 4);
+
+        let versions = store.versions(&file).unwrap();
+        assert_eq!(store.content(&file, versions[1].id).unwrap(), b"edited elsewhere\n");
+        assert!(store.content(&file, 9).is_err());
+    }
+
+    #[test]
+    fn file_commands_only_accept_known_file_names() {
+        assert!(check_file_name(Path::new("/work/app/AGENTS.md"), AGENT_FILE_NAMES, "error.notAgentFile").is_ok());
+        assert!(check_file_name(Path::new("/home/u/.codex/config.toml"), CONFIG_FILE_NAMES, "error.notConfigFile").is_ok());
+        let error = check_file_name(Path::new("/home/u/.ssh/authorized_keys"), AGENT_FILE_NAMES, "error.notAgentFile").unwrap_err();
+        assert_eq!(error.code, ErrorCode::InvalidArgument);
+    }
+
+    #[test]
+    fn rename_moves_history() {
+        let (dir, store, file) = fixture();
+        save(&store, &file, "text\n");
+        let renamed = dir.path().join("prompts").join("renamed.md");
+        store.rename(&file, &renamed).unwrap();
+
+        assert!(store.versions(&file).unwrap().is_empty());
+        assert_eq!(store.versions(&renamed).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,621 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use crate::claude_commands::{validate_command_name, CommandFrontmatter};
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::tf;
+use crate::prompt_history::{HistoryStore, VersionSource};
+use crate::prompt_store::{
+    check_unchanged, parse_document, render_document, split_frontmatter, validate_name, write_atomic, ArgumentType,
+    PromptArgument, PromptFrontmatter, PromptScope,
+};
+use crate::prompt_template::{tokenize, Token};
+use crate::search_index::{document_name, markdown_files, DocumentKind};
+
+/// 正文超过该字节数时提示拆分
+pub const OVERSIZED_BYTES: usize = 32 * 1024;
+
+/// Codex CLI 的内置斜杠命令，同名的自定义提示词无法调用
+const CODEX_BUILTIN_COMMANDS: &[&str] = &[
+    "approvals", "compact", "diff", "exit", "feedback", "init", "logout", "mcp", "mention", "model", "new", "quit",
+    "review", "status", "undo",
+];
+
+/// Claude Code 的内置斜杠命令
+const CLAUDE_BUILTIN_COMMANDS: &[&str] = &[
+    "add-dir", "agents", "bashes", "bug", "clear", "compact", "config", "context", "cost", "doctor", "exit", "export",
+    "help", "hooks", "ide", "init", "install-github-app", "login", "logout", "mcp", "memory", "model", "output-style",
+    "permissions", "pr-comments", "privacy-settings", "release-notes", "resume", "review", "rewind", "security-review",
+    "status", "statusline", "terminal-setup", "todos", "upgrade", "usage", "vim",
+];
+
+/// 规则 ID，前端据此过滤和显示文档
+pub mod rules {
+    pub const INVALID_NAME: &str = "invalid-name";
+    pub const DUPLICATE_NAME: &str = "duplicate-name";
+    pub const BUILTIN_COLLISION: &str = "builtin-collision";
+    pub const UNCLOSED_FRONTMATTER: &str = "unclosed-frontmatter";
+    pub const INVALID_FRONTMATTER: &str = "invalid-frontmatter";
+    pub const MISSING_DESCRIPTION: &str = "missing-description";
+    pub const DUPLICATE_ARGUMENT: &str = "duplicate-argument";
+    pub const INVALID_DEFAULT: &str = "invalid-default";
+    pub const UNUSED_ARGUMENT: &str = "unused-argument";
+    pub const UNDEFINED_PLACEHOLDER: &str = "undefined-placeholder";
+    pub const UNCLOSED_PLACEHOLDER: &str = "unclosed-placeholder";
+    pub const EMPTY_CONTENT: &str = "empty-content";
+    pub const OVERSIZED_PROMPT: &str = "oversized-prompt";
+    pub const UNREADABLE_FILE: &str = "unreadable-file";
+}
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// 修复建议；`replacement` 为修复后的完整文件内容，只给出标题的建议需要手动处理
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickFix {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement: Option<String>,
+}
+
+/// 一条检查结果；行列从 1 开始，列按字符计
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub kind: DocumentKind,
+    pub name: String,
+    pub file_path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    /// 相关的参数名或占位符，用于区分同一文件中同一规则的多条结果
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<QuickFix>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub files_checked: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// 重新转义 frontmatter 中 `key: "..."` 形式、值里含有未转义引号的行
+///
+/// 旧版前端直接把描述拼进双引号，描述中的引号会破坏 YAML。无法修复时返回 `None`。
+fn requote_frontmatter(yaml: &str) -> Option<String> {
+    let mut changed = false;
+    let mut lines: Vec<String> = yaml
+        .lines()
+        .map(|line| {
+            let Some((key, value)) = line.split_once(": ") else {
+                return line.to_string();
+            };
+            let value = value.trim_end();
+            let quoted = value.len() >= 2 && value.starts_with('"') && value.ends_with('"');
+            let plain_key = !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+            if quoted && plain_key && serde_yaml::from_str::<serde_yaml::Value>(line).is_err() {
+                changed = true;
+                let inner = &value[1..value.len() - 1];
+                // JSON 字符串同时是合法的 YAML 双引号字符串
+                format!("{}: {}", key, serde_json::Value::String(inner.to_string()))
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !changed {
+        return None;
+    }
+    if yaml.ends_with('\n') {
+        lines.push(String::new());
+    }
+    let fixed = lines.join("\n");
+    serde_yaml::from_str::<serde_yaml::Value>(&fixed).ok().map(|_| fixed)
+}
+
+/// 不能作为名称的字符替换为 `-`
+fn sanitize_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':') { c } else { '-' })
+        .collect();
+    sanitized.trim_start_matches(['.', '-']).to_string()
+}
+
+fn default_matches(argument: &PromptArgument, value: &str) -> bool {
+    match argument.arg_type {
+        ArgumentType::String => true,
+        ArgumentType::Number => value.trim().parse::<f64>().is_ok_and(f64::is_finite),
+        ArgumentType::Boolean => {
+            matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "false" | "yes" | "no" | "on" | "off" | "1" | "0")
+        }
+    }
+}
+
+/// 收集单个文件的检查结果
+struct Linter<'a> {
+    kind: DocumentKind,
+    name: &'a str,
+    path: &'a Path,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Linter<'_> {
+    fn push(&mut self, rule: &'static str, severity: Severity, key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> &mut Diagnostic {
+        self.diagnostics.push(Diagnostic {
+            rule,
+            severity,
+            message: tf(key, args),
+            kind: self.kind,
+            name: self.name.to_string(),
+            file_path: self.path.to_path_buf(),
+            line: None,
+            column: None,
+            target: None,
+            fix: None,
+        });
+        self.diagnostics.last_mut().expect("just pushed")
+    }
+
+    fn check_name(&mut self) {
+        let valid = match self.kind {
+            DocumentKind::ClaudeCommand => validate_command_name(self.name).is_ok(),
+            _ => validate_name(self.name).is_ok(),
+        };
+        if !valid {
+            let suggestion = sanitize_name(self.name);
+            let name = self.name;
+            let diagnostic = self.push(rules::INVALID_NAME, Severity::Error, "lint.invalidName", &[("name", &name)]);
+            if !suggestion.is_empty() {
+                diagnostic.fix = Some(QuickFix { title: tf("lint.fix.rename", &[("name", &suggestion)]), replacement: None });
+            }
+        }
+
+        let builtins = match self.kind {
+            DocumentKind::Prompt => CODEX_BUILTIN_COMMANDS,
+            DocumentKind::ClaudeCommand => CLAUDE_BUILTIN_COMMANDS,
+            DocumentKind::AgentFile => return,
+        };
+        if builtins.contains(&self.name) {
+            let suggestion = format!("{}-custom", self.name);
+            let name = self.name;
+            self.push(rules::BUILTIN_COLLISION, Severity::Warning, "lint.builtinCollision", &[("name", &name)]).fix =
+                Some(QuickFix { title: tf("lint.fix.rename", &[("name", &suggestion)]), replacement: None });
+        }
+    }
+
+    /// frontmatter 无法解析；能通过重新转义修复时附带修复结果
+    fn invalid_frontmatter(&mut self, text: &str, yaml: Option<&str>, error: &serde_yaml::Error) {
+        let fix = yaml.and_then(|yaml| {
+            let fixed = requote_frontmatter(yaml)?;
+            let start = text.find(yaml)?;
+            let replacement = format!("{}{}{}", &text[..start], fixed, &text[start + yaml.len()..]);
+            Some(QuickFix { title: tf("lint.fix.quoteValues", &[]), replacement: Some(replacement) })
+        });
+        // frontmatter 从第 2 行开始
+        let line = error.location().map(|location| location.line() + 1);
+        let diagnostic = self.push(rules::INVALID_FRONTMATTER, Severity::Error, "lint.invalidFrontmatter", &[("error", error)]);
+        diagnostic.line = line;
+        diagnostic.fix = fix;
+    }
+
+    /// 检查提示词声明的参数与正文占位符是否一致
+    fn check_arguments(&mut self, frontmatter: &PromptFrontmatter, body: &str, body_line: usize) {
+        let arguments = &frontmatter.arguments;
+        let placeholders = tokenize(body);
+        let uses_all = placeholders.iter().any(|span| span.token == Token::All);
+        let with_arguments = |arguments: Vec<PromptArgument>| {
+            let frontmatter = PromptFrontmatter { arguments, ..frontmatter.clone() };
+            render_document(&frontmatter, body).ok()
+        };
+
+        for (i, argument) in arguments.iter().enumerate() {
+            let name = argument.name.as_str();
+            if arguments[..i].iter().any(|a| a.name == name) {
+                let mut kept = arguments.clone();
+                kept.remove(i);
+                let diagnostic = self.push(rules::DUPLICATE_ARGUMENT, Severity::Error, "lint.duplicateArgument", &[("name", &name)]);
+                diagnostic.target = Some(name.to_string());
+                diagnostic.fix = Some(QuickFix {
+                    title: tf("lint.fix.removeDuplicate", &[("name", &name)]),
+                    replacement: with_arguments(kept),
+                });
+                continue;
+            }
+            if let Some(value) = argument.default_value.as_deref().filter(|v| !v.is_empty() && !default_matches(argument, v)) {
+                let mut fixed = arguments.clone();
+                fixed[i].default_value = None;
+                let arg_type = serde_json::to_value(argument.arg_type)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                let diagnostic = self.push(
+                    rules::INVALID_DEFAULT,
+                    Severity::Error,
+                    "lint.invalidDefault",
+                    &[("name", &name), ("value", &value), ("type", &arg_type)],
+                );
+                diagnostic.target = Some(name.to_string());
+                diagnostic.fix = Some(QuickFix {
+                    title: tf("lint.fix.removeDefault", &[("name", &name)]),
+                    replacement: with_arguments(fixed),
+                });
+            }
+            let used = uses_all
+                || placeholders.iter().any(|span| match span.token {
+                    Token::Positional(n) => n == i + 1,
+                    Token::Named(n) => n == name,
+                    _ => false,
+                });
+            if !used {
+                let mut kept = arguments.clone();
+                kept.remove(i);
+                let diagnostic = self.push(rules::UNUSED_ARGUMENT, Severity::Warning, "lint.unusedArgument", &[("name", &name)]);
+                diagnostic.target = Some(name.to_string());
+                diagnostic.fix = Some(QuickFix {
+                    title: tf("lint.fix.removeArgument", &[("name", &name)]),
+                    replacement: with_arguments(kept),
+                });
+            }
+        }
+
+        let mut reported: Vec<&str> = Vec::new();
+        for span in &placeholders {
+            let Token::Named(name) = span.token else {
+                continue;
+            };
+            if arguments.iter().any(|a| a.name == name) || reported.contains(&name) {
+                continue;
+            }
+            reported.push(name);
+            let mut declared = arguments.clone();
+            declared.push(PromptArgument {
+                name: name.to_string(),
+                arg_type: ArgumentType::String,
+                required: true,
+                description: None,
+                default_value: None,
+            });
+            let text = format!("{{{{{}}}}}", name);
+            let diagnostic = self.push(
+                rules::UNDEFINED_PLACEHOLDER,
+                Severity::Error,
+                "template.undefinedPlaceholder",
+                &[("placeholder", &text)],
+            );
+            diagnostic.line = Some(body_line + span.line - 1);
+            diagnostic.column = Some(span.column);
+            diagnostic.target = Some(name.to_string());
+            diagnostic.fix = Some(QuickFix {
+                title: tf("lint.fix.declareArgument", &[("name", &name)]),
+                replacement: with_arguments(declared),
+            });
+        }
+    }
+
+    /// Claude 命令不展开 `{{name}}`，只能使用 `$1`..`$9` 和 `$ARGUMENTS`
+    fn check_command_placeholders(&mut self, body: &str, body_line: usize) {
+        for span in tokenize(body) {
+            let Token::Named(name) = span.token else {
+                continue;
+            };
+            let text = format!("{{{{{}}}}}", name);
+            let diagnostic = self.push(
+                rules::UNDEFINED_PLACEHOLDER,
+                Severity::Warning,
+                "lint.commandPlaceholder",
+                &[("placeholder", &text)],
+            );
+            diagnostic.line = Some(body_line + span.line - 1);
+            diagnostic.column = Some(span.column);
+            diagnostic.target = Some(name.to_string());
+        }
+    }
+
+    fn check_body(&mut self, body: &str, body_line: usize) {
+        for span in tokenize(body).into_iter().filter(|span| span.token == Token::Unclosed) {
+            let diagnostic = self.push(rules::UNCLOSED_PLACEHOLDER, Severity::Error, "template.unclosedPlaceholder", &[]);
+            diagnostic.line = Some(body_line + span.line - 1);
+            diagnostic.column = Some(span.column);
+        }
+        let trimmed = body.trim();
+        if trimmed.is_empty() {
+            self.push(rules::EMPTY_CONTENT, Severity::Warning, "lint.emptyContent", &[]);
+        } else if trimmed.len() > OVERSIZED_BYTES {
+            let size = trimmed.len().div_ceil(1024);
+            let limit = OVERSIZED_BYTES / 1024;
+            self.push(rules::OVERSIZED_PROMPT, Severity::Warning, "lint.oversizedPrompt", &[("size", &size), ("limit", &limit)]);
+        }
+    }
+}
+
+/// 检查单个提示词或命令文件的内容
+///
+/// 只做与其他文件无关的检查；重名等跨文件的规则由 [`lint_library`] 处理。
+pub fn lint_document(kind: DocumentKind, name: &str, path: &Path, text: &str) -> Vec<Diagnostic> {
+    let mut lint = Linter { kind, name, path, diagnostics: Vec::new() };
+    lint.check_name();
+
+    let (yaml, body) = split_frontmatter(text);
+    let body_line = text[..text.len() - body.len()].matches('\n').count() + 1;
+    let fence = text.trim_start_matches('\u{feff}');
+    if yaml.is_none() && (fence.starts_with("---\n") || fence.starts_with("---\r\n")) {
+        lint.push(rules::UNCLOSED_FRONTMATTER, Severity::Error, "lint.unclosedFrontmatter", &[]).line = Some(1);
+    }
+
+    match kind {
+        DocumentKind::Prompt => match parse_document::<PromptFrontmatter>(text) {
+            Ok((frontmatter, _)) => {
+                if frontmatter.description.as_deref().is_none_or(|d| d.trim().is_empty()) {
+                    lint.push(rules::MISSING_DESCRIPTION, Severity::Info, "lint.missingDescription", &[]);
+                }
+                lint.check_arguments(&frontmatter, body, body_line);
+            }
+            Err(e) => lint.invalid_frontmatter(text, yaml, &e),
+        },
+        DocumentKind::ClaudeCommand => match parse_document::<CommandFrontmatter>(text) {
+            Ok((frontmatter, _)) => {
+                if frontmatter.description.as_deref().is_none_or(|d| d.trim().is_empty()) {
+                    lint.push(rules::MISSING_DESCRIPTION, Severity::Info, "lint.missingDescription", &[]);
+                }
+                lint.check_command_placeholders(body, body_line);
+            }
+            Err(e) => lint.invalid_frontmatter(text, yaml, &e),
+        },
+        DocumentKind::AgentFile => {}
+    }
+    lint.check_body(body, body_line);
+    lint.diagnostics
+}
+
+/// 检查多个根目录下的所有提示词和命令
+///
+/// `roots` 为家目录（用户级）或项目根目录，按优先级从低到高排列；
+/// 同类文档重名时（包括仅大小写不同），后出现的一个报告 `duplicate-name`。
+pub fn lint_library(roots: &[(PathBuf, PromptScope)]) -> LintReport {
+    let mut files = Vec::new();
+    for (base, _) in roots {
+        for (path, name) in markdown_files(&base.join(".codex").join("prompts"), false) {
+            files.push((DocumentKind::Prompt, name, path));
+        }
+        for (path, name) in markdown_files(&base.join(".claude").join("commands"), true) {
+            files.push((DocumentKind::ClaudeCommand, name, path));
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut seen: HashMap<(DocumentKind, String), PathBuf> = HashMap::new();
+    let mut files_checked = 0;
+    for (kind, name, path) in &files {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                let mut lint = Linter { kind: *kind, name, path, diagnostics: Vec::new() };
+                lint.push(rules::UNREADABLE_FILE, Severity::Error, "lint.unreadableFile", &[("error", &e)]);
+                diagnostics.append(&mut lint.diagnostics);
+                continue;
+            }
+        };
+        files_checked += 1;
+        diagnostics.extend(lint_document(*kind, name, path, &text));
+
+        let key = (*kind, name.to_lowercase());
+        if let Some(other) = seen.get(&key) {
+            let mut lint = Linter { kind: *kind, name, path, diagnostics: Vec::new() };
+            lint.push(rules::DUPLICATE_NAME, Severity::Warning, "lint.duplicateName", &[("name", name), ("path", &other.display())]);
+            diagnostics.append(&mut lint.diagnostics);
+        } else {
+            seen.insert(key, path.clone());
+        }
+    }
+
+    diagnostics.sort_by(|a, b| a.file_path.cmp(&b.file_path).then(a.line.cmp(&b.line)).then(a.severity.cmp(&b.severity)));
+    LintReport {
+        files_checked,
+        errors: diagnostics.iter().filter(|d| d.severity == Severity::Error).count(),
+        warnings: diagnostics.iter().filter(|d| d.severity == Severity::Warning).count(),
+        diagnostics,
+    }
+}
+
+/// Tauri 命令：检查用户级以及（给出 `projectPath` 时）项目级的全部提示词和命令
+#[tauri::command(rename_all = "camelCase")]
+pub async fn lint_prompts(app_handle: AppHandle, project_path: Option<String>) -> AppResult<LintReport> {
+    let home = app_handle
+        .path()
+        .home_dir()
+        .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))?;
+    let mut roots = vec![(home, PromptScope::User)];
+    if let Some(project) = project_path {
+        roots.push((PathBuf::from(project), PromptScope::Project));
+    }
+    Ok(lint_library(&roots))
+}
+
+/// 由文件路径得到与 `lint_library` 相同的文档名称
+///
+/// Claude 命令相对所在的 `.claude/commands` 目录命名（`frontend/review.md` 为 `frontend:review`），
+/// 其他文档取文件名。
+fn name_for_path(kind: DocumentKind, path: &Path) -> String {
+    let commands = Path::new(".claude").join("commands");
+    let root = match kind {
+        DocumentKind::ClaudeCommand => path.ancestors().skip(1).find(|dir| dir.ends_with(&commands)),
+        _ => None,
+    };
+    document_name(root.or(path.parent()).unwrap_or(Path::new("")), path)
+}
+
+/// Tauri 命令：应用一条检查结果的自动修复，返回该文件修复后剩余的检查结果
+///
+/// 修复前重新检查文件，只应用仍然存在、且 `rule` 和 `target` 都匹配的修复。
+#[tauri::command(rename_all = "camelCase")]
+pub async fn apply_lint_fix(
+    app_handle: AppHandle,
+    file_path: String,
+    kind: DocumentKind,
+    rule: String,
+    target: Option<String>,
+    expected_updated_at: Option<u64>,
+) -> AppResult<Vec<Diagnostic>> {
+    let path = PathBuf::from(&file_path);
+    if let Some(expected) = expected_updated_at {
+        check_unchanged(&path, expected)?;
+    }
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::io(tf("error.readFile", &[("error", &e)]), &path))?;
+    let name = name_for_path(kind, &path);
+    let replacement = lint_document(kind, &name, &path, &text)
+        .into_iter()
+        .find(|d| d.rule == rule && d.target == target)
+        .and_then(|d| d.fix)
+        .and_then(|fix| fix.replacement)
+        .ok_or_else(|| AppError::new(ErrorCode::InvalidArgument, tf("error.lintFixUnavailable", &[("rule", &rule)])).with_path(&path))?;
+
+    let history = HistoryStore::for_app(&app_handle).ok();
+    if let Some(history) = &history {
+        history.snapshot_existing(&path)?;
+    }
+    write_atomic(&path, replacement.as_bytes())
+        .map_err(|e| AppError::io(tf("error.writeFile", &[("error", &e)]), &path))?;
+    if let Some(history) = &history {
+        let message = tf("lint.fixMessage", &[("rule", &rule)]);
+        history.record(&path, replacement.as_bytes(), VersionSource::Ui, Some(message))?;
+    }
+    Ok(lint_document(kind, &name, &path, &replacement))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint_prompt(name: &str, text: &str) -> Vec<Diagnostic> {
+        lint_document(DocumentKind::Prompt, name, Path::new("/tmp/prompt.md"), text)
+    }
+
+    fn rule_ids(diagnostics: &[Diagnostic]) -> Vec<&'static str> {
+        diagnostics.iter().map(|d| d.rule).collect()
+    }
+
+    #[test]
+    fn requotes_frontmatter_written_by_old_frontend() {
+        let text = "---\ndescription: \"Say \"hi\" politely\"\ncategory: \"general\"\n---\nHello\n";
+        let diagnostics = lint_prompt("greet", text);
+        assert_eq!(rule_ids(&diagnostics), [rules::INVALID_FRONTMATTER]);
+        assert_eq!(diagnostics[0].line, Some(2));
+
+        let fixed = diagnostics[0].fix.as_ref().and_then(|f| f.replacement.clone()).unwrap();
+        assert!(lint_prompt("greet", &fixed).is_empty());
+        let (frontmatter, _): (PromptFrontmatter, String) = parse_document(&fixed).unwrap();
+        assert_eq!(frontmatter.description.as_deref(), Some("Say \"hi\" politely"));
+    }
+
+    #[test]
+    fn checks_arguments_against_placeholders() {
+        let text = "---\ndescription: Review\narguments:\n- name: file\n- name: depth\n  type: number\n  defaultValue: deep\n\
+                    - name: file\n---\n\nReview {{file}}\nusing {{style}} and {{open\n";
+        let diagnostics = lint_prompt("review-code", text);
+        assert_eq!(
+            rule_ids(&diagnostics),
+            [
+                rules::INVALID_DEFAULT,
+                rules::UNUSED_ARGUMENT,
+                rules::DUPLICATE_ARGUMENT,
+                rules::UNDEFINED_PLACEHOLDER,
+                rules::UNCLOSED_PLACEHOLDER,
+            ]
+        );
+        let undefined = &diagnostics[3];
+        assert_eq!((undefined.line, undefined.column, undefined.target.as_deref()), (Some(12), Some(7), Some("style")));
+        assert_eq!(diagnostics[4].line, Some(12));
+
+        let declared = undefined.fix.as_ref().and_then(|f| f.replacement.clone()).unwrap();
+        let (frontmatter, body): (PromptFrontmatter, String) = parse_document(&declared).unwrap();
+        assert_eq!(frontmatter.arguments.last().map(|a| a.name.as_str()), Some("style"));
+        assert!(body.starts_with("Review {{file}}"));
+    }
+
+    #[test]
+    fn positional_and_all_arguments_count_as_used() {
+        let text = "---\ndescription: x\narguments:\n- name: a\n- name: b\n---\n$1 then $$2\n";
+        let diagnostics = lint_prompt("pair", text);
+        assert_eq!(rule_ids(&diagnostics), [rules::UNUSED_ARGUMENT]);
+        assert_eq!(diagnostics[0].target.as_deref(), Some("b"));
+        assert!(lint_prompt("pair", &text.replace("$$2", "$ARGUMENTS")).is_empty());
+    }
+
+    #[test]
+    fn fix_uses_the_namespaced_command_name() {
+        let path = Path::new("/work/app/.claude/commands/frontend/review.md");
+        assert_eq!(name_for_path(DocumentKind::ClaudeCommand, path), "frontend:review");
+        assert_eq!(name_for_path(DocumentKind::Prompt, Path::new("/home/u/.codex/prompts/review.md")), "review");
+        // 命名空间下的同名命令不会与内置命令冲突
+        let diagnostics = lint_document(DocumentKind::ClaudeCommand, &name_for_path(DocumentKind::ClaudeCommand, path), path, "---\ndescription: Review\n---\nReview\n");
+        assert!(!rule_ids(&diagnostics).contains(&rules::BUILTIN_COLLISION));
+    }
+
+    #[test]
+    fn flags_builtin_names_size_and_broken_fences() {
+        let big = format!("---\ndescription: x\n---\n{}", "a".repeat(OVERSIZED_BYTES + 1));
+        assert_eq!(rule_ids(&lint_prompt("review", &big)), [rules::BUILTIN_COLLISION, rules::OVERSIZED_PROMPT]);
+
+        let command = lint_document(DocumentKind::ClaudeCommand, "clear", Path::new("/tmp/clear.md"), "Use {{x}}\n");
+        assert_eq!(rule_ids(&command), [rules::BUILTIN_COLLISION, rules::MISSING_DESCRIPTION, rules::UNDEFINED_PLACEHOLDER]);
+        assert_eq!(command[2].severity, Severity::Warning);
+
+        let unclosed = lint_prompt("notes", "---\ndescription: x\n\nBody\n");
+        assert_eq!(unclosed[0].rule, rules::UNCLOSED_FRONTMATTER);
+    }
+
+    #[test]
+    fn reports_duplicate_names_across_scopes() {
+        let home = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let write = |base: &Path, relative: &str| {
+            let path = base.join(relative);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "---\ndescription: x\n---\nBody\n").unwrap();
+        };
+        write(home.path(), ".codex/prompts/deploy.md");
+        write(project.path(), ".codex/prompts/Deploy.md");
+        write(project.path(), ".claude/commands/deploy.md");
+
+        let report = lint_library(&[
+            (home.path().to_path_buf(), PromptScope::User),
+            (project.path().to_path_buf(), PromptScope::Project),
+        ]);
+        assert_eq!(report.files_checked, 3);
+        assert_eq!(rule_ids(&report.diagnostics), [rules::DUPLICATE_NAME]);
+        assert!(report.diagnostics[0].file_path.ends_with("Deploy.md"));
+        assert_eq!((report.errors, report.warnings), (0, 1));
+    }
+
+    #[test]
+    fn reports_unreadable_files() {
+        let home = tempfile::tempdir().unwrap();
+        let prompts = home.path().join(".codex").join("prompts");
+        std::fs::create_dir_all(&prompts).unwrap();
+        std::fs::write(prompts.join("binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let report = lint_library(&[(home.path().to_path_buf(), PromptScope::User)]);
+        assert_eq!(report.files_checked, 0);
+        assert_eq!(rule_ids(&report.diagnostics), [rules::UNREADABLE_FILE]);
+        assert_eq!(report.errors, 1);
+    }
+}
@@ -0,0 +1,946 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::claude_commands::{validate_command_name, ClaudeCommandStore};
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::tf;
+use crate::process::now_millis;
+use crate::prompt_history::{unified_diff, HistoryStore, VersionSource};
+use crate::prompt_store::{validate_name, write_atomic, PromptStore};
+use crate::tool_registry::decode_hex;
+
+/// 包格式标识和当前版本；读取时拒绝更高的版本
+const PACK_FORMAT: &str = "mspack";
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "manifest.json";
+const SIGNATURE_PATH: &str = "manifest.sig";
+const SIGNATURE_ALGORITHM: &str = "ed25519";
+
+/// 单个条目的大小上限，防止解压超大文件
+const MAX_ENTRY_SIZE: u64 = 4 * 1024 * 1024;
+
+/// 包中条目的类型
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum PackItemKind {
+    /// Codex 提示词，`prompts/<name>.md`
+    Prompt,
+    /// Claude Code 命令，`commands/<ns>/<cmd>.md`
+    Command,
+    /// AGENTS.md 中的一段说明，`agents/<name>.md`
+    AgentSnippet,
+    /// MCP 服务器定义，保存在清单中
+    McpServer,
+}
+
+/// 清单中的一个文件条目
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackEntry {
+    pub kind: PackItemKind,
+    pub name: String,
+    /// 包内路径
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// `manifest.json`
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackManifest {
+    pub format: String,
+    pub format_version: u32,
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Unix 毫秒
+    pub created_at: u64,
+    pub entries: Vec<PackEntry>,
+    /// MCP 服务器定义，与 Codex `config.toml` 中 `[mcp_servers.<name>]` 的结构相同
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mcp_servers: BTreeMap<String, serde_json::Value>,
+}
+
+/// `manifest.sig`：发布者私钥对 `manifest.json` 原始字节的 Ed25519 签名
+///
+/// 清单包含所有文件的 SHA-256，因此签名覆盖整个包。`public_key` 只用于展示签名者，
+/// 校验时使用导入方自己信任的公钥。
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackSignature {
+    pub algorithm: String,
+    pub public_key: String,
+    pub signature: String,
+}
+
+/// 签名校验结果
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum SignatureStatus {
+    Unsigned,
+    /// 包已签名，但没有提供信任的公钥
+    Unverified,
+    Valid,
+    Invalid,
+}
+
+/// 十六进制编码的 Ed25519 密钥对
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackKeyPair {
+    pub public_key: String,
+    /// 32 字节的私钥种子，导出时作为 `signKey` 传入
+    pub private_key: String,
+}
+
+/// 导入时同名条目已存在的处理方式
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    #[default]
+    Skip,
+    Overwrite,
+    /// 以 `name-2`、`name-3` 等新名称导入
+    Rename,
+}
+
+/// 导入预览中条目的状态
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum ItemStatus {
+    New,
+    Identical,
+    Conflict,
+}
+
+/// 导入预览中的一个条目
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItem {
+    pub kind: PackItemKind,
+    pub name: String,
+    pub target_path: PathBuf,
+    pub status: ItemStatus,
+    /// 现有内容与包中内容之间的 unified diff，新条目为空
+    pub diff: String,
+}
+
+/// 导入预览
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackPreview {
+    pub manifest: PackManifest,
+    pub signature: SignatureStatus,
+    /// 签名文件中声明的签名者公钥，仅供展示
+    pub signer: Option<String>,
+    pub items: Vec<ImportItem>,
+}
+
+/// 为单个条目指定的冲突处理方式，优先于全局策略
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemResolution {
+    pub kind: PackItemKind,
+    pub name: String,
+    pub policy: ConflictPolicy,
+}
+
+/// 导入后条目的结果
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportOutcome {
+    Created,
+    Overwritten,
+    Renamed,
+    Skipped,
+    Unchanged,
+    /// MCP 服务器会在本机启动进程，未签名的包中的服务器需要逐个确认后才会写入
+    NotApproved,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub kind: PackItemKind,
+    pub name: String,
+    /// 实际写入的名称，重命名时与 `name` 不同
+    pub target_name: String,
+    pub outcome: ImportOutcome,
+}
+
+/// 导出时附带的 AGENTS.md 片段
+#[derive(serde::Deserialize)]
+pub struct SnippetInput {
+    pub name: String,
+    pub content: String,
+}
+
+/// 导出请求
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackExportRequest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub prompts: Vec<String>,
+    #[serde(default)]
+    pub commands: Vec<String>,
+    #[serde(default)]
+    pub agent_snippets: Vec<SnippetInput>,
+    #[serde(default)]
+    pub mcp_servers: Vec<String>,
+    /// 给出时用该私钥（十六进制的 32 字节种子）签名
+    #[serde(default)]
+    pub sign_key: Option<String>,
+}
+
+/// 导入请求
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackImportRequest {
+    #[serde(default)]
+    pub policy: ConflictPolicy,
+    #[serde(default)]
+    pub resolutions: Vec<ItemResolution>,
+    /// 信任的签名者公钥（十六进制）
+    #[serde(default)]
+    pub public_key: Option<String>,
+    #[serde(default)]
+    pub require_signature: bool,
+    /// 用户逐个确认过的 MCP 服务器名称；签名通过验证时不需要
+    #[serde(default)]
+    pub approved_mcp_servers: Vec<String>,
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    encode_hex(&Sha256::digest(bytes))
+}
+
+/// 生成新的签名密钥对
+pub fn generate_key_pair() -> AppResult<PackKeyPair> {
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed).map_err(|e| AppError::internal(tf("error.generateKey", &[("error", &e)])))?;
+    let key = SigningKey::from_bytes(&seed);
+    Ok(PackKeyPair { public_key: encode_hex(key.verifying_key().as_bytes()), private_key: encode_hex(&seed) })
+}
+
+fn parse_signing_key(hex: &str) -> AppResult<SigningKey> {
+    decode_hex(hex)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .map(|seed| SigningKey::from_bytes(&seed))
+        .ok_or_else(|| AppError::new(ErrorCode::InvalidArgument, tf("error.invalidPackKey", &[])))
+}
+
+fn parse_verifying_key(hex: &str) -> AppResult<VerifyingKey> {
+    decode_hex(hex)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| AppError::new(ErrorCode::InvalidArgument, tf("error.invalidPackKey", &[])))
+}
+
+fn pack_error(e: impl std::fmt::Display) -> AppError {
+    AppError::new(ErrorCode::InvalidConfig, tf("error.invalidPack", &[("error", &e)]))
+}
+
+fn write_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.writeFile", &[("error", &e)]), path)
+}
+
+fn read_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.readFile", &[("error", &e)]), path)
+}
+
+/// 条目在包内的路径
+fn entry_path(kind: PackItemKind, name: &str) -> String {
+    match kind {
+        PackItemKind::Prompt => format!("prompts/{}.md", name),
+        PackItemKind::Command => format!("commands/{}.md", name.replace(':', "/")),
+        PackItemKind::AgentSnippet => format!("agents/{}.md", name),
+        // MCP 服务器保存在清单中，没有单独的文件
+        PackItemKind::McpServer => MANIFEST_PATH.to_string(),
+    }
+}
+
+fn validate_item_name(kind: PackItemKind, name: &str) -> AppResult<()> {
+    match kind {
+        PackItemKind::Command => validate_command_name(name),
+        _ => validate_name(name),
+    }
+}
+
+fn section_markers(name: &str) -> (String, String) {
+    (format!("<!-- mspack:begin {} -->", name), format!("<!-- mspack:end {} -->", name))
+}
+
+/// 读取 AGENTS.md 中由 `mspack:begin`/`mspack:end` 标记的片段
+pub fn extract_section(text: &str, name: &str) -> Option<String> {
+    let (begin, end) = section_markers(name);
+    let start = text.find(&begin)? + begin.len();
+    let stop = start + text[start..].find(&end)?;
+    Some(text[start..stop].trim_matches('\n').to_string())
+}
+
+/// 替换已有的片段，不存在时追加到文件末尾
+pub fn replace_section(text: &str, name: &str, content: &str) -> String {
+    let (begin, end) = section_markers(name);
+    let section = format!("{}\n{}\n{}", begin, content.trim_matches('\n'), end);
+    if let Some(start) = text.find(&begin) {
+        if let Some(stop) = text[start..].find(&end).map(|i| start + i + end.len()) {
+            return format!("{}{}{}", &text[..start], section, &text[stop..]);
+        }
+    }
+    let separator = match text {
+        "" => "",
+        t if t.ends_with("\n\n") => "",
+        t if t.ends_with('\n') => "\n",
+        _ => "\n\n",
+    };
+    format!("{}{}{}\n", text, separator, section)
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> serde_json::Value {
+    use toml_edit::Value;
+    match value {
+        Value::String(s) => s.value().clone().into(),
+        Value::Integer(i) => (*i.value()).into(),
+        Value::Float(f) => serde_json::Number::from_f64(*f.value()).map_or(serde_json::Value::Null, Into::into),
+        Value::Boolean(b) => (*b.value()).into(),
+        Value::Datetime(d) => d.value().to_string().into(),
+        Value::Array(items) => items.iter().map(toml_value_to_json).collect(),
+        Value::InlineTable(table) => table.iter().map(|(k, v)| (k.to_string(), toml_value_to_json(v))).collect(),
+    }
+}
+
+fn toml_item_to_json(item: &Item) -> serde_json::Value {
+    match item {
+        Item::None => serde_json::Value::Null,
+        Item::Value(value) => toml_value_to_json(value),
+        Item::Table(table) => table.iter().map(|(k, v)| (k.to_string(), toml_item_to_json(v))).collect(),
+        Item::ArrayOfTables(tables) => tables
+            .iter()
+            .map(|t| {
+                let fields: serde_json::Map<String, serde_json::Value> =
+                    t.iter().map(|(k, v)| (k.to_string(), toml_item_to_json(v))).collect();
+                serde_json::Value::Object(fields)
+            })
+            .collect(),
+    }
+}
+
+/// JSON 转 TOML；`null` 没有对应的值，返回 `None` 后被忽略
+fn json_to_toml(value: &serde_json::Value) -> Option<toml_edit::Value> {
+    use serde_json::Value;
+    match value {
+        Value::Null => None,
+        Value::Bool(b) => Some((*b).into()),
+        Value::Number(n) => n.as_i64().map(Into::into).or_else(|| n.as_f64().map(Into::into)),
+        Value::String(s) => Some(s.as_str().into()),
+        Value::Array(items) => Some(toml_edit::Value::Array(items.iter().filter_map(json_to_toml).collect())),
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (k, v) in map {
+                if let Some(v) = json_to_toml(v) {
+                    table.insert(k.as_str(), v);
+                }
+            }
+            Some(toml_edit::Value::InlineTable(table))
+        }
+    }
+}
+
+/// 导入导出涉及的本地位置
+pub struct PackTargets {
+    pub prompts: PromptStore,
+    pub commands: ClaudeCommandStore,
+    /// 接收说明片段的 AGENTS.md
+    pub agent_file: PathBuf,
+    /// 保存 MCP 服务器定义的 Codex `config.toml`
+    pub mcp_config: PathBuf,
+    pub history: Option<HistoryStore>,
+}
+
+impl PackTargets {
+    /// 用户级写入 `~/.codex` 和 `~/.claude`；给出项目路径时提示词、命令和 AGENTS.md 写入项目，
+    /// MCP 服务器始终写入 `~/.codex/config.toml`
+    pub fn for_scope(app_handle: &AppHandle, project_path: Option<&str>) -> AppResult<Self> {
+        let home = app_handle
+            .path()
+            .home_dir()
+            .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))?;
+        Ok(Self {
+            prompts: PromptStore::for_scope(app_handle, project_path)?,
+            commands: ClaudeCommandStore::for_scope(app_handle, project_path)?,
+            agent_file: match project_path {
+                Some(project) => Path::new(project).join("AGENTS.md"),
+                None => home.join(".codex").join("AGENTS.md"),
+            },
+            mcp_config: home.join(".codex").join("config.toml"),
+            history: HistoryStore::for_app(app_handle).ok(),
+        })
+    }
+
+    fn target_path(&self, kind: PackItemKind, name: &str) -> PathBuf {
+        match kind {
+            PackItemKind::Prompt => self.prompts.path_for(name),
+            PackItemKind::Command => self.commands.path_for(name),
+            PackItemKind::AgentSnippet => self.agent_file.clone(),
+            PackItemKind::McpServer => self.mcp_config.clone(),
+        }
+    }
+
+    fn read_config(&self) -> AppResult<DocumentMut> {
+        match std::fs::read_to_string(&self.mcp_config) {
+            Ok(text) => text.parse::<DocumentMut>().map_err(|e| {
+                AppError::new(ErrorCode::InvalidConfig, tf("error.invalidMcpConfig", &[("error", &e)])).with_path(&self.mcp_config)
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DocumentMut::new()),
+            Err(e) => Err(read_error(e, &self.mcp_config)),
+        }
+    }
+
+    fn mcp_server(&self, name: &str) -> AppResult<Option<serde_json::Value>> {
+        let config = self.read_config()?;
+        Ok(config.get("mcp_servers").and_then(|servers| servers.get(name)).map(toml_item_to_json))
+    }
+
+    /// 条目的现有内容，不存在时为 `None`
+    fn current(&self, kind: PackItemKind, name: &str) -> AppResult<Option<String>> {
+        Ok(match kind {
+            PackItemKind::Prompt | PackItemKind::Command => std::fs::read_to_string(self.target_path(kind, name)).ok(),
+            PackItemKind::AgentSnippet => std::fs::read_to_string(&self.agent_file)
+                .ok()
+                .and_then(|text| extract_section(&text, name)),
+            PackItemKind::McpServer => self.mcp_server(name)?.map(|v| serde_json::to_string_pretty(&v).unwrap_or_default()),
+        })
+    }
+
+    fn write_file(&self, path: &Path, text: &str, message: &str) -> AppResult<()> {
+        if let Some(history) = &self.history {
+            history.snapshot_existing(path)?;
+        }
+        write_atomic(path, text.as_bytes()).map_err(|e| write_error(e, path))?;
+        if let Some(history) = &self.history {
+            history.record(path, text.as_bytes(), VersionSource::Ui, Some(message.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, kind: PackItemKind, name: &str, text: &str, message: &str) -> AppResult<()> {
+        let path = self.target_path(kind, name);
+        match kind {
+            PackItemKind::Prompt | PackItemKind::Command => self.write_file(&path, text, message),
+            PackItemKind::AgentSnippet => {
+                let existing = std::fs::read_to_string(&path).unwrap_or_default();
+                self.write_file(&path, &replace_section(&existing, name, text), message)
+            }
+            PackItemKind::McpServer => {
+                let value: serde_json::Value = serde_json::from_str(text).map_err(pack_error)?;
+                let serde_json::Value::Object(fields) = value else {
+                    return Err(pack_error(format!("mcpServers.{}", name)));
+                };
+                let mut server = Table::new();
+                for (key, value) in &fields {
+                    if let Some(value) = json_to_toml(value) {
+                        server.insert(key, Item::Value(value));
+                    }
+                }
+                let mut config = self.read_config()?;
+                if !config.contains_key("mcp_servers") {
+                    let mut servers = Table::new();
+                    servers.set_implicit(true);
+                    config.insert("mcp_servers", Item::Table(servers));
+                }
+                let servers = config["mcp_servers"]
+                    .as_table_like_mut()
+                    .ok_or_else(|| AppError::new(ErrorCode::InvalidConfig, tf("error.invalidMcpConfig", &[("error", &"mcp_servers")])))?;
+                servers.insert(name, Item::Table(server));
+                self.write_file(&path, &config.to_string(), message)
+            }
+        }
+    }
+
+    /// 重命名时使用的第一个未被占用的名称；命令只在最后一段加后缀
+    fn free_name(&self, kind: PackItemKind, name: &str) -> AppResult<String> {
+        for n in 2.. {
+            let candidate = format!("{}-{}", name, n);
+            if self.current(kind, &candidate)?.is_none() {
+                return Ok(candidate);
+            }
+        }
+        unreachable!()
+    }
+}
+
+/// 读取并校验后的包
+pub struct LoadedPack {
+    pub manifest: PackManifest,
+    manifest_bytes: Vec<u8>,
+    signature: Option<PackSignature>,
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl LoadedPack {
+    /// 读取包并校验格式版本和所有文件的 SHA-256
+    pub fn read(bytes: &[u8]) -> AppResult<Self> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(pack_error)?;
+        let mut read_entry = |path: &str| -> AppResult<Option<Vec<u8>>> {
+            let file = match archive.by_name(path) {
+                Ok(file) => file,
+                Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+                Err(e) => return Err(pack_error(e)),
+            };
+            let mut content = Vec::new();
+            file.take(MAX_ENTRY_SIZE + 1).read_to_end(&mut content).map_err(pack_error)?;
+            if content.len() as u64 > MAX_ENTRY_SIZE {
+                return Err(AppError::new(ErrorCode::InvalidConfig, tf("error.packEntryTooLarge", &[("path", &path)])));
+            }
+            Ok(Some(content))
+        };
+
+        let manifest_bytes = read_entry(MANIFEST_PATH)?.ok_or_else(|| pack_error(MANIFEST_PATH))?;
+        let manifest: PackManifest = serde_json::from_slice(&manifest_bytes).map_err(pack_error)?;
+        if manifest.format != PACK_FORMAT || manifest.format_version > FORMAT_VERSION {
+            return Err(AppError::new(
+                ErrorCode::Unsupported,
+                tf("error.packVersion", &[("version", &format!("{} {}", manifest.format, manifest.format_version))]),
+            ));
+        }
+        let signature = match read_entry(SIGNATURE_PATH)? {
+            Some(bytes) => Some(serde_json::from_slice(&bytes).map_err(pack_error)?),
+            None => None,
+        };
+
+        let mut files = HashMap::new();
+        for entry in &manifest.entries {
+            validate_item_name(entry.kind, &entry.name)?;
+            let content = read_entry(&entry.path)?.ok_or_else(|| pack_error(&entry.path))?;
+            if sha256_hex(&content) != entry.sha256 {
+                return Err(AppError::new(ErrorCode::ChecksumMismatch, tf("error.packChecksum", &[("path", &entry.path)])));
+            }
+            files.insert(entry.path.clone(), content);
+        }
+        for name in manifest.mcp_servers.keys() {
+            validate_name(name)?;
+        }
+        Ok(Self { manifest, manifest_bytes, signature, files })
+    }
+
+    /// 签名者在签名文件中声明的公钥，未签名时为空
+    pub fn signer(&self) -> Option<&str> {
+        self.signature.as_ref().map(|s| s.public_key.as_str())
+    }
+
+    /// 用导入方信任的公钥校验签名
+    pub fn signature_status(&self, key: Option<&VerifyingKey>) -> SignatureStatus {
+        match (&self.signature, key) {
+            (None, _) => SignatureStatus::Unsigned,
+            (Some(_), None) => SignatureStatus::Unverified,
+            (Some(signature), Some(key)) => {
+                let valid = signature.algorithm == SIGNATURE_ALGORITHM
+                    && decode_hex(&signature.signature)
+                        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+                        .is_some_and(|bytes| key.verify_strict(&self.manifest_bytes, &Signature::from_bytes(&bytes)).is_ok());
+                if valid {
+                    SignatureStatus::Valid
+                } else {
+                    SignatureStatus::Invalid
+                }
+            }
+        }
+    }
+
+    /// 包中所有条目的类型、名称和文本内容
+    fn items(&self) -> Vec<(PackItemKind, String, String)> {
+        let files = self.manifest.entries.iter().map(|entry| {
+            let text = String::from_utf8_lossy(&self.files[&entry.path]).into_owned();
+            (entry.kind, entry.name.clone(), text)
+        });
+        let servers = self.manifest.mcp_servers.iter().map(|(name, value)| {
+            (PackItemKind::McpServer, name.clone(), serde_json::to_string_pretty(value).unwrap_or_default())
+        });
+        files.chain(servers).collect()
+    }
+}
+
+/// MCP 服务器按 JSON 值比较，忽略键的顺序；其他条目按文本比较
+fn same_content(kind: PackItemKind, current: &str, incoming: &str) -> bool {
+    if kind == PackItemKind::McpServer {
+        let parse = |text: &str| serde_json::from_str::<serde_json::Value>(text).ok();
+        return parse(current).is_some_and(|c| Some(c) == parse(incoming));
+    }
+    current.trim_end() == incoming.trim_end()
+}
+
+/// 预览导入：列出每个条目的目标位置、状态和差异
+pub fn preview(pack: &LoadedPack, targets: &PackTargets) -> AppResult<Vec<ImportItem>> {
+    pack.items()
+        .into_iter()
+        .map(|(kind, name, text)| {
+            let current = targets.current(kind, &name)?;
+            let (status, diff) = match &current {
+                None => (ItemStatus::New, String::new()),
+                Some(current) if same_content(kind, current, &text) => (ItemStatus::Identical, String::new()),
+                Some(current) => (ItemStatus::Conflict, unified_diff(current, &text, &name, &name).0),
+            };
+            Ok(ImportItem { target_path: targets.target_path(kind, &name), kind, name, status, diff })
+        })
+        .collect()
+}
+
+/// 按冲突策略导入；`resolutions` 中的条目覆盖全局策略
+///
+/// MCP 服务器只有列在 `approved_servers` 中时才会写入，其余记为 `NotApproved`。
+pub fn import(
+    pack: &LoadedPack,
+    targets: &PackTargets,
+    policy: ConflictPolicy,
+    resolutions: &[ItemResolution],
+    approved_servers: &[String],
+) -> AppResult<Vec<ImportResult>> {
+    let message = tf("pack.importMessage", &[("name", &pack.manifest.name), ("version", &pack.manifest.version)]);
+    let mut results = Vec::new();
+    for (kind, name, text) in pack.items() {
+        let policy = resolutions
+            .iter()
+            .find(|r| r.kind == kind && r.name == name)
+            .map_or(policy, |r| r.policy);
+        let (target_name, outcome) = match targets.current(kind, &name)? {
+            Some(current) if same_content(kind, &current, &text) => (name.clone(), ImportOutcome::Unchanged),
+            _ if kind == PackItemKind::McpServer && !approved_servers.contains(&name) => {
+                (name.clone(), ImportOutcome::NotApproved)
+            }
+            None => (name.clone(), ImportOutcome::Created),
+            Some(_) => match policy {
+                ConflictPolicy::Skip => (name.clone(), ImportOutcome::Skipped),
+                ConflictPolicy::Overwrite => (name.clone(), ImportOutcome::Overwritten),
+                ConflictPolicy::Rename => (targets.free_name(kind, &name)?, ImportOutcome::Renamed),
+            },
+        };
+        if matches!(outcome, ImportOutcome::Created | ImportOutcome::Overwritten | ImportOutcome::Renamed) {
+            targets.write(kind, &target_name, &text, &message)?;
+        }
+        results.push(ImportResult { kind, name, target_name, outcome });
+    }
+    Ok(results)
+}
+
+/// 打包选中的条目，返回包的字节和清单
+pub fn export(targets: &PackTargets, request: &PackExportRequest) -> AppResult<(Vec<u8>, PackManifest)> {
+    let mut contents: Vec<(PackEntry, Vec<u8>)> = Vec::new();
+    let mut add = |kind: PackItemKind, name: &str, bytes: Vec<u8>| {
+        let entry = PackEntry {
+            kind,
+            name: name.to_string(),
+            path: entry_path(kind, name),
+            sha256: sha256_hex(&bytes),
+            size: bytes.len() as u64,
+        };
+        contents.push((entry, bytes));
+    };
+
+    for name in &request.prompts {
+        validate_name(name)?;
+        let path = targets.prompts.path_for(name);
+        let bytes = std::fs::read(&path).map_err(|_| {
+            AppError::new(ErrorCode::NotFound, tf("error.promptNotFound", &[("name", name)])).with_path(&path)
+        })?;
+        add(PackItemKind::Prompt, name, bytes);
+    }
+    for name in &request.commands {
+        validate_command_name(name)?;
+        let path = targets.commands.path_for(name);
+        let bytes = std::fs::read(&path).map_err(|_| {
+            AppError::new(ErrorCode::NotFound, tf("error.commandNotFound", &[("name", name)])).with_path(&path)
+        })?;
+        add(PackItemKind::Command, name, bytes);
+    }
+    for snippet in &request.agent_snippets {
+        validate_name(&snippet.name)?;
+        add(PackItemKind::AgentSnippet, &snippet.name, snippet.content.clone().into_bytes());
+    }
+    let mut mcp_servers = BTreeMap::new();
+    for name in &request.mcp_servers {
+        let server = targets
+            .mcp_server(name)?
+            .ok_or_else(|| AppError::new(ErrorCode::NotFound, tf("error.mcpServerNotFound", &[("name", name)])))?;
+        mcp_servers.insert(name.clone(), server);
+    }
+
+    let manifest = PackManifest {
+        format: PACK_FORMAT.to_string(),
+        format_version: FORMAT_VERSION,
+        name: request.name.clone(),
+        version: request.version.clone(),
+        description: request.description.clone().filter(|d| !d.trim().is_empty()),
+        author: request.author.clone().filter(|a| !a.trim().is_empty()),
+        created_at: now_millis(),
+        entries: contents.iter().map(|(entry, _)| entry.clone()).collect(),
+        mcp_servers,
+    };
+    let manifest_bytes = serde_json::to_vec_pretty(&manifest).map_err(pack_error)?;
+
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut put = |path: &str, bytes: &[u8]| -> AppResult<()> {
+        writer.start_file(path, options).map_err(pack_error)?;
+        writer.write_all(bytes).map_err(pack_error)
+    };
+    put(MANIFEST_PATH, &manifest_bytes)?;
+    if let Some(key) = request.sign_key.as_deref().filter(|k| !k.is_empty()) {
+        let key = parse_signing_key(key)?;
+        let signature = PackSignature {
+            algorithm: SIGNATURE_ALGORITHM.to_string(),
+            public_key: encode_hex(key.verifying_key().as_bytes()),
+            signature: encode_hex(&key.sign(&manifest_bytes).to_bytes()),
+        };
+        put(SIGNATURE_PATH, &serde_json::to_vec_pretty(&signature).map_err(pack_error)?)?;
+    }
+    for (entry, bytes) in &contents {
+        put(&entry.path, bytes)?;
+    }
+    let bytes = writer.finish().map_err(pack_error)?.into_inner();
+    Ok((bytes, manifest))
+}
+
+fn read_pack_file(path: &str) -> AppResult<LoadedPack> {
+    let bytes = std::fs::read(path).map_err(|e| read_error(e, Path::new(path)))?;
+    LoadedPack::read(&bytes)
+}
+
+/// 解析可选的信任公钥，空字符串视为未提供
+fn trusted_key(public_key: Option<&str>) -> AppResult<Option<VerifyingKey>> {
+    public_key.map(str::trim).filter(|k| !k.is_empty()).map(parse_verifying_key).transpose()
+}
+
+/// Tauri 命令：生成用于签名提示词包的 Ed25519 密钥对
+#[tauri::command]
+pub async fn generate_pack_key_pair() -> AppResult<PackKeyPair> {
+    generate_key_pair()
+}
+
+/// Tauri 命令：把选中的提示词、命令、说明片段和 MCP 服务器导出为 `.mspack`
+#[tauri::command(rename_all = "camelCase")]
+pub async fn export_prompt_pack(
+    app_handle: AppHandle,
+    request: PackExportRequest,
+    output_path: String,
+    project_path: Option<String>,
+) -> AppResult<PackManifest> {
+    let targets = PackTargets::for_scope(&app_handle, project_path.as_deref())?;
+    let (bytes, manifest) = export(&targets, &request)?;
+    let output = Path::new(&output_path);
+    write_atomic(output, &bytes).map_err(|e| write_error(e, output))?;
+    Ok(manifest)
+}
+
+/// Tauri 命令：预览 `.mspack` 的内容、签名状态和与本地条目的冲突
+#[tauri::command(rename_all = "camelCase")]
+pub async fn preview_prompt_pack(
+    app_handle: AppHandle,
+    path: String,
+    project_path: Option<String>,
+    public_key: Option<String>,
+) -> AppResult<PackPreview> {
+    let pack = read_pack_file(&path)?;
+    let targets = PackTargets::for_scope(&app_handle, project_path.as_deref())?;
+    let items = preview(&pack, &targets)?;
+    let signature = pack.signature_status(trusted_key(public_key.as_deref())?.as_ref());
+    let signer = pack.signer().map(str::to_string);
+    Ok(PackPreview { manifest: pack.manifest, signature, signer, items })
+}
+
+/// Tauri 命令：导入 `.mspack`
+///
+/// 签名无效时总是拒绝；`requireSignature` 时未签名或无法验证的包也会被拒绝。
+/// 签名通过验证时写入包中所有 MCP 服务器，否则只写入 `approvedMcpServers` 中逐个确认过的服务器。
+#[tauri::command(rename_all = "camelCase")]
+pub async fn import_prompt_pack(
+    app_handle: AppHandle,
+    path: String,
+    project_path: Option<String>,
+    request: PackImportRequest,
+) -> AppResult<Vec<ImportResult>> {
+    let pack = read_pack_file(&path)?;
+    let approved = match pack.signature_status(trusted_key(request.public_key.as_deref())?.as_ref()) {
+        SignatureStatus::Invalid => {
+            return Err(AppError::new(ErrorCode::ChecksumMismatch, tf("error.packSignatureInvalid", &[])).with_path(&path));
+        }
+        SignatureStatus::Valid => pack.manifest.mcp_servers.keys().cloned().collect(),
+        _ if request.require_signature => {
+            return Err(AppError::new(ErrorCode::InvalidArgument, tf("error.packSignatureRequired", &[])).with_path(&path));
+        }
+        _ => request.approved_mcp_servers,
+    };
+    let targets = PackTargets::for_scope(&app_handle, project_path.as_deref())?;
+    import(&pack, &targets, request.policy, &request.resolutions, &approved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prompt_store::PromptScope;
+
+    fn targets(root: &Path) -> PackTargets {
+        PackTargets {
+            prompts: PromptStore::new(root.join("prompts"), PromptScope::Project),
+            commands: ClaudeCommandStore::new(root.join("commands"), PromptScope::Project),
+            agent_file: root.join("AGENTS.md"),
+            mcp_config: root.join("config.toml"),
+            history: None,
+        }
+    }
+
+    fn request(sign_key: Option<&str>) -> PackExportRequest {
+        PackExportRequest {
+            name: "team".to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            author: None,
+            prompts: vec!["review".to_string()],
+            commands: vec!["git:commit".to_string()],
+            agent_snippets: vec![SnippetInput { name: "style".to_string(), content: "Use tabs.".to_string() }],
+            mcp_servers: vec!["docs".to_string()],
+            sign_key: sign_key.map(str::to_string),
+        }
+    }
+
+    fn source() -> (tempfile::TempDir, PackTargets) {
+        let dir = tempfile::tempdir().unwrap();
+        let targets = targets(dir.path());
+        write_atomic(&targets.prompts.path_for("review"), b"---\ndescription: Review\n---\n\nReview $1\n").unwrap();
+        write_atomic(&targets.commands.path_for("git:commit"), b"Commit $ARGUMENTS\n").unwrap();
+        write_atomic(&targets.mcp_config, b"model = \"o3\"\n\n[mcp_servers.docs]\ncommand = \"npx\"\nargs = [\"docs-mcp\"]\nenv = { TOKEN = \"x\" }\n")
+            .unwrap();
+        (dir, targets)
+    }
+
+    #[test]
+    fn agent_sections_are_replaced_in_place() {
+        let text = replace_section("# Rules\n", "style", "Use tabs.");
+        assert_eq!(text, "# Rules\n\n<!-- mspack:begin style -->\nUse tabs.\n<!-- mspack:end style -->\n");
+        let text = replace_section(&text, "style", "Use spaces.");
+        assert_eq!(extract_section(&text, "style").as_deref(), Some("Use spaces."));
+        assert!(text.starts_with("# Rules\n\n<!-- mspack:begin style -->"));
+    }
+
+    #[test]
+    fn round_trips_into_an_empty_location() {
+        let (_src, from) = source();
+        let (bytes, manifest) = export(&from, &request(None)).unwrap();
+        assert_eq!(manifest.entries.len(), 3);
+
+        let pack = LoadedPack::read(&bytes).unwrap();
+        assert_eq!(pack.signature_status(None), SignatureStatus::Unsigned);
+
+        let dest_dir = tempfile::tempdir().unwrap();
+        let to = targets(dest_dir.path());
+        let statuses: Vec<ItemStatus> = preview(&pack, &to).unwrap().iter().map(|i| i.status).collect();
+        assert_eq!(statuses, [ItemStatus::New; 4]);
+
+        import(&pack, &to, ConflictPolicy::Skip, &[], &["docs".to_string()]).unwrap();
+        assert_eq!(std::fs::read_to_string(to.prompts.path_for("review")).unwrap(), "---\ndescription: Review\n---\n\nReview $1\n");
+        assert!(dest_dir.path().join("commands").join("git").join("commit.md").is_file());
+        assert_eq!(to.current(PackItemKind::AgentSnippet, "style").unwrap().as_deref(), Some("Use tabs."));
+        let config = std::fs::read_to_string(&to.mcp_config).unwrap();
+        assert!(config.contains("[mcp_servers.docs]"));
+        assert!(config.contains("TOKEN = \"x\""));
+
+        let statuses: Vec<ItemStatus> = preview(&pack, &to).unwrap().iter().map(|i| i.status).collect();
+        assert_eq!(statuses, [ItemStatus::Identical; 4]);
+    }
+
+    #[test]
+    fn conflicts_follow_policy_and_resolutions() {
+        let (_src, from) = source();
+        let (bytes, _) = export(&from, &request(None)).unwrap();
+        let pack = LoadedPack::read(&bytes).unwrap();
+
+        write_atomic(&from.prompts.path_for("review"), b"local edit\n").unwrap();
+        write_atomic(&from.commands.path_for("git:commit"), b"local commit\n").unwrap();
+        let items = preview(&pack, &from).unwrap();
+        assert_eq!(items[0].status, ItemStatus::Conflict);
+        assert!(items[0].diff.contains("-local edit"));
+
+        let resolutions = [ItemResolution { kind: PackItemKind::Command, name: "git:commit".to_string(), policy: ConflictPolicy::Overwrite }];
+        let results = import(&pack, &from, ConflictPolicy::Rename, &resolutions, &[]).unwrap();
+        let outcomes: Vec<(&str, ImportOutcome)> = results.iter().map(|r| (r.target_name.as_str(), r.outcome)).collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("review-2", ImportOutcome::Renamed),
+                ("git:commit", ImportOutcome::Overwritten),
+                ("style", ImportOutcome::Created),
+                ("docs", ImportOutcome::Unchanged),
+            ]
+        );
+        assert_eq!(std::fs::read_to_string(from.prompts.path_for("review")).unwrap(), "local edit\n");
+        assert_eq!(std::fs::read_to_string(from.commands.path_for("git:commit")).unwrap(), "Commit $ARGUMENTS\n");
+    }
+
+    #[test]
+    fn mcp_servers_need_approval() {
+        let (_src, from) = source();
+        let (bytes, _) = export(&from, &request(None)).unwrap();
+        let pack = LoadedPack::read(&bytes).unwrap();
+
+        let dest_dir = tempfile::tempdir().unwrap();
+        let to = targets(dest_dir.path());
+        let results = import(&pack, &to, ConflictPolicy::Overwrite, &[], &["other".to_string()]).unwrap();
+        let docs = results.iter().find(|r| r.kind == PackItemKind::McpServer).unwrap();
+        assert_eq!(docs.outcome, ImportOutcome::NotApproved);
+        assert!(to.mcp_server("docs").unwrap().is_none());
+        assert!(to.prompts.path_for("review").is_file());
+    }
+
+    #[test]
+    fn signatures_and_checksums_are_verified() {
+        let (_src, from) = source();
+        let keys = generate_key_pair().unwrap();
+        let (bytes, _) = export(&from, &request(Some(&keys.private_key))).unwrap();
+        let pack = LoadedPack::read(&bytes).unwrap();
+        assert_eq!(pack.signer(), Some(keys.public_key.as_str()));
+        assert_eq!(pack.signature_status(None), SignatureStatus::Unverified);
+        let trusted = parse_verifying_key(&keys.public_key).unwrap();
+        assert_eq!(pack.signature_status(Some(&trusted)), SignatureStatus::Valid);
+        let other = parse_verifying_key(&generate_key_pair().unwrap().public_key).unwrap();
+        assert_eq!(pack.signature_status(Some(&other)), SignatureStatus::Invalid);
+        assert!(export(&from, &request(Some("not-a-key"))).is_err_and(|e| e.code == ErrorCode::InvalidArgument));
+
+        // 改动文件内容后清单中的校验和不再匹配
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let name = file.name().to_string();
+            let mut content = Vec::new();
+            file.read_to_end(&mut content).unwrap();
+            if name == "prompts/review.md" {
+                content = b"tampered".to_vec();
+            }
+            writer.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+            writer.write_all(&content).unwrap();
+        }
+        let tampered = writer.finish().unwrap().into_inner();
+        let error = LoadedPack::read(&tampered).err().unwrap();
+        assert!(error.code == ErrorCode::ChecksumMismatch);
+    }
+}
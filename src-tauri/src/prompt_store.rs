@@ -0,0 +1,576 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::tf;
+use crate::prompt_history::{HistoryStore, VersionSource};
+
+/// 提示词参数的类型
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+}
+
+/// 提示词参数定义，与前端的 `PromptArgument` 对应
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptArgument {
+    pub name: String,
+    #[serde(default, rename = "type")]
+    pub arg_type: ArgumentType,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_value: Option<String>,
+}
+
+/// `tools` 字段兼容旧格式的逗号分隔字符串和 YAML 列表
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ToolList {
+    Text(String),
+    List(Vec<String>),
+}
+
+fn deserialize_tools<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    let tools = match <Option<ToolList> as serde::Deserialize>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(ToolList::Text(text)) => text.split(',').map(|t| t.trim().to_string()).collect(),
+        Some(ToolList::List(list)) => list,
+    };
+    Ok(tools.into_iter().filter(|t| !t.is_empty()).collect())
+}
+
+/// 提示词文件的 YAML frontmatter
+///
+/// 未识别的键保存在 `extra` 中，写回时原样保留。
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptFrontmatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_tools", skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<PromptArgument>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// 提示词的作用域
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptScope {
+    /// `~/.codex/prompts`
+    User,
+    /// `<project>/.codex/prompts`
+    Project,
+}
+
+/// 一个提示词，字段与前端的 `CustomPrompt` 对应
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Prompt {
+    /// 文件名去掉 `.md`，也是 `/name` 调用时的名称
+    pub name: String,
+    pub scope: PromptScope,
+    pub description: String,
+    pub category: String,
+    pub allowed_tools: Vec<String>,
+    pub arguments: Vec<PromptArgument>,
+    pub content: String,
+    pub file_path: PathBuf,
+    /// Unix 毫秒，文件系统不提供创建时间时取修改时间
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+/// 创建或更新提示词时前端提交的内容
+#[derive(serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptInput {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub allowed_tools: Vec<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+    #[serde(default)]
+    pub content: String,
+    /// 编辑开始时文件的修改时间；与磁盘上的不一致说明文件已被外部修改，拒绝覆盖
+    #[serde(default)]
+    pub expected_updated_at: Option<u64>,
+    /// 记录在版本历史中的说明
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// 将文本拆分为 frontmatter 和正文；没有 frontmatter 时第一项为 `None`
+///
+/// frontmatter 必须从第一行的 `---` 开始，到下一行单独的 `---` 结束。
+pub fn split_frontmatter(text: &str) -> (Option<&str>, &str) {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let Some(rest) = text.strip_prefix("---").and_then(|r| r.strip_prefix('\n').or_else(|| r.strip_prefix("\r\n"))) else {
+        return (None, text);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            let body = &rest[offset + line.len()..];
+            return (Some(&rest[..offset]), body);
+        }
+        offset += line.len();
+    }
+    (None, text)
+}
+
+/// 解析 frontmatter 和正文，正文去掉首尾空白
+pub fn parse_document<T: serde::de::DeserializeOwned + Default>(text: &str) -> Result<(T, String), serde_yaml::Error> {
+    let (yaml, body) = split_frontmatter(text);
+    let frontmatter = match yaml {
+        Some(yaml) if !yaml.trim().is_empty() => serde_yaml::from_str(yaml)?,
+        _ => T::default(),
+    };
+    Ok((frontmatter, body.trim().to_string()))
+}
+
+/// 生成带 frontmatter 的 Markdown 文本；frontmatter 为空时只输出正文
+pub fn render_document<T: serde::Serialize>(frontmatter: &T, body: &str) -> Result<String, serde_yaml::Error> {
+    let yaml = serde_yaml::to_string(frontmatter)?;
+    let body = body.trim();
+    if yaml.trim() == "{}" {
+        return Ok(format!("{}\n", body));
+    }
+    Ok(format!("---\n{}---\n\n{}\n", yaml, body))
+}
+
+/// 原子写入：先写入同目录下的临时文件，再重命名覆盖目标文件
+pub fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp = dir.join(format!(".{}.{}.tmp", file_name, crate::process::next_id("write")));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+    match &result {
+        Ok(()) => crate::file_watcher::record_own_write(path),
+        Err(_) => {
+            let _ = std::fs::remove_file(&tmp);
+        }
+    }
+    result
+}
+
+/// 重命名文件，目标已存在时返回 `AlreadyExists` 而不是覆盖
+///
+/// 通过硬链接实现，检查和重命名是同一个原子操作；文件系统不支持硬链接时，
+/// 先以 `create_new` 独占创建目标占位，再把源文件重命名到占位上。
+pub fn rename_no_replace(from: &Path, to: &Path) -> std::io::Result<()> {
+    match std::fs::hard_link(from, to) {
+        Ok(()) => std::fs::remove_file(from),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Err(e),
+        Err(_) => {
+            std::fs::OpenOptions::new().write(true).create_new(true).open(to)?;
+            std::fs::rename(from, to)
+        }
+    }
+}
+
+/// 文件的修改时间（Unix 毫秒）
+pub fn modified_millis(meta: &std::fs::Metadata) -> u64 {
+    meta.modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 校验提示词名称
+///
+/// 名称会直接成为文件名，只允许字母、数字、`-`、`_` 和 `.`，
+/// 不能以 `.` 开头，从而排除 `..`、路径分隔符和隐藏文件。
+pub fn validate_name(name: &str) -> AppResult<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::new(ErrorCode::InvalidArgument, tf("error.invalidPromptName", &[("name", &name)])))
+    }
+}
+
+/// 检查文件自 `expected`（Unix 毫秒）以来是否被修改，被修改时返回 `Conflict`
+pub fn check_unchanged(path: &Path, expected: u64) -> AppResult<()> {
+    let current = std::fs::metadata(path).map(|meta| modified_millis(&meta)).unwrap_or(0);
+    if current == expected {
+        Ok(())
+    } else {
+        Err(AppError::new(ErrorCode::Conflict, tf("error.conflict", &[("path", &path.display())])).with_path(path))
+    }
+}
+
+fn write_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.writeFile", &[("error", &e)]), path)
+}
+
+fn read_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.readFile", &[("error", &e)]), path)
+}
+
+/// 以目录中的 Markdown 文件为唯一数据来源的提示词存储
+pub struct PromptStore {
+    dir: PathBuf,
+    scope: PromptScope,
+    history: Option<HistoryStore>,
+}
+
+impl PromptStore {
+    pub fn new(dir: impl Into<PathBuf>, scope: PromptScope) -> Self {
+        Self { dir: dir.into(), scope, history: None }
+    }
+
+    /// 写入和重命名时同时维护版本历史
+    pub fn with_history(mut self, history: HistoryStore) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// 给出项目路径时为 `<project>/.codex/prompts`，否则为 `~/.codex/prompts`
+    pub fn for_scope(app_handle: &AppHandle, project_path: Option<&str>) -> AppResult<Self> {
+        let store = match project_path {
+            Some(project) => Self::new(Path::new(project).join(".codex").join("prompts"), PromptScope::Project),
+            None => app_handle
+                .path()
+                .home_dir()
+                .map(|home| Self::new(home.join(".codex").join("prompts"), PromptScope::User))
+                .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))?,
+        };
+        Ok(match HistoryStore::for_app(app_handle) {
+            Ok(history) => store.with_history(history),
+            Err(_) => store,
+        })
+    }
+
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.md", name))
+    }
+
+    fn existing_path(&self, name: &str) -> AppResult<PathBuf> {
+        validate_name(name)?;
+        let path = self.path_for(name);
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(AppError::new(ErrorCode::NotFound, tf("error.promptNotFound", &[("name", &name)])).with_path(&path))
+        }
+    }
+
+    /// 读取并解析一个提示词文件
+    pub fn load(&self, path: &Path) -> AppResult<Prompt> {
+        let text = std::fs::read_to_string(path).map_err(|e| read_error(e, path))?;
+        let meta = std::fs::metadata(path).map_err(|e| read_error(e, path))?;
+        let (frontmatter, content): (PromptFrontmatter, String) = parse_document(&text).map_err(|e| {
+            AppError::new(ErrorCode::InvalidConfig, tf("error.invalidFrontmatter", &[("error", &e)])).with_path(path)
+        })?;
+
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let updated_at = modified_millis(&meta);
+        let created_at = meta
+            .created()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(updated_at);
+        Ok(Prompt {
+            description: frontmatter.description.unwrap_or_else(|| format!("Prompt {}", name)),
+            category: frontmatter.category.unwrap_or_else(|| "general".to_string()),
+            allowed_tools: frontmatter.tools,
+            arguments: frontmatter.arguments,
+            scope: self.scope,
+            file_path: path.to_path_buf(),
+            name,
+            content,
+            created_at,
+            updated_at,
+        })
+    }
+
+    /// 列出目录中的所有提示词，按名称排序；无法解析的文件跳过，其错误一并返回
+    pub fn list(&self) -> (Vec<Prompt>, Vec<AppError>) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return (Vec::new(), Vec::new());
+        };
+        let (mut prompts, mut errors) = (Vec::new(), Vec::new());
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_file() || path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            match self.load(&path) {
+                Ok(prompt) => prompts.push(prompt),
+                Err(e) => errors.push(e),
+            }
+        }
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        (prompts, errors)
+    }
+
+    pub fn get(&self, name: &str) -> AppResult<Prompt> {
+        let path = self.existing_path(name)?;
+        self.load(&path)
+    }
+
+    /// 生成写入 `path` 的文件内容；已存在的文件保留 frontmatter 中未识别的键
+    pub fn render(&self, path: &Path, input: &PromptInput) -> AppResult<String> {
+        let extra = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| parse_document::<PromptFrontmatter>(&text).ok())
+            .map(|(frontmatter, _)| frontmatter.extra)
+            .unwrap_or_default();
+        let frontmatter = PromptFrontmatter {
+            description: input.description.clone().filter(|d| !d.trim().is_empty()),
+            category: input.category.clone().filter(|c| !c.trim().is_empty()),
+            tools: input.allowed_tools.iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+            arguments: input.arguments.clone(),
+            extra,
+        };
+        render_document(&frontmatter, &input.content).map_err(|e| write_error(e, path))
+    }
+
+    fn write(&self, path: &Path, input: &PromptInput) -> AppResult<Prompt> {
+        let text = self.render(path, input)?;
+        if let Some(history) = &self.history {
+            history.snapshot_existing(path)?;
+        }
+        write_atomic(path, text.as_bytes()).map_err(|e| write_error(e, path))?;
+        if let Some(history) = &self.history {
+            history.record(path, text.as_bytes(), VersionSource::Ui, input.message.clone())?;
+        }
+        self.load(path)
+    }
+
+    pub fn create(&self, name: &str, input: &PromptInput) -> AppResult<Prompt> {
+        validate_name(name)?;
+        let path = self.path_for(name);
+        if path.exists() {
+            return Err(AppError::new(ErrorCode::InvalidArgument, tf("error.promptExists", &[("name", &name)])).with_path(&path));
+        }
+        self.write(&path, input)
+    }
+
+    pub fn update(&self, name: &str, input: &PromptInput) -> AppResult<Prompt> {
+        let path = self.existing_path(name)?;
+        if let Some(expected) = input.expected_updated_at {
+            check_unchanged(&path, expected)?;
+        }
+        self.write(&path, input)
+    }
+
+    /// 重命名提示词文件；目标名称已存在时拒绝覆盖
+    pub fn rename(&self, name: &str, new_name: &str) -> AppResult<Prompt> {
+        let from = self.existing_path(name)?;
+        validate_name(new_name)?;
+        let to = self.path_for(new_name);
+        rename_no_replace(&from, &to).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::AlreadyExists {
+                AppError::new(ErrorCode::InvalidArgument, tf("error.promptExists", &[("name", &new_name)])).with_path(&to)
+            } else {
+                write_error(e, &to)
+            }
+        })?;
+        if let Some(history) = &self.history {
+            history.rename(&from, &to)?;
+        }
+        self.load(&to)
+    }
+
+    pub fn delete(&self, name: &str) -> AppResult<()> {
+        let path = self.existing_path(name)?;
+        std::fs::remove_file(&path).map_err(|e| write_error(e, &path))
+    }
+}
+
+/// Tauri 命令：列出提示词；给出 `projectPath` 时列出项目级提示词
+///
+/// 无法解析的文件不影响其他提示词，逐个通过 `app-error` 事件提示。
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_prompts(app_handle: AppHandle, project_path: Option<String>) -> AppResult<Vec<Prompt>> {
+    let (prompts, errors) = PromptStore::for_scope(&app_handle, project_path.as_deref())?.list();
+    for error in &errors {
+        error.report(&app_handle);
+    }
+    Ok(prompts)
+}
+
+/// Tauri 命令：读取单个提示词
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_prompt(app_handle: AppHandle, name: String, project_path: Option<String>) -> AppResult<Prompt> {
+    PromptStore::for_scope(&app_handle, project_path.as_deref())?.get(&name)
+}
+
+/// Tauri 命令：创建提示词，同名文件已存在时报错
+#[tauri::command(rename_all = "camelCase")]
+pub async fn create_prompt(
+    app_handle: AppHandle,
+    name: String,
+    prompt: PromptInput,
+    project_path: Option<String>,
+) -> AppResult<Prompt> {
+    PromptStore::for_scope(&app_handle, project_path.as_deref())?.create(&name, &prompt)
+}
+
+/// Tauri 命令：更新已有提示词
+#[tauri::command(rename_all = "camelCase")]
+pub async fn update_prompt(
+    app_handle: AppHandle,
+    name: String,
+    prompt: PromptInput,
+    project_path: Option<String>,
+) -> AppResult<Prompt> {
+    PromptStore::for_scope(&app_handle, project_path.as_deref())?.update(&name, &prompt)
+}
+
+/// Tauri 命令：重命名提示词
+#[tauri::command(rename_all = "camelCase")]
+pub async fn rename_prompt(
+    app_handle: AppHandle,
+    name: String,
+    new_name: String,
+    project_path: Option<String>,
+) -> AppResult<Prompt> {
+    PromptStore::for_scope(&app_handle, project_path.as_deref())?.rename(&name, &new_name)
+}
+
+/// Tauri 命令：删除提示词
+#[tauri::command(rename_all = "camelCase")]
+pub async fn delete_prompt(app_handle: AppHandle, name: String, project_path: Option<String>) -> AppResult<()> {
+    PromptStore::for_scope(&app_handle, project_path.as_deref())?.delete(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(content: &str) -> PromptInput {
+        PromptInput { content: content.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn names_must_be_plain_file_stems() {
+        for name in ["review", "code-review_v2", "release.notes", "代码审查"] {
+            assert!(validate_name(name).is_ok(), "{}", name);
+        }
+        let too_long = "a".repeat(129);
+        for name in ["", ".hidden", "..", "../escape", "a/b", "a\\b", "with space", too_long.as_str()] {
+            assert!(validate_name(name).is_err_and(|e| e.code == ErrorCode::InvalidArgument), "{}", name);
+        }
+    }
+
+    #[test]
+    fn splits_frontmatter_only_at_the_start() {
+        assert_eq!(split_frontmatter("---\na: 1\n---\nbody"), (Some("a: 1\n"), "body"));
+        assert_eq!(split_frontmatter("\u{feff}---\r\na: 1\r\n---\r\nbody"), (Some("a: 1\r\n"), "body"));
+        assert_eq!(split_frontmatter("---\n---\nbody"), (Some(""), "body"));
+        // 没有结束标记或不在首行时不视为 frontmatter
+        assert_eq!(split_frontmatter("---\na: 1\nbody"), (None, "---\na: 1\nbody"));
+        assert_eq!(split_frontmatter("text\n---\na: 1\n---\n"), (None, "text\n---\na: 1\n---\n"));
+        assert_eq!(split_frontmatter("----\nbody"), (None, "----\nbody"));
+    }
+
+    #[test]
+    fn documents_round_trip_unknown_keys() {
+        let text = "---\ndescription: Review\ntools: read, write\nmodel: gpt-5\n---\n\nBody\n";
+        let (frontmatter, body): (PromptFrontmatter, String) = parse_document(text).unwrap();
+        assert_eq!(frontmatter.tools, ["read", "write"]);
+        assert_eq!(body, "Body");
+        let rendered = render_document(&frontmatter, &body).unwrap();
+        assert!(rendered.contains("model: gpt-5"));
+        assert_eq!(render_document(&PromptFrontmatter::default(), " only body ").unwrap(), "only body\n");
+    }
+
+    #[test]
+    fn write_atomic_replaces_content_without_leaving_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("a.md");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        let names: Vec<_> = std::fs::read_dir(path.parent().unwrap()).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(names, ["a.md"]);
+    }
+
+    #[test]
+    fn check_unchanged_detects_external_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.md");
+        std::fs::write(&path, "x").unwrap();
+        let loaded = modified_millis(&std::fs::metadata(&path).unwrap());
+        assert!(check_unchanged(&path, loaded).is_ok());
+        assert!(check_unchanged(&path, loaded + 1).is_err_and(|e| e.code == ErrorCode::Conflict));
+        assert!(check_unchanged(&dir.path().join("missing.md"), loaded).is_err());
+    }
+
+    #[test]
+    fn update_rejects_stale_modification_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PromptStore::new(dir.path(), PromptScope::User);
+        let created = store.create("a", &input("one")).unwrap();
+
+        let stale = PromptInput { expected_updated_at: Some(created.updated_at + 1), ..input("two") };
+        assert!(store.update("a", &stale).is_err_and(|e| e.code == ErrorCode::Conflict));
+        let fresh = PromptInput { expected_updated_at: Some(created.updated_at), ..input("two") };
+        assert_eq!(store.update("a", &fresh).unwrap().content, "two");
+    }
+
+    #[test]
+    fn rename_never_overwrites_an_existing_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PromptStore::new(dir.path(), PromptScope::User);
+        store.create("a", &input("one")).unwrap();
+        store.create("b", &input("two")).unwrap();
+
+        assert!(store.rename("a", "b").is_err_and(|e| e.code == ErrorCode::InvalidArgument));
+        assert_eq!(store.get("a").unwrap().content, "one");
+        assert_eq!(store.get("b").unwrap().content, "two");
+
+        let renamed = store.rename("a", "c").unwrap();
+        assert_eq!((renamed.name.as_str(), renamed.content.as_str()), ("c", "one"));
+        assert!(store.get("a").is_err_and(|e| e.code == ErrorCode::NotFound));
+        assert!(store.rename("missing", "d").is_err_and(|e| e.code == ErrorCode::NotFound));
+    }
+
+    #[test]
+    fn list_returns_unreadable_files_as_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PromptStore::new(dir.path(), PromptScope::User);
+        store.create("b", &input("two")).unwrap();
+        store.create("a", &input("one")).unwrap();
+        std::fs::write(dir.path().join("broken.md"), "---\n[unclosed\n---\nbody").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let (prompts, errors) = store.list();
+        let names: Vec<_> = prompts.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, ErrorCode::InvalidConfig);
+    }
+}
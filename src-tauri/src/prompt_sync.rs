@@ -0,0 +1,716 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use git2::{build::CheckoutBuilder, Cred, FetchOptions, IndexAddOption, PushOptions, RemoteCallbacks, Repository, Signature};
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::tf;
+use crate::prompt_history::{unified_diff, HistoryStore, VersionSource};
+use crate::prompt_store::write_atomic;
+
+/// 仓库中提示词和命令所在的子目录
+const PROMPTS_DIR: &str = "codex/prompts";
+const COMMANDS_DIR: &str = "claude/commands";
+
+const REMOTE_NAME: &str = "origin";
+
+/// 同步配置，保存在 `<app_config>/prompt-sync.json`
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConfig {
+    /// 本地 git 仓库目录
+    pub repo_path: Option<String>,
+    /// 远程仓库地址，为空时只在本地提交
+    #[serde(default)]
+    pub remote_url: Option<String>,
+    #[serde(default)]
+    pub branch: Option<String>,
+    #[serde(default)]
+    pub author_name: Option<String>,
+    #[serde(default)]
+    pub author_email: Option<String>,
+}
+
+/// 合并远程分支的结果
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeOutcome {
+    /// 没有配置远程仓库或远程分支还不存在
+    NoRemote,
+    UpToDate,
+    FastForward,
+    Merged,
+    /// 有未解决的冲突，本地提示词和仓库都没有被修改
+    Conflicted,
+}
+
+/// 一个合并冲突；文件被删除的一方为空
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    /// 仓库内路径，例如 `codex/prompts/review.md`
+    pub path: String,
+    pub ancestor: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    /// 本地版本到远程版本的 unified diff
+    pub diff: String,
+}
+
+/// 冲突的解决方式
+#[derive(serde::Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ResolutionChoice {
+    Ours,
+    Theirs,
+    /// 使用 `content` 中手工合并的内容
+    Custom,
+}
+
+#[derive(serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictResolution {
+    pub path: String,
+    pub choice: ResolutionChoice,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// 一次同步的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// 本地修改是否产生了新的提交
+    pub committed: bool,
+    pub merge: MergeOutcome,
+    pub pushed: bool,
+    /// 从仓库写入或删除的本地文件
+    pub updated_files: Vec<PathBuf>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+fn git_error(e: impl std::fmt::Display) -> AppError {
+    AppError::new(ErrorCode::ProcessFailed, tf("error.git", &[("error", &e)]))
+}
+
+fn io_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.writeFile", &[("error", &e)]), path)
+}
+
+/// 目录下的 Markdown 文件的相对路径（使用 `/` 分隔），跳过隐藏文件和临时文件
+///
+/// 只有目录不存在时视为空目录；其他读取错误会中止同步，否则读不到的文件会被当作已删除。
+fn markdown_files(dir: &Path, recursive: bool) -> AppResult<BTreeSet<String>> {
+    let read_error = |e: std::io::Error, path: &Path| AppError::io(tf("error.readFile", &[("error", &e)]), path);
+    let mut files = BTreeSet::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = match std::fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(read_error(e, &current)),
+        };
+        for entry in entries {
+            let path = entry.map_err(|e| read_error(e, &current))?.path();
+            if path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) {
+                continue;
+            }
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "md") {
+                if let Ok(relative) = path.strip_prefix(dir) {
+                    let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+                    files.insert(parts.join("/"));
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// 仓库子目录与本地目录的对应关系
+struct Mapping {
+    repo_dir: &'static str,
+    local_dir: PathBuf,
+    recursive: bool,
+}
+
+/// 以 git 仓库为中转，在多台机器之间同步 `~/.codex/prompts` 和 `~/.claude/commands`
+///
+/// 同步分四步：把本地目录镜像到工作区并提交；拉取远程分支并合并；把合并结果镜像回本地目录；
+/// 推送。上次同步后工作区与本地目录一致，所以本地删除的文件会作为删除提交，
+/// 远程删除的文件会从本地删除。合并有冲突时不修改任何内容，
+/// 带上每个冲突的解决方式再次同步即可完成合并。
+pub struct PromptSync {
+    repo_dir: PathBuf,
+    remote_url: Option<String>,
+    branch: String,
+    author: Option<(String, String)>,
+    mappings: Vec<Mapping>,
+    history: Option<HistoryStore>,
+}
+
+impl PromptSync {
+    pub fn new(repo_dir: impl Into<PathBuf>, prompts_dir: impl Into<PathBuf>, commands_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            repo_dir: repo_dir.into(),
+            remote_url: None,
+            branch: "main".to_string(),
+            author: None,
+            mappings: vec![
+                Mapping { repo_dir: PROMPTS_DIR, local_dir: prompts_dir.into(), recursive: false },
+                Mapping { repo_dir: COMMANDS_DIR, local_dir: commands_dir.into(), recursive: true },
+            ],
+            history: None,
+        }
+    }
+
+    pub fn remote(mut self, url: impl Into<String>) -> Self {
+        self.remote_url = Some(url.into());
+        self
+    }
+
+    pub fn branch(mut self, branch: impl Into<String>) -> Self {
+        self.branch = branch.into();
+        self
+    }
+
+    pub fn author(mut self, name: impl Into<String>, email: impl Into<String>) -> Self {
+        self.author = Some((name.into(), email.into()));
+        self
+    }
+
+    /// 从仓库写入本地文件时同时记录版本历史
+    pub fn with_history(mut self, history: HistoryStore) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// 按保存的配置创建；没有配置仓库目录时报错
+    pub fn for_app(app_handle: &AppHandle) -> AppResult<Self> {
+        let config = load_config(app_handle)?;
+        let repo_path = config
+            .repo_path
+            .filter(|p| !p.trim().is_empty())
+            .ok_or_else(|| AppError::new(ErrorCode::InvalidConfig, tf("error.syncNotConfigured", &[])))?;
+        let home = app_handle
+            .path()
+            .home_dir()
+            .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))?;
+        let mut sync = Self::new(
+            repo_path,
+            home.join(".codex").join("prompts"),
+            home.join(".claude").join("commands"),
+        );
+        if let Some(url) = config.remote_url.filter(|u| !u.trim().is_empty()) {
+            sync = sync.remote(url);
+        }
+        if let Some(branch) = config.branch.filter(|b| !b.trim().is_empty()) {
+            sync = sync.branch(branch);
+        }
+        if let (Some(name), Some(email)) = (config.author_name, config.author_email) {
+            sync = sync.author(name, email);
+        }
+        if let Ok(history) = HistoryStore::for_app(app_handle) {
+            sync = sync.with_history(history);
+        }
+        Ok(sync)
+    }
+
+    fn branch_ref(&self) -> String {
+        format!("refs/heads/{}", self.branch)
+    }
+
+    fn remote_ref(&self) -> String {
+        format!("refs/remotes/{}/{}", REMOTE_NAME, self.branch)
+    }
+
+    /// 打开仓库；目录不是仓库时初始化，并按配置设置远程仓库地址
+    pub fn open(&self) -> AppResult<Repository> {
+        let repo = match Repository::open(&self.repo_dir) {
+            Ok(repo) => repo,
+            Err(_) => {
+                std::fs::create_dir_all(&self.repo_dir).map_err(|e| io_error(e, &self.repo_dir))?;
+                let repo = Repository::init(&self.repo_dir).map_err(git_error)?;
+                repo.set_head(&self.branch_ref()).map_err(git_error)?;
+                repo
+            }
+        };
+        if let Some(url) = &self.remote_url {
+            match repo.find_remote(REMOTE_NAME) {
+                Ok(remote) if remote.url() == Some(url.as_str()) => {}
+                Ok(_) => repo.remote_set_url(REMOTE_NAME, url).map_err(git_error)?,
+                Err(_) => {
+                    repo.remote(REMOTE_NAME, url).map_err(git_error)?;
+                }
+            }
+        }
+        Ok(repo)
+    }
+
+    fn signature(&self, repo: &Repository) -> AppResult<Signature<'static>> {
+        match &self.author {
+            Some((name, email)) => Signature::now(name, email),
+            None => repo.signature().or_else(|_| Signature::now("MakingStore", "makingstore@localhost")),
+        }
+        .map_err(git_error)
+    }
+
+    /// SSH 使用 ssh-agent，HTTPS 使用 git 配置的凭据助手
+    ///
+    /// 凭据被拒绝时 libgit2 会再次请求凭据，每种方式只尝试一次，之后返回错误结束认证。
+    fn callbacks(repo: &Repository) -> RemoteCallbacks<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        let (mut tried_ssh, mut tried_helper, mut tried_default) = (false, false, false);
+        callbacks.credentials(move |url, username, allowed| {
+            if allowed.is_ssh_key() && !tried_ssh {
+                tried_ssh = true;
+                return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+            }
+            if allowed.is_user_pass_plaintext() && !tried_helper {
+                tried_helper = true;
+                let config = repo.config()?;
+                return Cred::credential_helper(&config, url, username);
+            }
+            if allowed.is_default() && !tried_default {
+                tried_default = true;
+                return Cred::default();
+            }
+            Err(git2::Error::from_str(&tf("error.gitAuth", &[("url", &url)])))
+        });
+        callbacks
+    }
+
+    /// 第一步：把本地目录镜像到工作区，有变化时提交
+    fn commit_local(&self, repo: &Repository) -> AppResult<bool> {
+        for mapping in &self.mappings {
+            let repo_dir = self.repo_dir.join(mapping.repo_dir);
+            mirror(&mapping.local_dir, &repo_dir, mapping.recursive, None)?;
+        }
+        let mut index = repo.index().map_err(git_error)?;
+        let paths: Vec<&str> = self.mappings.iter().map(|m| m.repo_dir).collect();
+        index.add_all(paths.iter(), IndexAddOption::DEFAULT, None).map_err(git_error)?;
+        index.update_all(paths.iter(), None).map_err(git_error)?;
+        index.write().map_err(git_error)?;
+        let tree_id = index.write_tree().map_err(git_error)?;
+
+        let head = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let unchanged = match &head {
+            Some(commit) => commit.tree_id() == tree_id,
+            None => index.is_empty(),
+        };
+        if unchanged {
+            return Ok(false);
+        }
+        let tree = repo.find_tree(tree_id).map_err(git_error)?;
+        let signature = self.signature(repo)?;
+        let parents: Vec<&git2::Commit> = head.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, &tf("sync.commitMessage", &[]), &tree, &parents)
+            .map_err(git_error)?;
+        Ok(true)
+    }
+
+    fn fetch(&self, repo: &Repository) -> AppResult<Option<git2::Oid>> {
+        if self.remote_url.is_none() {
+            return Ok(None);
+        }
+        let mut remote = repo.find_remote(REMOTE_NAME).map_err(git_error)?;
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(Self::callbacks(repo));
+        let refspec = format!("+{}:{}", self.branch_ref(), self.remote_ref());
+        remote.fetch(&[refspec], Some(&mut options), None).map_err(git_error)?;
+        Ok(repo.refname_to_id(&self.remote_ref()).ok())
+    }
+
+    /// 第二步：合并远程分支；有冲突且没有给出全部解决方式时返回冲突，不做任何修改
+    fn merge(
+        &self,
+        repo: &Repository,
+        remote_id: git2::Oid,
+        resolutions: &[ConflictResolution],
+    ) -> AppResult<(MergeOutcome, Vec<SyncConflict>)> {
+        let theirs = repo.find_commit(remote_id).map_err(git_error)?;
+        let Some(ours) = repo.head().ok().and_then(|h| h.peel_to_commit().ok()) else {
+            self.move_branch(repo, remote_id)?;
+            return Ok((MergeOutcome::FastForward, Vec::new()));
+        };
+        if ours.id() == theirs.id() || repo.graph_descendant_of(ours.id(), theirs.id()).map_err(git_error)? {
+            return Ok((MergeOutcome::UpToDate, Vec::new()));
+        }
+        if repo.graph_descendant_of(theirs.id(), ours.id()).map_err(git_error)? {
+            self.move_branch(repo, remote_id)?;
+            return Ok((MergeOutcome::FastForward, Vec::new()));
+        }
+
+        let mut index = repo.merge_commits(&ours, &theirs, None).map_err(git_error)?;
+        if index.has_conflicts() {
+            let conflicts = collect_conflicts(repo, &index)?;
+            let resolved = conflicts.iter().all(|c| resolutions.iter().any(|r| r.path == c.path));
+            if !resolved {
+                return Ok((MergeOutcome::Conflicted, conflicts));
+            }
+            apply_resolutions(repo, &mut index, resolutions)?;
+        }
+
+        let tree_id = index.write_tree_to(repo).map_err(git_error)?;
+        let tree = repo.find_tree(tree_id).map_err(git_error)?;
+        let signature = self.signature(repo)?;
+        let message = tf("sync.mergeMessage", &[("branch", &self.branch)]);
+        let merged = repo
+            .commit(None, &signature, &signature, &message, &tree, &[&ours, &theirs])
+            .map_err(git_error)?;
+        self.move_branch(repo, merged)?;
+        Ok((MergeOutcome::Merged, Vec::new()))
+    }
+
+    /// 把当前分支指向 `target` 并强制检出；此时工作区与 HEAD 一致，不会丢失内容
+    fn move_branch(&self, repo: &Repository, target: git2::Oid) -> AppResult<()> {
+        repo.reference(&self.branch_ref(), target, true, "prompt sync").map_err(git_error)?;
+        repo.set_head(&self.branch_ref()).map_err(git_error)?;
+        repo.checkout_head(Some(CheckoutBuilder::new().force().remove_untracked(false)))
+            .map_err(git_error)
+    }
+
+    /// 第四步：本地分支领先远程时推送
+    fn push(&self, repo: &Repository, remote_id: Option<git2::Oid>) -> AppResult<bool> {
+        if self.remote_url.is_none() {
+            return Ok(false);
+        }
+        let Ok(local_id) = repo.refname_to_id(&self.branch_ref()) else {
+            return Ok(false);
+        };
+        if remote_id == Some(local_id) {
+            return Ok(false);
+        }
+
+        let rejected: RefCell<Option<String>> = RefCell::new(None);
+        let mut callbacks = Self::callbacks(repo);
+        callbacks.push_update_reference(|_, status| {
+            if let Some(status) = status {
+                *rejected.borrow_mut() = Some(status.to_string());
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        let mut remote = repo.find_remote(REMOTE_NAME).map_err(git_error)?;
+        let refspec = format!("{0}:{0}", self.branch_ref());
+        remote.push(&[refspec], Some(&mut options)).map_err(git_error)?;
+        drop(options);
+        if let Some(status) = rejected.into_inner() {
+            return Err(AppError::new(ErrorCode::Conflict, tf("error.syncPushRejected", &[("error", &status)])));
+        }
+        repo.reference(&self.remote_ref(), local_id, true, "prompt sync").map_err(git_error)?;
+        Ok(true)
+    }
+
+    /// 执行一次完整的同步
+    pub fn sync(&self, resolutions: &[ConflictResolution]) -> AppResult<SyncReport> {
+        let repo = self.open()?;
+        let committed = self.commit_local(&repo)?;
+        let remote_id = self.fetch(&repo)?;
+        let (merge, conflicts) = match remote_id {
+            Some(id) => self.merge(&repo, id, resolutions)?,
+            None => (MergeOutcome::NoRemote, Vec::new()),
+        };
+        if merge == MergeOutcome::Conflicted {
+            return Ok(SyncReport { committed, merge, pushed: false, updated_files: Vec::new(), conflicts });
+        }
+
+        let mut updated_files = Vec::new();
+        for mapping in &self.mappings {
+            let repo_dir = self.repo_dir.join(mapping.repo_dir);
+            updated_files.extend(mirror(&repo_dir, &mapping.local_dir, mapping.recursive, self.history.as_ref())?);
+        }
+        let pushed = self.push(&repo, remote_id)?;
+        Ok(SyncReport { committed, merge, pushed, updated_files, conflicts })
+    }
+}
+
+/// 把 `from` 中的 Markdown 文件镜像到 `to`：新增和修改的写入，`to` 中多余的删除
+///
+/// 写入本地目录时传入 `history`，被覆盖和删除前的内容会记入版本历史。返回被修改的文件。
+fn mirror(from: &Path, to: &Path, recursive: bool, history: Option<&HistoryStore>) -> AppResult<Vec<PathBuf>> {
+    let source = markdown_files(from, recursive)?;
+    let target = markdown_files(to, recursive)?;
+    let mut changed = Vec::new();
+    let message = tf("sync.historyMessage", &[]);
+
+    for relative in &source {
+        let src = from.join(relative);
+        let dst = to.join(relative);
+        let content = std::fs::read(&src).map_err(|e| io_error(e, &src))?;
+        if std::fs::read(&dst).ok().as_deref() == Some(content.as_slice()) {
+            continue;
+        }
+        if let Some(history) = history {
+            history.snapshot_existing(&dst)?;
+        }
+        write_atomic(&dst, &content).map_err(|e| io_error(e, &dst))?;
+        if let Some(history) = history {
+            history.record(&dst, &content, VersionSource::External, Some(message.clone()))?;
+        }
+        changed.push(dst);
+    }
+    for relative in target.difference(&source) {
+        let dst = to.join(relative);
+        if let Some(history) = history {
+            history.snapshot_existing(&dst)?;
+        }
+        std::fs::remove_file(&dst).map_err(|e| io_error(e, &dst))?;
+        changed.push(dst);
+    }
+    Ok(changed)
+}
+
+fn blob_text(repo: &Repository, entry: Option<&git2::IndexEntry>) -> Option<String> {
+    let entry = entry?;
+    let blob = repo.find_blob(entry.id).ok()?;
+    Some(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+fn collect_conflicts(repo: &Repository, index: &git2::Index) -> AppResult<Vec<SyncConflict>> {
+    let mut conflicts = Vec::new();
+    for conflict in index.conflicts().map_err(git_error)? {
+        let conflict = conflict.map_err(git_error)?;
+        let path = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .flatten()
+            .next()
+            .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+            .unwrap_or_default();
+        let ours = blob_text(repo, conflict.our.as_ref());
+        let theirs = blob_text(repo, conflict.their.as_ref());
+        let (diff, _, _) = unified_diff(ours.as_deref().unwrap_or(""), theirs.as_deref().unwrap_or(""), "ours", "theirs");
+        conflicts.push(SyncConflict {
+            ancestor: blob_text(repo, conflict.ancestor.as_ref()),
+            path,
+            ours,
+            theirs,
+            diff,
+        });
+    }
+    Ok(conflicts)
+}
+
+/// 按解决方式把冲突条目替换为单一版本；选中被删除的一方时删除该文件
+fn apply_resolutions(repo: &Repository, index: &mut git2::Index, resolutions: &[ConflictResolution]) -> AppResult<()> {
+    let conflicts: Vec<git2::IndexConflict> = index
+        .conflicts()
+        .map_err(git_error)?
+        .collect::<Result<_, _>>()
+        .map_err(git_error)?;
+    for conflict in conflicts {
+        let our_id = conflict.our.as_ref().map(|e| e.id);
+        let their_id = conflict.their.as_ref().map(|e| e.id);
+        let Some(template) = conflict.our.or(conflict.their).or(conflict.ancestor) else {
+            continue;
+        };
+        let path = String::from_utf8_lossy(&template.path).into_owned();
+        let Some(resolution) = resolutions.iter().find(|r| r.path == path) else {
+            continue;
+        };
+        let id = match resolution.choice {
+            ResolutionChoice::Ours => our_id,
+            ResolutionChoice::Theirs => their_id,
+            ResolutionChoice::Custom => {
+                let content = resolution.content.as_deref().unwrap_or_default();
+                Some(repo.blob(content.as_bytes()).map_err(git_error)?)
+            }
+        };
+        index.conflict_remove(Path::new(&path)).map_err(git_error)?;
+        if let Some(id) = id {
+            let entry = git2::IndexEntry {
+                id,
+                // 清除冲突阶段位，只保留路径长度
+                flags: template.path.len().min(0xfff) as u16,
+                flags_extended: 0,
+                ..template
+            };
+            index.add(&entry).map_err(git_error)?;
+        }
+    }
+    Ok(())
+}
+
+fn config_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join("prompt-sync.json"))
+        .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))
+}
+
+pub fn load_config(app_handle: &AppHandle) -> AppResult<SyncConfig> {
+    let path = config_path(app_handle)?;
+    Ok(std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default())
+}
+
+/// Tauri 命令：读取同步配置
+#[tauri::command]
+pub async fn get_prompt_sync_config(app_handle: AppHandle) -> AppResult<SyncConfig> {
+    load_config(&app_handle)
+}
+
+/// Tauri 命令：保存同步配置，并初始化仓库目录
+#[tauri::command]
+pub async fn set_prompt_sync_config(app_handle: AppHandle, config: SyncConfig) -> AppResult<SyncConfig> {
+    let path = config_path(&app_handle)?;
+    let content = serde_json::to_string_pretty(&config).map_err(|e| io_error(e, &path))?;
+    write_atomic(&path, content.as_bytes()).map_err(|e| io_error(e, &path))?;
+    if config.repo_path.as_deref().is_some_and(|p| !p.trim().is_empty()) {
+        PromptSync::for_app(&app_handle)?.open()?;
+    }
+    Ok(config)
+}
+
+/// Tauri 命令：同步提示词库；返回冲突时带上 `resolutions` 再次调用以完成合并
+#[tauri::command]
+pub async fn sync_prompt_library(app_handle: AppHandle, resolutions: Option<Vec<ConflictResolution>>) -> AppResult<SyncReport> {
+    let sync = PromptSync::for_app(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || sync.sync(&resolutions.unwrap_or_default()))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 一台“机器”：自己的仓库克隆目录和本地提示词目录
+    struct Machine {
+        root: tempfile::TempDir,
+        sync: PromptSync,
+    }
+
+    impl Machine {
+        fn new(remote: &Path) -> Self {
+            let root = tempfile::tempdir().unwrap();
+            let sync = PromptSync::new(root.path().join("repo"), root.path().join("prompts"), root.path().join("commands"))
+                .remote(remote.to_string_lossy())
+                .author("Tester", "tester@example.com");
+            Self { root, sync }
+        }
+
+        fn prompt(&self, name: &str) -> PathBuf {
+            self.root.path().join("prompts").join(format!("{}.md", name))
+        }
+
+        fn write(&self, name: &str, text: &str) {
+            write_atomic(&self.prompt(name), text.as_bytes()).unwrap();
+        }
+
+        fn read(&self, name: &str) -> Option<String> {
+            std::fs::read_to_string(self.prompt(name)).ok()
+        }
+    }
+
+    fn bare_remote() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init_bare(dir.path()).unwrap();
+        dir
+    }
+
+    #[test]
+    fn unreadable_directories_abort_instead_of_looking_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(markdown_files(&dir.path().join("missing"), false).unwrap().is_empty());
+
+        // 读取失败（这里是路径指向一个文件）不能当作空目录，否则镜像时会删除另一侧的全部文件
+        let blocked = dir.path().join("blocked");
+        std::fs::write(&blocked, "").unwrap();
+        let target = dir.path().join("target");
+        write_atomic(&target.join("keep.md"), b"Keep\n").unwrap();
+        assert!(mirror(&blocked, &target, false, None).is_err());
+        assert!(target.join("keep.md").is_file());
+    }
+
+    #[test]
+    fn changes_travel_between_machines() {
+        let remote = bare_remote();
+        let a = Machine::new(remote.path());
+        let b = Machine::new(remote.path());
+
+        a.write("review", "Review $1\n");
+        write_atomic(&a.root.path().join("commands").join("git").join("commit.md"), b"Commit\n").unwrap();
+        let report = a.sync.sync(&[]).unwrap();
+        assert!(report.committed && report.pushed);
+        assert_eq!(report.merge, MergeOutcome::NoRemote);
+
+        let report = b.sync.sync(&[]).unwrap();
+        assert_eq!(report.merge, MergeOutcome::FastForward);
+        assert_eq!(b.read("review").as_deref(), Some("Review $1\n"));
+        assert!(b.root.path().join("commands").join("git").join("commit.md").is_file());
+
+        // B 删除后 A 同步时也删除
+        std::fs::remove_file(b.prompt("review")).unwrap();
+        assert!(b.sync.sync(&[]).unwrap().pushed);
+        a.sync.sync(&[]).unwrap();
+        assert!(a.read("review").is_none());
+    }
+
+    #[test]
+    fn independent_edits_are_merged() {
+        let remote = bare_remote();
+        let a = Machine::new(remote.path());
+        let b = Machine::new(remote.path());
+        a.write("shared", "base\n");
+        a.sync.sync(&[]).unwrap();
+        b.sync.sync(&[]).unwrap();
+
+        a.write("from-a", "a\n");
+        a.sync.sync(&[]).unwrap();
+        b.write("from-b", "b\n");
+        let report = b.sync.sync(&[]).unwrap();
+        assert_eq!(report.merge, MergeOutcome::Merged);
+        assert!(report.pushed);
+        assert_eq!(b.read("from-a").as_deref(), Some("a\n"));
+
+        a.sync.sync(&[]).unwrap();
+        assert_eq!(a.read("from-b").as_deref(), Some("b\n"));
+    }
+
+    #[test]
+    fn conflicts_are_reported_then_resolved() {
+        let remote = bare_remote();
+        let a = Machine::new(remote.path());
+        let b = Machine::new(remote.path());
+        a.write("shared", "base\n");
+        a.sync.sync(&[]).unwrap();
+        b.sync.sync(&[]).unwrap();
+
+        a.write("shared", "from a\n");
+        a.sync.sync(&[]).unwrap();
+        b.write("shared", "from b\n");
+
+        let report = b.sync.sync(&[]).unwrap();
+        assert_eq!(report.merge, MergeOutcome::Conflicted);
+        assert!(!report.pushed);
+        let conflict = &report.conflicts[0];
+        assert_eq!(conflict.path, "codex/prompts/shared.md");
+        assert_eq!(conflict.ancestor.as_deref(), Some("base\n"));
+        assert_eq!(conflict.ours.as_deref(), Some("from b\n"));
+        assert_eq!(conflict.theirs.as_deref(), Some("from a\n"));
+        assert!(conflict.diff.contains("-from b\n+from a\n"));
+        assert_eq!(b.read("shared").as_deref(), Some("from b\n"));
+
+        let resolution = ConflictResolution {
+            path: conflict.path.clone(),
+            choice: ResolutionChoice::Custom,
+            content: Some("from a and b\n".to_string()),
+        };
+        let report = b.sync.sync(&[resolution]).unwrap();
+        assert_eq!(report.merge, MergeOutcome::Merged);
+        assert_eq!(b.read("shared").as_deref(), Some("from a and b\n"));
+
+        a.sync.sync(&[]).unwrap();
+        assert_eq!(a.read("shared").as_deref(), Some("from a and b\n"));
+    }
+}
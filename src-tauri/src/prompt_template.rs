@@ -0,0 +1,471 @@
+use std::collections::BTreeMap;
+
+use tauri::AppHandle;
+
+use crate::error::AppResult;
+use crate::i18n::tf;
+use crate::prompt_store::{ArgumentType, PromptArgument, PromptStore};
+
+/// 展开为全部参数的占位符
+const ARGUMENTS: &str = "$ARGUMENTS";
+
+/// 参数值：前端表单可能传入字符串、数字或布尔值
+pub type ArgumentValues = BTreeMap<String, serde_json::Value>;
+
+/// 渲染错误的类型
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum TemplateErrorKind {
+    /// 必填参数没有值也没有默认值
+    MissingArgument,
+    /// 值不是合法的数字
+    InvalidNumber,
+    /// 值不是合法的布尔值
+    InvalidBoolean,
+    /// 传入了未声明的参数
+    UnknownArgument,
+    /// 占位符没有对应的参数或值
+    UndefinedPlaceholder,
+    /// `{{` 没有闭合
+    UnclosedPlaceholder,
+}
+
+/// 一个渲染错误；占位符相关的错误带有位置（从 1 开始，列按字符计）
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateError {
+    pub kind: TemplateErrorKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+/// 渲染结果；有错误时 `text` 为空
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderResult {
+    pub text: Option<String>,
+    /// 最终使用的参数值（已应用默认值并规范化）
+    pub values: BTreeMap<String, String>,
+    pub errors: Vec<TemplateError>,
+}
+
+/// 把 `/command` 后面的参数串按空白切分，支持单双引号包裹含空格的参数
+pub fn split_arguments(raw: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_arg = false;
+    for c in raw.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            None if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// 模板中的一段
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Token<'a> {
+    /// 原样输出的文本
+    Text(&'a str),
+    /// `$$`，字面量 `$`
+    Dollar,
+    /// `$1`..`$9`
+    Positional(usize),
+    /// `$ARGUMENTS`
+    All,
+    /// `{{name}}`，名称已去除首尾空白
+    Named(&'a str),
+    /// 没有在同一行闭合的 `{{`
+    Unclosed,
+}
+
+/// 一段模板及其在原文中的文本和起始位置（从 1 开始，列按字符计）
+#[derive(Clone, Copy, Debug)]
+pub struct Span<'a> {
+    pub token: Token<'a>,
+    pub source: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// 把模板切分为文本和占位符；渲染、检查和格式转换都以此为准，保证对占位符的理解一致
+pub fn tokenize(content: &str) -> Vec<Span<'_>> {
+    fn advance(text: &str, line: &mut usize, column: &mut usize) {
+        for c in text.chars() {
+            if c == '\n' {
+                *line += 1;
+                *column = 1;
+            } else {
+                *column += 1;
+            }
+        }
+    }
+
+    let mut spans = Vec::new();
+    let (mut line, mut column) = (1, 1);
+    let (mut text_start, mut text_line, mut text_column) = (0, 1, 1);
+    let mut i = 0;
+    while i < content.len() {
+        let rest = &content[i..];
+        let positional = rest.strip_prefix('$').and_then(|r| r.chars().next()).and_then(|d| d.to_digit(10)).filter(|&d| d > 0);
+        let special = if rest.starts_with("$$") {
+            Some((Token::Dollar, 2))
+        } else if rest.starts_with(ARGUMENTS) {
+            Some((Token::All, ARGUMENTS.len()))
+        } else if let Some(d) = positional {
+            Some((Token::Positional(d as usize), 2))
+        } else if let Some(inner) = rest.strip_prefix("{{") {
+            let line_end = inner.find('\n').unwrap_or(inner.len());
+            match inner[..line_end].find("}}") {
+                Some(end) => Some((Token::Named(inner[..end].trim()), end + 4)),
+                None => Some((Token::Unclosed, 2)),
+            }
+        } else {
+            None
+        };
+        let Some((token, len)) = special else {
+            let c = rest.chars().next().unwrap_or_default();
+            advance(&rest[..c.len_utf8()], &mut line, &mut column);
+            i += c.len_utf8();
+            continue;
+        };
+        if text_start < i {
+            let text = &content[text_start..i];
+            spans.push(Span { token: Token::Text(text), source: text, line: text_line, column: text_column });
+        }
+        spans.push(Span { token, source: &rest[..len], line, column });
+        advance(&rest[..len], &mut line, &mut column);
+        i += len;
+        (text_start, text_line, text_column) = (i, line, column);
+    }
+    if text_start < content.len() {
+        let text = &content[text_start..];
+        spans.push(Span { token: Token::Text(text), source: text, line: text_line, column: text_column });
+    }
+    spans
+}
+
+fn error(kind: TemplateErrorKind, argument: Option<&str>, key: &str, args: &[(&str, &dyn std::fmt::Display)]) -> TemplateError {
+    TemplateError {
+        kind,
+        argument: argument.map(str::to_string),
+        line: None,
+        column: None,
+        message: tf(key, args),
+    }
+}
+
+/// 校验并规范化一个参数值：数字保持原样，布尔值统一为 `true`/`false`
+fn normalize(argument: &PromptArgument, value: &str) -> Result<String, TemplateError> {
+    let name = argument.name.as_str();
+    match argument.arg_type {
+        ArgumentType::String => Ok(value.to_string()),
+        ArgumentType::Number => match value.trim().parse::<f64>() {
+            Ok(n) if n.is_finite() => Ok(value.trim().to_string()),
+            _ => Err(error(
+                TemplateErrorKind::InvalidNumber,
+                Some(name),
+                "template.invalidNumber",
+                &[("name", &name), ("value", &value)],
+            )),
+        },
+        ArgumentType::Boolean => match value.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+            "false" | "no" | "off" | "0" => Ok("false".to_string()),
+            _ => Err(error(
+                TemplateErrorKind::InvalidBoolean,
+                Some(name),
+                "template.invalidBoolean",
+                &[("name", &name), ("value", &value)],
+            )),
+        },
+    }
+}
+
+fn value_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// 按 Codex 和 Claude 自定义命令的约定渲染模板
+///
+/// - `$1`..`$9`：第 N 个位置参数；声明的参数按顺序对应位置，命名值优先于位置值
+/// - `$ARGUMENTS`：原始参数串，未给出时为所有位置参数以空格连接
+/// - `{{name}}`：按名称引用声明的参数
+/// - `$$`：字面量 `$`
+///
+/// 所有问题一次性收集，方便界面逐项标出。
+pub fn render(content: &str, arguments: &[PromptArgument], values: &ArgumentValues, raw: Option<&str>) -> RenderResult {
+    let mut errors = Vec::new();
+    let positional = raw.map(split_arguments).unwrap_or_default();
+
+    for name in values.keys() {
+        if !arguments.iter().any(|a| &a.name == name) {
+            errors.push(error(TemplateErrorKind::UnknownArgument, Some(name), "template.unknownArgument", &[("name", name)]));
+        }
+    }
+
+    // 声明参数的最终值，未提供且无默认值的可选参数为空字符串
+    let mut resolved: BTreeMap<String, String> = BTreeMap::new();
+    let mut ordered: Vec<Option<String>> = Vec::new();
+    for (i, argument) in arguments.iter().enumerate() {
+        let given = values
+            .get(&argument.name)
+            .and_then(value_to_string)
+            .or_else(|| positional.get(i).cloned())
+            .filter(|v| !v.is_empty())
+            .or_else(|| argument.default_value.clone().filter(|v| !v.is_empty()));
+        let value = match given {
+            Some(value) => match normalize(argument, &value) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    errors.push(e);
+                    None
+                }
+            },
+            None if argument.required => {
+                errors.push(error(
+                    TemplateErrorKind::MissingArgument,
+                    Some(&argument.name),
+                    "template.missingArgument",
+                    &[("name", &argument.name)],
+                ));
+                None
+            }
+            None => Some(String::new()),
+        };
+        if let Some(value) = &value {
+            resolved.insert(argument.name.clone(), value.clone());
+        }
+        ordered.push(value);
+    }
+    // 超出声明数量的位置参数仍可通过 `$N` 引用
+    ordered.extend(positional.iter().skip(arguments.len()).cloned().map(Some));
+
+    let all_arguments = match raw {
+        Some(raw) => raw.trim().to_string(),
+        None => ordered.iter().flatten().filter(|v| !v.is_empty()).cloned().collect::<Vec<_>>().join(" "),
+    };
+
+    let mut text = String::with_capacity(content.len());
+    let at = |kind, argument: Option<&str>, key: &str, args: &[(&str, &dyn std::fmt::Display)], span: &Span| {
+        let mut e = error(kind, argument, key, args);
+        e.line = Some(span.line);
+        e.column = Some(span.column);
+        e
+    };
+
+    for span in tokenize(content) {
+        match span.token {
+            Token::Text(source) => text.push_str(source),
+            Token::Dollar => text.push('$'),
+            Token::All => text.push_str(&all_arguments),
+            Token::Positional(position) => match ordered.get(position - 1) {
+                Some(Some(value)) => text.push_str(value),
+                // 参数本身有错误，已经报告过
+                Some(None) => {}
+                None => errors.push(at(
+                    TemplateErrorKind::UndefinedPlaceholder,
+                    None,
+                    "template.undefinedPlaceholder",
+                    &[("placeholder", &span.source)],
+                    &span,
+                )),
+            },
+            Token::Named(name) => match resolved.get(name) {
+                Some(value) => text.push_str(value),
+                None if arguments.iter().any(|a| a.name == name) => {}
+                None => {
+                    let placeholder = format!("{{{{{}}}}}", name);
+                    errors.push(at(
+                        TemplateErrorKind::UndefinedPlaceholder,
+                        Some(name),
+                        "template.undefinedPlaceholder",
+                        &[("placeholder", &placeholder)],
+                        &span,
+                    ));
+                }
+            },
+            Token::Unclosed => {
+                errors.push(at(TemplateErrorKind::UnclosedPlaceholder, None, "template.unclosedPlaceholder", &[], &span));
+                text.push_str(span.source);
+            }
+        }
+    }
+
+    RenderResult {
+        text: errors.is_empty().then_some(text),
+        values: resolved,
+        errors,
+    }
+}
+
+/// Tauri 命令：用给定参数渲染已保存的提示词
+#[tauri::command(rename_all = "camelCase")]
+pub async fn render_prompt(
+    app_handle: AppHandle,
+    name: String,
+    values: ArgumentValues,
+    raw_arguments: Option<String>,
+    project_path: Option<String>,
+) -> AppResult<RenderResult> {
+    let prompt = PromptStore::for_scope(&app_handle, project_path.as_deref())?.get(&name)?;
+    Ok(render(&prompt.content, &prompt.arguments, &values, raw_arguments.as_deref()))
+}
+
+/// Tauri 命令：渲染尚未保存的模板，用于编辑时预览
+#[tauri::command(rename_all = "camelCase")]
+pub async fn render_template(
+    content: String,
+    arguments: Vec<PromptArgument>,
+    values: ArgumentValues,
+    raw_arguments: Option<String>,
+) -> AppResult<RenderResult> {
+    Ok(render(&content, &arguments, &values, raw_arguments.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn argument(name: &str, arg_type: ArgumentType, required: bool, default_value: Option<&str>) -> PromptArgument {
+        PromptArgument {
+            name: name.to_string(),
+            arg_type,
+            required,
+            description: None,
+            default_value: default_value.map(str::to_string),
+        }
+    }
+
+    fn values(pairs: &[(&str, serde_json::Value)]) -> ArgumentValues {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    fn kinds(result: &RenderResult) -> Vec<TemplateErrorKind> {
+        result.errors.iter().map(|e| e.kind).collect()
+    }
+
+    #[test]
+    fn tokenizes_placeholders_with_positions() {
+        let spans = tokenize("a $$1 $2\n{{ name }} $ARGUMENTS {{open\n}}");
+        let tokens: Vec<Token> = spans.iter().map(|s| s.token).collect();
+        assert_eq!(
+            tokens,
+            [
+                Token::Text("a "),
+                Token::Dollar,
+                Token::Text("1 "),
+                Token::Positional(2),
+                Token::Text("\n"),
+                Token::Named("name"),
+                Token::Text(" "),
+                Token::All,
+                Token::Text(" "),
+                Token::Unclosed,
+                Token::Text("open\n}}"),
+            ]
+        );
+        let named = spans.iter().find(|s| matches!(s.token, Token::Named(_))).unwrap();
+        assert_eq!((named.line, named.column, named.source), (2, 1, "{{ name }}"));
+        assert_eq!(spans.iter().map(|s| s.source).collect::<String>(), "a $$1 $2\n{{ name }} $ARGUMENTS {{open\n}}");
+    }
+
+    #[test]
+    fn splits_quoted_arguments() {
+        assert_eq!(split_arguments(r#"src/main.rs "fix the bug" '' x"#), ["src/main.rs", "fix the bug", "", "x"]);
+    }
+
+    #[test]
+    fn substitutes_positional_named_and_all_arguments() {
+        let args = [
+            argument("file", ArgumentType::String, true, None),
+            argument("depth", ArgumentType::Number, false, Some("2")),
+        ];
+        let result = render(
+            "Review $1 at depth {{ depth }} ($ARGUMENTS) costs $$5",
+            &args,
+            &values(&[("file", json!("lib.rs"))]),
+            None,
+        );
+        assert_eq!(result.text.as_deref(), Some("Review lib.rs at depth 2 (lib.rs 2) costs $5"));
+    }
+
+    #[test]
+    fn raw_arguments_fill_positions_and_arguments_placeholder() {
+        let args = [argument("target", ArgumentType::String, true, None)];
+        let result = render("$1 / $2 / $ARGUMENTS", &args, &ArgumentValues::new(), Some("api \"extra words\""));
+        assert_eq!(result.text.as_deref(), Some("api / extra words / api \"extra words\""));
+    }
+
+    #[test]
+    fn validates_types_and_required_arguments() {
+        let args = [
+            argument("count", ArgumentType::Number, true, None),
+            argument("dry_run", ArgumentType::Boolean, false, None),
+            argument("title", ArgumentType::String, true, None),
+        ];
+        let result = render(
+            "{{count}} {{dry_run}} {{title}}",
+            &args,
+            &values(&[("count", json!("many")), ("dry_run", json!("maybe")), ("bogus", json!(1))]),
+            None,
+        );
+        assert!(result.text.is_none());
+        assert_eq!(
+            kinds(&result),
+            [
+                TemplateErrorKind::UnknownArgument,
+                TemplateErrorKind::InvalidNumber,
+                TemplateErrorKind::InvalidBoolean,
+                TemplateErrorKind::MissingArgument,
+            ]
+        );
+
+        let ok = render("{{dry_run}}", &args[1..2], &values(&[("dry_run", json!(true))]), None);
+        assert_eq!(ok.text.as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn reports_placeholder_positions() {
+        let result = render("first line\n  {{missing}} and $3\n{{open", &[], &ArgumentValues::new(), None);
+        let positions: Vec<(TemplateErrorKind, Option<usize>, Option<usize>)> =
+            result.errors.iter().map(|e| (e.kind, e.line, e.column)).collect();
+        assert_eq!(
+            positions,
+            [
+                (TemplateErrorKind::UndefinedPlaceholder, Some(2), Some(3)),
+                (TemplateErrorKind::UndefinedPlaceholder, Some(2), Some(19)),
+                (TemplateErrorKind::UnclosedPlaceholder, Some(3), Some(1)),
+            ]
+        );
+    }
+}
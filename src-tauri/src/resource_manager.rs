@@ -1,61 +1,554 @@
-use std::path::PathBuf;
-use tauri::path::BaseDirectory;
-use tauri::{AppHandle, Manager};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, SystemTime};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
 
-/// 资源管理器，用于处理外部可执行文件和资源
-pub struct ResourceManager {
-    app_handle: AppHandle,
+use crate::command_policy::{self, ExecContext};
+use crate::command_job::{spawn_job, OutputMode};
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::{t, tf};
+use crate::tool_registry::{InstallReceipt, ToolStore};
+use crate::process::{check_output, kill_process, spawn_audited, ProcessBuilder, SpawnContext};
+
+/// 覆盖资源目录的环境变量
+const RESOURCE_DIR_ENV: &str = "MAKINGSTORE_RESOURCE_DIR";
+
+/// 写入 stdin 的内容：字符串或字节数组
+#[derive(serde::Deserialize, Clone)]
+#[serde(untagged)]
+pub enum StdinInput {
+    Text(String),
+    Bytes(Vec<u8>),
 }
 
-impl ResourceManager {
-    pub fn new(app_handle: AppHandle) -> Self {
-        Self { app_handle }
+impl StdinInput {
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            StdinInput::Text(text) => text.into_bytes(),
+            StdinInput::Bytes(bytes) => bytes,
+        }
     }
+}
 
-    /// 获取指定平台的可执行文件路径
-    pub fn get_executable_path(&self, exe_name: &str) -> Result<PathBuf, String> {
-        let platform_dir = self.get_platform_dir();
-        let exe_path = format!("bin/{}/{}", platform_dir, exe_name);
-        
-        // 首先尝试标准的资源路径解析
-        match self.app_handle
-            .path()
-            .resolve(&exe_path, BaseDirectory::Resource) {
-            Ok(path) => {
-                if path.exists() {
-                    return Ok(path);
-                }
-                
-                // 如果标准路径不存在，尝试开发模式的路径修正
-                if let Some(corrected_path) = self.try_dev_mode_path_correction(&path) {
-                    if corrected_path.exists() {
-                        return Ok(corrected_path);
-                    }
-                }
-                
-                // 返回原始路径，即使不存在
-                Ok(path)
+/// 执行内置工具的可选参数
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolOptions {
+    /// 工作目录
+    pub cwd: Option<String>,
+    /// 额外注入的环境变量
+    pub env: Option<HashMap<String, String>>,
+    /// 写入 stdin 的内容，写完后关闭 stdin
+    pub stdin: Option<StdinInput>,
+    /// 超时时间（毫秒），超时后强制结束进程
+    pub timeout_ms: Option<u64>,
+    /// 是否以流式方式执行
+    #[serde(default)]
+    pub stream: bool,
+}
+
+/// 内置工具清单相对资源根目录的路径
+const MANIFEST_PATH: &str = "bin/manifest.json";
+
+/// 资源根目录的来源
+#[derive(serde::Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum RootSource {
+    /// 环境变量 `MAKINGSTORE_RESOURCE_DIR`
+    Override,
+    /// 安装包内的资源目录
+    Bundle,
+    /// debug 构建记录的源码资源目录
+    Dev,
+}
+
+/// 一个资源根目录；不可用时记录原因
+struct ResourceRoot {
+    source: RootSource,
+    dir: Result<PathBuf, String>,
+}
+
+/// 资源根目录的诊断信息
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootInfo {
+    pub source: RootSource,
+    pub dir: Option<PathBuf>,
+    pub unavailable: Option<String>,
+}
+
+/// 一次候选路径检查的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateCheck {
+    pub source: RootSource,
+    pub relative: PathBuf,
+    pub path: Option<PathBuf>,
+    pub found: bool,
+    /// 未命中的原因
+    pub rejected: Option<String>,
+}
+
+/// `diagnose_resource_paths` 的结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceDiagnostics {
+    pub roots: Vec<RootInfo>,
+    pub candidates: Vec<CandidateCheck>,
+}
+
+/// `bin/manifest.json` 中描述的一个内置工具
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolEntry {
+    /// 逻辑名，例如 `making-mcp`
+    pub name: String,
+    /// 不含扩展名的可执行文件名，例如 `MakingMcp.Web`
+    pub executable: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// 平台目录到 SHA-256（十六进制）的映射，例如 `"windows-x64": "ab12..."`
+    #[serde(default)]
+    pub sha256: HashMap<String, String>,
+    /// 工具运行所需的权限说明，例如 `network`、`filesystem`
+    #[serde(default)]
+    pub permissions: Vec<String>,
+}
+
+/// 校验和不匹配时的处理方式
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Verification {
+    /// 拒绝执行
+    #[default]
+    Enforce,
+    /// 仅打印警告，仍然执行
+    Warn,
+}
+
+/// 内置工具清单
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolManifest {
+    #[serde(default)]
+    pub verification: Verification,
+    #[serde(default)]
+    pub tools: Vec<ToolEntry>,
+}
+
+/// 可执行文件的校验状态
+#[derive(serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChecksumStatus {
+    /// 与清单一致
+    Verified,
+    /// 与清单不一致
+    Mismatch,
+    /// 清单中没有当前平台的校验和
+    Unverified,
+    /// 文件不存在
+    Missing,
+}
+
+/// 执行前对可执行文件做过的处理
+#[derive(serde::Serialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PrepareAction {
+    /// 原地补上了可执行权限
+    #[cfg_attr(not(unix), allow(dead_code))]
+    PermissionFixed { path: PathBuf },
+    /// 原位置不可写，复制到缓存目录后授权
+    #[cfg_attr(not(unix), allow(dead_code))]
+    CopiedToCache { from: PathBuf, to: PathBuf },
+    /// 清除了 macOS 的隔离属性
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    QuarantineCleared { path: PathBuf },
+}
+
+/// `prepare_executable` 的结果
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedExecutable {
+    pub name: String,
+    /// 实际应执行的路径，复制到缓存时与原路径不同
+    pub path: PathBuf,
+    pub actions: Vec<PrepareAction>,
+}
+
+/// 解析后的工具位置
+struct ResolvedTool {
+    path: PathBuf,
+    /// 命中的平台目录，例如 `windows-x64`
+    platform: String,
+    entry: Option<ToolEntry>,
+    /// 命中的是应用数据目录中安装的版本时，对应的安装记录
+    installed: Option<InstallReceipt>,
+    /// 校验未通过但按清单设置仍允许执行时的警告，由调用方决定如何提示
+    warning: Option<AppError>,
+}
+
+/// 工具的清单条目，以及按优先级排列的（平台目录，相对路径）候选
+type ToolCandidates = (Option<ToolEntry>, Vec<(String, PathBuf)>);
+
+/// 已计算过的文件哈希，文件大小或修改时间变化后失效
+struct CachedHash {
+    len: u64,
+    modified: SystemTime,
+    sha256: String,
+}
+
+/// 按路径缓存文件哈希，避免每次执行都重新读取整个文件
+static HASH_CACHE: Mutex<Option<HashMap<PathBuf, CachedHash>>> = Mutex::new(None);
+
+/// 计算文件的 SHA-256（小写十六进制），命中缓存时不重新读取文件
+fn file_sha256(path: &Path) -> std::io::Result<String> {
+    let meta = std::fs::metadata(path)?;
+    let (len, modified) = (meta.len(), meta.modified()?);
+
+    if let Ok(cache) = HASH_CACHE.lock() {
+        if let Some(cached) = cache.as_ref().and_then(|c| c.get(path)) {
+            if cached.len == len && cached.modified == modified {
+                return Ok(cached.sha256.clone());
+            }
+        }
+    }
+
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let hash: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+
+    if let Ok(mut cache) = HASH_CACHE.lock() {
+        cache
+            .get_or_insert_with(HashMap::new)
+            .insert(path.to_path_buf(), CachedHash { len, modified, sha256: hash.clone() });
+    }
+    Ok(hash)
+}
+
+/// 资源解析所依赖的目录
+///
+/// 运行时由 `AppHandle` 提供；测试中可以换成指向临时目录的实现，不需要启动 Tauri。
+pub trait PathProvider {
+    /// 安装包资源目录，即 Tauri 的 `resource_dir`
+    fn resource_dir(&self) -> Result<PathBuf, String>;
+
+    /// 应用数据目录，已安装的工具位于其下的 `tools/`
+    fn app_data_dir(&self) -> Result<PathBuf, String>;
+
+    /// 覆盖资源目录，默认读取环境变量 `MAKINGSTORE_RESOURCE_DIR`
+    fn override_dir(&self) -> Option<PathBuf> {
+        std::env::var_os(RESOURCE_DIR_ENV).map(PathBuf::from)
+    }
+
+    /// 源码资源目录，只在 debug 构建中由 build.rs 记录
+    fn dev_dir(&self) -> Option<PathBuf> {
+        option_env!("MAKINGSTORE_DEV_RESOURCE_DIR").map(PathBuf::from)
+    }
+}
+
+impl PathProvider for AppHandle {
+    fn resource_dir(&self) -> Result<PathBuf, String> {
+        self.path().resource_dir().map_err(|e| e.to_string())
+    }
+
+    fn app_data_dir(&self) -> Result<PathBuf, String> {
+        self.path().app_data_dir().map_err(|e| e.to_string())
+    }
+}
+
+/// 资源解析：在各资源根目录和已安装工具中查找文件并校验
+///
+/// 不依赖 Tauri 运行时，只通过 `PathProvider` 获取目录。
+pub struct ResourceLocator<P: PathProvider = AppHandle> {
+    paths: P,
+}
+
+impl<P: PathProvider> ResourceLocator<P> {
+    pub fn new(paths: P) -> Self {
+        Self { paths }
+    }
+
+    /// 获取当前平台的可执行文件路径
+    ///
+    /// `exe_name` 可以是清单中的逻辑名（如 `making-mcp`），也可以是文件名。
+    /// 依次在 `bin/<os>-<arch>/`、`bin/<os>/` 中查找，Windows 下自动补全 `.exe`，
+    /// 其他平台自动去掉 `.exe`。都找不到时返回首选位置的路径（可能不存在）。
+    pub fn get_executable_path(&self, exe_name: &str) -> AppResult<PathBuf> {
+        self.resolve_tool(&self.load_manifest()?, exe_name).map(|resolved| resolved.path)
+    }
+
+    /// 工具在各平台目录下可能的相对路径，按优先级排列
+    ///
+    /// 清单外的名称只能是单个文件名，不能包含路径分隔符、盘符或 `..`，
+    /// 保证解析结果始终位于 `bin/<平台>/` 之下。
+    fn tool_candidates(manifest: &ToolManifest, exe_name: &str) -> AppResult<ToolCandidates> {
+        let entry = manifest.tools.iter().find(|tool| tool.name == exe_name).cloned();
+        let file_name = entry.as_ref().map_or(exe_name, |tool| tool.executable.as_str());
+        if !is_plain_file_name(file_name) {
+            return Err(AppError::new(ErrorCode::InvalidArgument, tf("error.toolNameNotPlain", &[("name", &exe_name)]))
+                .with_program(exe_name));
+        }
+
+        let candidates = platform_dirs()
+            .into_iter()
+            .flat_map(|dir| {
+                file_name_candidates(file_name)
+                    .into_iter()
+                    .map(move |name| (dir.clone(), Path::new("bin").join(&dir).join(name)))
+            })
+            .collect();
+        Ok((entry, candidates))
+    }
+
+    fn resolve_tool(&self, manifest: &ToolManifest, exe_name: &str) -> AppResult<ResolvedTool> {
+        let (entry, candidates) = Self::tool_candidates(manifest, exe_name)?;
+
+        // 通过工具仓库安装的当前版本优先于随应用发布的版本
+        if let Some((path, receipt)) = ToolStore::for_app(&self.paths)
+            .ok()
+            .and_then(|store| store.active_executable(exe_name))
+        {
+            return Ok(ResolvedTool { path, platform: receipt.platform.clone(), entry, installed: Some(receipt), warning: None });
+        }
+
+        if let Some((platform, path)) = candidates
+            .iter()
+            .find_map(|(dir, rel)| self.locate(rel).map(|path| (dir.clone(), path)))
+        {
+            return Ok(ResolvedTool { path, platform, entry, installed: None, warning: None });
+        }
+
+        // 返回首选路径，即使不存在
+        let (platform, rel) = &candidates[0];
+        let path = self.preferred_path(rel)?;
+        Ok(ResolvedTool { path, platform: platform.clone(), entry, installed: None, warning: None })
+    }
+
+    /// 期望的校验和：已安装版本取安装记录，内置版本取清单中当前平台的值
+    fn expected_sha256(resolved: &ResolvedTool) -> Option<String> {
+        match &resolved.installed {
+            Some(receipt) => Some(receipt.executable_sha256.clone()),
+            None => resolved.entry.as_ref().and_then(|e| e.sha256.get(&resolved.platform)).cloned(),
+        }
+    }
+
+    /// 对比文件哈希与期望的校验和
+    fn checksum_status(resolved: &ResolvedTool) -> AppResult<ChecksumStatus> {
+        if !resolved.path.exists() {
+            return Ok(ChecksumStatus::Missing);
+        }
+        let Some(expected) = Self::expected_sha256(resolved) else {
+            return Ok(ChecksumStatus::Unverified);
+        };
+        let actual = file_sha256(&resolved.path)
+            .map_err(|e| AppError::io(tf("error.readFile", &[("error", &e)]), &resolved.path))?;
+        Ok(if actual.eq_ignore_ascii_case(expected.trim()) {
+            ChecksumStatus::Verified
+        } else {
+            ChecksumStatus::Mismatch
+        })
+    }
+
+    /// 解析可执行文件并校验
+    ///
+    /// 文件不存在时返回 `ExecutableNotFound`。清单为 `enforce` 时，校验和不一致或
+    /// 缺少校验和都拒绝执行；为 `warn` 时仍然执行，并在 `warning` 中给出原因。
+    fn verify_tool(&self, exe_name: &str) -> AppResult<ResolvedTool> {
+        let manifest = self.load_manifest()?;
+        let mut resolved = self.resolve_tool(&manifest, exe_name)?;
+        let enforce = manifest.verification == Verification::Enforce;
+
+        let reject = |code: ErrorCode, message: String| {
+            AppError::new(code, message).with_program(exe_name).with_path(&resolved.path)
+        };
+        let warning = match Self::checksum_status(&resolved)? {
+            ChecksumStatus::Missing => {
+                return Err(reject(
+                    ErrorCode::ExecutableNotFound,
+                    tf("error.executableNotFound", &[("program", &resolved.path.display())]),
+                ));
             }
-            Err(e) => Err(format!("Failed to resolve executable path: {}", e))
-        }
-    }
-
-    /// 尝试修正开发模式下的路径问题
-    fn try_dev_mode_path_correction(&self, original_path: &PathBuf) -> Option<PathBuf> {
-        let path_str = original_path.to_string_lossy();
-        
-        // 检查是否是开发模式路径问题（target/debug/bin 而不是 target/debug/resources/bin）
-        if path_str.contains("target") && path_str.contains("debug") && path_str.contains("bin") {
-            // 尝试将 target/debug/bin 替换为 target/debug/resources/bin
-            let corrected_str = path_str.replace("target\\debug\\bin", "target\\debug\\resources\\bin")
-                                       .replace("target/debug/bin", "target/debug/resources/bin");
-            
-            if corrected_str != path_str {
-                return Some(PathBuf::from(corrected_str));
+            // 已安装版本的校验和由安装时写入，不一致说明文件被改动，总是拒绝执行
+            ChecksumStatus::Mismatch if enforce || resolved.installed.is_some() => {
+                return Err(reject(ErrorCode::ChecksumMismatch, tf("error.checksumMismatch", &[("program", &exe_name)])));
             }
+            ChecksumStatus::Unverified if enforce => {
+                return Err(reject(
+                    ErrorCode::ChecksumMissing,
+                    tf("error.checksumMissing", &[("program", &exe_name), ("platform", &resolved.platform)]),
+                ));
+            }
+            ChecksumStatus::Mismatch => Some(reject(
+                ErrorCode::ChecksumMismatch,
+                tf("warning.checksumMismatch", &[("program", &exe_name)]),
+            )),
+            ChecksumStatus::Unverified if resolved.entry.is_some() => Some(reject(
+                ErrorCode::ChecksumMissing,
+                tf("warning.checksumMissing", &[("program", &exe_name), ("platform", &resolved.platform)]),
+            )),
+            ChecksumStatus::Verified | ChecksumStatus::Unverified => None,
+        };
+        resolved.warning = warning;
+        Ok(resolved)
+    }
+
+    /// 获取清单中某个工具的元数据及其在当前平台上的校验状态
+    pub fn tool_metadata(&self, manifest: &ToolManifest, name: &str) -> AppResult<ToolMetadata> {
+        let resolved = self.resolve_tool(manifest, name)?;
+        let checksum = Self::checksum_status(&resolved)?;
+        let expected_sha256 = Self::expected_sha256(&resolved);
+        let entry = resolved.entry.ok_or_else(|| {
+            AppError::new(ErrorCode::NotFound, tf("error.toolNotFound", &[("name", &name)]))
+        })?;
+        Ok(ToolMetadata {
+            expected_sha256,
+            installed_version: resolved.installed.map(|receipt| receipt.version),
+            exists: resolved.path.exists(),
+            path: resolved.path.to_string_lossy().to_string(),
+            platform: resolved.platform,
+            checksum,
+            entry,
+        })
+    }
+
+    /// 读取 `bin/manifest.json`
+    ///
+    /// 清单决定了校验策略，不存在或格式错误时返回错误，而不是当作空清单跳过校验。
+    pub fn load_manifest(&self) -> AppResult<ToolManifest> {
+        let path = self.locate(&relative_path(MANIFEST_PATH)).ok_or_else(|| {
+            AppError::new(ErrorCode::NotFound, tf("error.manifestMissing", &[("path", &MANIFEST_PATH)]))
+        })?;
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::io(tf("error.readFile", &[("error", &e)]), &path))?;
+        serde_json::from_str(&content)
+            .map_err(|e| AppError::new(ErrorCode::InvalidConfig, tf("error.invalidManifest", &[("error", &e)])).with_path(&path))
+    }
+
+    /// 按优先级排列的资源根目录
+    ///
+    /// 1. 环境变量 `MAKINGSTORE_RESOURCE_DIR` 指定的目录
+    /// 2. 安装包中的资源目录：tauri.conf.json 以 `resources/**/*` 打包，
+    ///    文件位于 `<resource_dir>/resources/` 下
+    /// 3. debug 构建时由 build.rs 记录的源码目录 `src-tauri/resources`
+    fn resource_roots(&self) -> Vec<ResourceRoot> {
+        let override_dir = self
+            .paths
+            .override_dir()
+            .ok_or_else(|| tf("resource.envNotSet", &[("name", &RESOURCE_DIR_ENV)]));
+        let bundle_dir = self
+            .paths
+            .resource_dir()
+            .map(|dir| dir.join("resources"))
+            .map_err(|e| tf("error.resolveDir", &[("error", &e)]));
+        let dev_dir = self.paths.dev_dir().ok_or_else(|| t("resource.releaseBuild"));
+
+        vec![
+            ResourceRoot { source: RootSource::Override, dir: override_dir },
+            ResourceRoot { source: RootSource::Bundle, dir: bundle_dir },
+            ResourceRoot { source: RootSource::Dev, dir: dev_dir },
+        ]
+    }
+
+    /// 在每个资源根目录下检查相对路径，记录命中或被跳过的原因
+    fn check_candidates(&self, rel: &Path) -> Vec<CandidateCheck> {
+        self.resource_roots()
+            .into_iter()
+            .map(|root| {
+                let (path, rejected) = match root.dir {
+                    Err(reason) => (None, Some(reason)),
+                    Ok(dir) if !dir.is_dir() => {
+                        (Some(dir.join(rel)), Some(tf("resource.dirMissing", &[("dir", &dir.display())])))
+                    }
+                    Ok(dir) => {
+                        let path = dir.join(rel);
+                        let rejected = if path.is_file() {
+                            None
+                        } else if path.exists() {
+                            Some(t("resource.notAFile"))
+                        } else {
+                            Some(t("resource.fileMissing"))
+                        };
+                        (Some(path), rejected)
+                    }
+                };
+                CandidateCheck {
+                    source: root.source,
+                    relative: rel.to_path_buf(),
+                    found: rejected.is_none(),
+                    path,
+                    rejected,
+                }
+            })
+            .collect()
+    }
+
+    /// 返回相对路径在第一个命中的资源根目录下的位置
+    fn locate(&self, rel: &Path) -> Option<PathBuf> {
+        self.check_candidates(rel)
+            .into_iter()
+            .find(|check| check.found)
+            .and_then(|check| check.path)
+    }
+
+    /// 相对路径在首个可用资源根目录下的位置（不检查是否存在）
+    fn preferred_path(&self, rel: &Path) -> AppResult<PathBuf> {
+        let roots = self.resource_roots();
+        roots
+            .iter()
+            .find_map(|root| root.dir.as_ref().ok().filter(|dir| dir.is_dir()))
+            .or_else(|| roots.iter().find_map(|root| root.dir.as_ref().ok()))
+            .map(|dir| dir.join(rel))
+            .ok_or_else(|| AppError::internal(tf("error.resolveDir", &[("error", &rel.display())])))
+    }
+
+    /// 获取资源文件路径，`resource_path` 中的 `/` 和 `\\` 都视为分隔符
+    pub fn get_resource_path(&self, resource_path: &str) -> AppResult<PathBuf> {
+        let rel = relative_path(resource_path);
+        match self.locate(&rel) {
+            Some(path) => Ok(path),
+            None => self.preferred_path(&rel),
         }
-        
-        None
+    }
+
+    /// 列出解析资源时尝试过的所有位置
+    ///
+    /// 给出 `exe_name` 时检查该工具的所有候选文件，否则检查清单文件本身。
+    /// 清单不可用时按文件名检查，诊断本身不执行任何文件。
+    pub fn diagnose(&self, exe_name: Option<&str>) -> AppResult<ResourceDiagnostics> {
+        let rels: Vec<PathBuf> = match exe_name {
+            Some(name) => Self::tool_candidates(&self.load_manifest().unwrap_or_default(), name)?
+                .1
+                .into_iter()
+                .map(|(_, rel)| rel)
+                .collect(),
+            None => vec![PathBuf::from(MANIFEST_PATH)],
+        };
+        Ok(ResourceDiagnostics {
+            roots: self
+                .resource_roots()
+                .into_iter()
+                .map(|root| RootInfo {
+                    source: root.source,
+                    dir: root.dir.as_ref().ok().cloned(),
+                    unavailable: root.dir.err(),
+                })
+                .collect(),
+            candidates: rels.iter().flat_map(|rel| self.check_candidates(rel)).collect(),
+        })
     }
 
     /// 检查可执行文件是否存在
@@ -65,87 +558,710 @@ impl ResourceManager {
             Err(_) => false,
         }
     }
+}
+
+/// 获取当前平台的目录名
+fn get_platform_dir() -> &'static str {
+    #[cfg(target_os = "windows")]
+    return "windows";
+    
+    #[cfg(target_os = "macos")]
+    return "macos";
+    
+    #[cfg(target_os = "linux")]
+    return "linux";
+    
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    return "unknown";
+}
+
+/// 获取当前 CPU 架构的目录后缀
+fn get_arch_suffix() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        other => other,
+    }
+}
+
+/// 按优先级排列的平台目录：`<os>-<arch>`，然后是 `<os>`
+pub(crate) fn platform_dirs() -> [String; 2] {
+    let os = get_platform_dir();
+    [format!("{}-{}", os, get_arch_suffix()), os.to_string()]
+}
+
+/// 同一工具在当前平台上可能的文件名
+pub(crate) fn file_name_candidates(name: &str) -> Vec<String> {
+    file_name_candidates_for(name, cfg!(target_os = "windows"))
+}
+
+/// Windows 下优先带 `.exe` 的文件名，其他平台优先去掉 `.exe`
+fn file_name_candidates_for(name: &str, windows: bool) -> Vec<String> {
+    let stem = name.strip_suffix(".exe").unwrap_or(name);
+    let mut names = if windows {
+        vec![format!("{}.exe", stem), name.to_string()]
+    } else {
+        vec![stem.to_string(), name.to_string()]
+    };
+    names.dedup();
+    names
+}
+
+/// 是否为单个普通文件名：非空，不是 `.` 或 `..`，不含路径分隔符和盘符
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', ':'])
+}
+
+/// 将使用 `/` 或 `\\` 分隔的相对路径转换为当前平台的路径，忽略空段和 `.`
+fn relative_path(path: &str) -> PathBuf {
+    path.split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect()
+}
+
+/// 资源管理器，用于处理外部可执行文件和资源
+pub struct ResourceManager {
+    app_handle: AppHandle,
+    locator: ResourceLocator,
+}
+
+impl ResourceManager {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { locator: ResourceLocator::new(app_handle.clone()), app_handle }
+    }
+
+    /// 解析并校验可执行文件；按清单设置放行的校验问题通过 `app-error` 事件提示
+    fn verify_tool(&self, exe_name: &str) -> AppResult<ResolvedTool> {
+        let resolved = self.locator.verify_tool(exe_name)?;
+        if let Some(warning) = &resolved.warning {
+            warning.report(&self.app_handle);
+        }
+        Ok(resolved)
+    }
+
+    /// 解析可执行文件并在执行前校验、修复权限，返回实际应执行的路径
+    pub fn verified_executable_path(&self, exe_name: &str) -> AppResult<PathBuf> {
+        let resolved = self.verify_tool(exe_name)?;
+        self.prepare_executable(exe_name, &resolved.path).map(|prepared| prepared.path)
+    }
+
+    /// 确保文件可以被执行，并记录做过的处理
+    ///
+    /// Unix 下缺少可执行位时先尝试原地 `chmod +x`，原位置只读（例如 AppImage、
+    /// 系统目录）时复制到 `<app_data>/bin-cache/` 后再授权；macOS 下还会清除
+    /// `com.apple.quarantine` 属性。有任何处理时发送 `tool-prepared` 事件。
+    pub fn prepare_executable(&self, name: &str, path: &Path) -> AppResult<PreparedExecutable> {
+        let mut actions = Vec::new();
+
+        #[cfg(unix)]
+        let path = self.ensure_executable_bit(path, &mut actions)?;
+        #[cfg(not(unix))]
+        let path = path.to_path_buf();
 
-    /// 获取当前平台的目录名
-    fn get_platform_dir(&self) -> &'static str {
-        #[cfg(target_os = "windows")]
-        return "windows";
-        
         #[cfg(target_os = "macos")]
-        return "macos";
-        
-        #[cfg(target_os = "linux")]
-        return "linux";
-        
-        #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-        return "unknown";
+        self.clear_quarantine(&path, &mut actions);
+
+        let prepared = PreparedExecutable { name: name.to_string(), path, actions };
+        if !prepared.actions.is_empty() {
+            let _ = self.app_handle.emit("tool-prepared", &prepared);
+        }
+        Ok(prepared)
+    }
+
+    #[cfg(unix)]
+    fn ensure_executable_bit(&self, path: &Path, actions: &mut Vec<PrepareAction>) -> AppResult<PathBuf> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let not_executable = |e: &dyn std::fmt::Display| {
+            AppError::new(
+                ErrorCode::PermissionDenied,
+                tf("error.notExecutable", &[("path", &path.display()), ("error", e)]),
+            )
+            .with_path(path)
+        };
+
+        let mode = std::fs::metadata(path).map_err(|e| not_executable(&e))?.permissions().mode();
+        if mode & 0o111 != 0 {
+            return Ok(path.to_path_buf());
+        }
+
+        // 与 chmod +x 一致：对有读权限的用户类别补上执行权限
+        let fixed_mode = mode | ((mode & 0o444) >> 2);
+        if std::fs::set_permissions(path, std::fs::Permissions::from_mode(fixed_mode)).is_ok() {
+            actions.push(PrepareAction::PermissionFixed { path: path.to_path_buf() });
+            return Ok(path.to_path_buf());
+        }
+
+        // 原位置不可写：复制到应用数据目录下的缓存中
+        let platform_dir = path
+            .parent()
+            .and_then(Path::file_name)
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let cache_dir = self
+            .app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| not_executable(&e))?
+            .join("bin-cache")
+            .join(platform_dir);
+        let target = cache_dir.join(path.file_name().unwrap_or_default());
+
+        let up_to_date = target.exists()
+            && matches!((file_sha256(&target), file_sha256(path)), (Ok(a), Ok(b)) if a == b);
+        if !up_to_date {
+            std::fs::create_dir_all(&cache_dir).map_err(|e| not_executable(&e))?;
+            std::fs::copy(path, &target).map_err(|e| not_executable(&e))?;
+        }
+        std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| not_executable(&e))?;
+        actions.push(PrepareAction::CopiedToCache { from: path.to_path_buf(), to: target.clone() });
+        Ok(target)
+    }
+
+    /// 清除 Gatekeeper 的隔离属性；属性不存在时 xattr 返回非零，不视为错误
+    #[cfg(target_os = "macos")]
+    fn clear_quarantine(&self, path: &Path, actions: &mut Vec<PrepareAction>) {
+        let builder = ProcessBuilder::new("xattr")
+            .args(["-d", "com.apple.quarantine"])
+            .arg(path);
+        let ctx = SpawnContext::new("prepare_executable");
+        if let Ok(output) = crate::process::output_audited(&self.app_handle, &ctx, &builder) {
+            if output.status.success() {
+                actions.push(PrepareAction::QuarantineCleared { path: path.to_path_buf() });
+            }
+        }
     }
 
-    /// 执行外部可执行文件
-    pub async fn execute_external_tool(
+    /// 为内置工具构建进程，应用工作目录和环境变量
+    fn tool_builder(&self, exe_name: &str, args: &[String], options: &ToolOptions) -> AppResult<ProcessBuilder> {
+        let exe_path = self.verified_executable_path(exe_name)?;
+        let mut builder = ProcessBuilder::new(&exe_path).args(args);
+        if let Some(cwd) = &options.cwd {
+            builder = builder.cwd(cwd);
+        }
+        if let Some(env_map) = &options.env {
+            builder = builder.envs(env_map);
+        }
+        Ok(builder)
+    }
+
+    /// 执行外部工具并等待结束，返回完整输出
+    ///
+    /// 设置了超时时，超时后结束进程并返回 `TimedOut` 错误。`decision` 为 `command_policy::authorize` 的判定结果。
+    pub fn execute_external_tool(
         &self,
         exe_name: &str,
-        args: Vec<&str>,
-    ) -> Result<std::process::Output, String> {
-        let exe_path = self.get_executable_path(exe_name)?;
-        
-        if !exe_path.exists() {
-            return Err(format!("Executable not found: {}", exe_path.display()));
-        }
-
-        let mut cmd = std::process::Command::new(&exe_path);
-        cmd.args(args);
-        
-        // Windows 下隐藏控制台窗口
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            // CREATE_NO_WINDOW = 0x08000000
-            cmd.creation_flags(0x08000000);
+        args: &[String],
+        options: &ToolOptions,
+        decision: &str,
+    ) -> AppResult<std::process::Output> {
+        let builder = self.tool_builder(exe_name, args, options)?;
+        let stdin = options.stdin.clone().map(StdinInput::into_bytes);
+        let has_stdin = stdin.is_some();
+
+        let ctx = SpawnContext::new("execute_external_tool").decision(decision);
+        let (mut child, audit) = spawn_audited(&self.app_handle, &ctx, &builder, |cmd| {
+            cmd.stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .stdin(if has_stdin { Stdio::piped() } else { Stdio::null() });
+        })
+        .map_err(|e| AppError::from(e).context(tf("error.executeTool", &[("program", &exe_name)])))?;
+
+        if let (Some(mut pipe), Some(input)) = (child.stdin.take(), stdin) {
+            std::thread::spawn(move || {
+                let _ = pipe.write_all(&input);
+            });
+        }
+
+        // 超时线程：在进程结束前收到通知则退出，否则结束进程
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        let timer = options.timeout_ms.map(|timeout_ms| {
+            let pid = child.id();
+            std::thread::spawn(move || {
+                let timed_out = matches!(
+                    done_rx.recv_timeout(Duration::from_millis(timeout_ms)),
+                    Err(mpsc::RecvTimeoutError::Timeout)
+                );
+                if timed_out {
+                    let _ = kill_process(pid);
+                }
+                timed_out
+            })
+        });
+
+        let result = child.wait_with_output();
+        let _ = done_tx.send(());
+        let timed_out = timer.and_then(|t| t.join().ok()).unwrap_or(false);
+
+        match result {
+            Ok(output) => {
+                audit.finish(output.status.code(), None);
+                if timed_out {
+                    return Err(AppError::new(
+                        ErrorCode::TimedOut,
+                        tf("error.timedOut", &[("program", &exe_name)]),
+                    )
+                    .with_program(exe_name)
+                    .with_stderr(String::from_utf8_lossy(&output.stderr)));
+                }
+                Ok(output)
+            }
+            Err(e) => {
+                audit.finish(None, Some(e.to_string()));
+                Err(AppError::new(
+                    ErrorCode::ProcessFailed,
+                    tf("error.processFailed", &[("program", &exe_name), ("error", &e)]),
+                )
+                .with_program(exe_name))
+            }
         }
-        
-        cmd.output()
-            .map_err(|e| format!("Failed to execute {}: {}", exe_name, e))
+    }
 
+    /// 以流式方式执行外部工具，按行推送 `tool-output` 事件，立即返回任务 ID
+    pub fn execute_external_tool_stream(
+        &self,
+        exe_name: &str,
+        args: &[String],
+        options: ToolOptions,
+        decision: &str,
+    ) -> AppResult<String> {
+        let builder = self.tool_builder(exe_name, args, &options)?;
+        let ctx = SpawnContext::new("execute_external_tool").decision(decision);
+        spawn_job(
+            &self.app_handle,
+            &ctx,
+            &builder,
+            options.stdin.map(StdinInput::into_bytes),
+            options.timeout_ms,
+            OutputMode::Lines,
+        )
     }
 }
 
-/// Tauri 命令：获取可执行文件路径
+// Tauri 命令函数
 #[tauri::command]
 pub async fn get_executable_path(
     app_handle: AppHandle,
     exe_name: String,
-) -> Result<String, String> {
-    let resource_manager = ResourceManager::new(app_handle);
-    let path = resource_manager.get_executable_path(&exe_name)?;
-    Ok(path.to_string_lossy().to_string())
+) -> AppResult<String> {
+    ResourceLocator::new(app_handle).get_executable_path(&exe_name).map(|p| p.to_string_lossy().to_string())
 }
 
-/// Tauri 命令：检查可执行文件是否存在
 #[tauri::command]
 pub async fn check_executable_exists(
     app_handle: AppHandle,
     exe_name: String,
-) -> Result<bool, String> {
-    let resource_manager = ResourceManager::new(app_handle);
-    Ok(resource_manager.executable_exists(&exe_name))
+) -> AppResult<bool> {
+    Ok(ResourceLocator::new(app_handle).executable_exists(&exe_name))
 }
 
-/// Tauri 命令：执行外部工具
+/// 执行内置工具
+///
+/// 默认等待进程结束并返回 stdout；`options.stream` 为 true 时立即返回任务 ID，
+/// 输出通过 `tool-output` 事件逐行推送，结束时推送 `command-exit`，可用 `cancel_command` 取消。
+///
+/// 与 `execute_command` 一样经过命令策略授权：按工具名匹配允许列表，带工作目录或环境变量时总是需要确认。
 #[tauri::command]
 pub async fn execute_external_tool(
     app_handle: AppHandle,
     exe_name: String,
     args: Vec<String>,
-) -> Result<String, String> {
-    let resource_manager = ResourceManager::new(app_handle);
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    
-    let output = resource_manager.execute_external_tool(&exe_name, args_refs).await?;
-    
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    options: Option<ToolOptions>,
+) -> AppResult<String> {
+    let options = options.unwrap_or_default();
+    let exec = ExecContext { cwd: options.cwd.as_deref(), env: options.env.as_ref() };
+    let decision = command_policy::authorize(&app_handle, "execute_external_tool", &exe_name, &args, &exec)?;
+    let manager = ResourceManager::new(app_handle);
+
+    if options.stream {
+        return manager.execute_external_tool_stream(&exe_name, &args, options, decision);
     }
-}
\ No newline at end of file
+    let output = manager.execute_external_tool(&exe_name, &args, &options, decision)?;
+    Ok(check_output(&exe_name, output)?)
+}
+
+/// 内置工具的元数据及其在当前平台上的解析结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolMetadata {
+    #[serde(flatten)]
+    pub entry: ToolEntry,
+    pub platform: String,
+    pub path: String,
+    pub exists: bool,
+    pub expected_sha256: Option<String>,
+    /// 使用的是应用数据目录中安装的版本时为其版本号
+    pub installed_version: Option<String>,
+    pub checksum: ChecksumStatus,
+}
+
+/// 列出清单中的内置工具、版本及校验状态
+#[tauri::command]
+pub async fn list_bundled_tools(app_handle: AppHandle) -> AppResult<Vec<ToolMetadata>> {
+    let locator = ResourceLocator::new(app_handle);
+    let manifest = locator.load_manifest()?;
+    manifest
+        .tools
+        .iter()
+        .map(|entry| locator.tool_metadata(&manifest, &entry.name))
+        .collect()
+}
+
+/// 获取单个内置工具的元数据
+#[tauri::command]
+pub async fn get_tool_metadata(app_handle: AppHandle, name: String) -> AppResult<ToolMetadata> {
+    let locator = ResourceLocator::new(app_handle);
+    locator.tool_metadata(&locator.load_manifest()?, &name)
+}
+
+/// 校验并修复内置工具的执行权限，返回做过的处理
+#[tauri::command]
+pub async fn prepare_bundled_tool(app_handle: AppHandle, name: String) -> AppResult<PreparedExecutable> {
+    let manager = ResourceManager::new(app_handle);
+    let resolved = manager.verify_tool(&name)?;
+    manager.prepare_executable(&name, &resolved.path)
+}
+
+/// 列出解析资源时尝试过的所有路径及未命中的原因，用于排查找不到内置工具的问题
+#[tauri::command]
+pub async fn diagnose_resource_paths(app_handle: AppHandle, name: Option<String>) -> AppResult<ResourceDiagnostics> {
+    ResourceLocator::new(app_handle).diagnose(name.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tool_registry::ToolStore;
+    use tempfile::TempDir;
+
+    /// 指向临时目录的路径提供者；不读取环境变量，避免测试之间互相影响
+    struct FixedPaths {
+        resource: PathBuf,
+        data: PathBuf,
+        override_dir: Option<PathBuf>,
+        dev: Option<PathBuf>,
+    }
+
+    impl PathProvider for FixedPaths {
+        fn resource_dir(&self) -> Result<PathBuf, String> {
+            Ok(self.resource.clone())
+        }
+
+        fn app_data_dir(&self) -> Result<PathBuf, String> {
+            Ok(self.data.clone())
+        }
+
+        fn override_dir(&self) -> Option<PathBuf> {
+            self.override_dir.clone()
+        }
+
+        fn dev_dir(&self) -> Option<PathBuf> {
+            self.dev.clone()
+        }
+    }
+
+    struct Fixture {
+        tmp: TempDir,
+    }
+
+    impl Fixture {
+        /// 带一份空的 `warn` 清单的安装包布局
+        fn new() -> Self {
+            let fx = Self { tmp: TempDir::new().unwrap() };
+            fx.write("app/resources/bin/manifest.json", EMPTY_MANIFEST);
+            fx
+        }
+
+        fn path(&self, rel: &str) -> PathBuf {
+            self.tmp.path().join(relative_path(rel))
+        }
+
+        fn write(&self, rel: &str, content: &[u8]) -> PathBuf {
+            let path = self.path(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+            path
+        }
+
+        /// 安装包布局：`<resource_dir>/resources/`
+        fn bundle_locator(&self) -> ResourceLocator<FixedPaths> {
+            ResourceLocator::new(FixedPaths {
+                resource: self.path("app"),
+                data: self.path("data"),
+                override_dir: None,
+                dev: None,
+            })
+        }
+    }
+
+    const EMPTY_MANIFEST: &[u8] = br#"{"verification":"warn","tools":[]}"#;
+
+    fn sha256_of(content: &[u8]) -> String {
+        Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn exe(name: &str) -> String {
+        file_name_candidates(name).remove(0)
+    }
+
+    fn arch_dir() -> String {
+        platform_dirs()[0].clone()
+    }
+
+    fn os_dir() -> String {
+        platform_dirs()[1].clone()
+    }
+
+    #[test]
+    fn file_names_follow_platform_conventions() {
+        assert_eq!(file_name_candidates_for("tool", true), ["tool.exe", "tool"]);
+        assert_eq!(file_name_candidates_for("tool.exe", true), ["tool.exe"]);
+        assert_eq!(file_name_candidates_for("tool", false), ["tool"]);
+        assert_eq!(file_name_candidates_for("tool.exe", false), ["tool", "tool.exe"]);
+    }
+
+    #[test]
+    fn relative_paths_accept_both_separators() {
+        let expected: PathBuf = ["bin", "windows-x64", "tool.exe"].iter().collect();
+        assert_eq!(relative_path("bin\\windows-x64\\tool.exe"), expected);
+        assert_eq!(relative_path("bin/windows-x64/tool.exe"), expected);
+        assert_eq!(relative_path("./bin//windows-x64\\tool.exe"), expected);
+    }
+
+    #[test]
+    fn platform_dirs_prefer_arch_specific_directory() {
+        let [arch, os] = platform_dirs();
+        assert!(arch.starts_with(&format!("{}-", os)));
+        assert_eq!(os, get_platform_dir());
+    }
+
+    #[test]
+    fn tool_names_must_stay_inside_the_bin_directory() {
+        for name in ["tool", "MakingMcp.Web", "tool.exe", "..tool"] {
+            assert!(is_plain_file_name(name), "{}", name);
+        }
+        for name in ["", ".", "..", "../tool", "..\\tool", "/bin/sh", "C:\\Windows\\cmd.exe", "C:cmd.exe", "sub/tool"] {
+            assert!(!is_plain_file_name(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn path_like_tool_names_are_rejected() {
+        let fx = Fixture::new();
+        fx.write(
+            "app/resources/bin/manifest.json",
+            br#"{"tools":[{"name":"making-mcp","executable":"MakingMcp.Web"},{"name":"escape","executable":"../../evil"}]}"#,
+        );
+        fx.write("app/resources/evil", b"evil");
+        let locator = fx.bundle_locator();
+
+        assert!(locator.get_executable_path("making-mcp").is_ok());
+        for name in ["../../evil", "/bin/sh", "escape"] {
+            assert_eq!(locator.get_executable_path(name).err().unwrap().code, ErrorCode::InvalidArgument);
+            assert!(locator.diagnose(Some(name)).is_err());
+        }
+    }
+
+    #[test]
+    fn resolves_arch_directory_before_os_directory() {
+        let fx = Fixture::new();
+        fx.write(&format!("app/resources/bin/{}/{}", os_dir(), exe("tool")), b"os");
+        let arch = fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("tool")), b"arch");
+
+        assert_eq!(fx.bundle_locator().get_executable_path("tool").unwrap(), arch);
+    }
+
+    #[test]
+    fn falls_back_to_os_directory() {
+        let fx = Fixture::new();
+        let path = fx.write(&format!("app/resources/bin/{}/{}", os_dir(), exe("tool")), b"os");
+
+        assert_eq!(fx.bundle_locator().get_executable_path("tool").unwrap(), path);
+        assert!(fx.bundle_locator().executable_exists("tool"));
+    }
+
+    #[test]
+    fn resource_paths_with_windows_separators_resolve() {
+        let fx = Fixture::new();
+        let path = fx.write("app/resources/config/settings.json", b"{}");
+
+        let locator = fx.bundle_locator();
+        assert_eq!(locator.get_resource_path("config\\settings.json").unwrap(), path);
+        assert_eq!(locator.get_resource_path("config/settings.json").unwrap(), path);
+    }
+
+    #[test]
+    fn manifest_maps_logical_name_to_executable() {
+        let fx = Fixture::new();
+        fx.write(
+            "app/resources/bin/manifest.json",
+            br#"{"tools":[{"name":"making-mcp","executable":"MakingMcp.Web"}]}"#,
+        );
+        let path = fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("MakingMcp.Web")), b"mcp");
+
+        let locator = fx.bundle_locator();
+        assert_eq!(locator.get_executable_path("making-mcp").unwrap(), path);
+        let metadata = locator.tool_metadata(&locator.load_manifest().unwrap(), "making-mcp").unwrap();
+        assert_eq!(metadata.checksum, ChecksumStatus::Unverified);
+        assert!(metadata.exists);
+    }
+
+    #[test]
+    fn override_root_takes_precedence_over_bundle() {
+        let fx = Fixture::new();
+        fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("tool")), b"bundle");
+        let overridden = fx.write(&format!("override/bin/{}/{}", arch_dir(), exe("tool")), b"override");
+
+        let locator = ResourceLocator::new(FixedPaths {
+            resource: fx.path("app"),
+            data: fx.path("data"),
+            override_dir: Some(fx.path("override")),
+            dev: None,
+        });
+        assert_eq!(locator.get_executable_path("tool").unwrap(), overridden);
+    }
+
+    #[test]
+    fn dev_layout_is_used_when_bundle_is_missing() {
+        let fx = Fixture::new();
+        let dev = fx.write(&format!("src-tauri/resources/bin/{}/{}", os_dir(), exe("tool")), b"dev");
+        fx.write("src-tauri/resources/bin/manifest.json", EMPTY_MANIFEST);
+
+        let locator = ResourceLocator::new(FixedPaths {
+            resource: fx.path("target/debug"),
+            data: fx.path("data"),
+            override_dir: None,
+            dev: Some(fx.path("src-tauri/resources")),
+        });
+        assert_eq!(locator.get_executable_path("tool").unwrap(), dev);
+
+        let diagnostics = locator.diagnose(Some("tool")).unwrap();
+        let bundle = diagnostics
+            .candidates
+            .iter()
+            .find(|check| matches!(check.source, RootSource::Bundle))
+            .unwrap();
+        assert!(!bundle.found);
+        assert!(bundle.rejected.is_some());
+    }
+
+    #[test]
+    fn missing_resource_reports_preferred_path_and_reasons() {
+        let fx = Fixture::new();
+
+        let locator = fx.bundle_locator();
+        let path = locator.get_executable_path("tool").unwrap();
+        assert_eq!(path, fx.path(&format!("app/resources/bin/{}/{}", arch_dir(), exe("tool"))));
+        assert!(!locator.executable_exists("tool"));
+
+        let err = locator.verify_tool("tool").err().unwrap();
+        assert_eq!(err.code, ErrorCode::ExecutableNotFound);
+
+        let diagnostics = locator.diagnose(Some("tool")).unwrap();
+        assert!(diagnostics.candidates.iter().all(|check| !check.found && check.rejected.is_some()));
+        assert_eq!(diagnostics.roots.len(), 3);
+    }
+
+    #[test]
+    fn checksum_mismatch_is_enforced_unless_manifest_warns() {
+        let fx = Fixture::new();
+        let manifest = |verification: &str, sha: &str| {
+            format!(
+                r#"{{"verification":"{}","tools":[{{"name":"tool","executable":"tool","sha256":{{"{}":"{}"}}}}]}}"#,
+                verification,
+                arch_dir(),
+                sha
+            )
+        };
+        fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("tool")), b"binary");
+        let locator = fx.bundle_locator();
+
+        fx.write("app/resources/bin/manifest.json", manifest("enforce", &sha256_of(b"binary")).as_bytes());
+        assert!(locator.verify_tool("tool").is_ok());
+
+        fx.write("app/resources/bin/manifest.json", manifest("enforce", &sha256_of(b"other")).as_bytes());
+        assert_eq!(locator.verify_tool("tool").err().unwrap().code, ErrorCode::ChecksumMismatch);
+
+        fx.write("app/resources/bin/manifest.json", manifest("warn", &sha256_of(b"other")).as_bytes());
+        let resolved = locator.verify_tool("tool").unwrap();
+        assert_eq!(resolved.warning.map(|w| w.code), Some(ErrorCode::ChecksumMismatch));
+    }
+
+    #[test]
+    fn missing_checksums_are_rejected_under_enforce() {
+        let fx = Fixture::new();
+        fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("tool")), b"binary");
+        let locator = fx.bundle_locator();
+
+        fx.write(
+            "app/resources/bin/manifest.json",
+            br#"{"verification":"enforce","tools":[{"name":"tool","executable":"tool","sha256":{}}]}"#,
+        );
+        assert_eq!(locator.verify_tool("tool").err().unwrap().code, ErrorCode::ChecksumMissing);
+
+        // 清单外的文件同样没有校验和
+        fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("other")), b"other");
+        assert_eq!(locator.verify_tool("other").err().unwrap().code, ErrorCode::ChecksumMissing);
+
+        fx.write(
+            "app/resources/bin/manifest.json",
+            br#"{"verification":"warn","tools":[{"name":"tool","executable":"tool","sha256":{}}]}"#,
+        );
+        let resolved = locator.verify_tool("tool").unwrap();
+        assert_eq!(resolved.warning.map(|w| w.code), Some(ErrorCode::ChecksumMissing));
+        assert!(locator.verify_tool("other").unwrap().warning.is_none());
+    }
+
+    #[test]
+    fn missing_or_broken_manifest_is_an_error() {
+        let fx = Fixture::new();
+        fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("tool")), b"binary");
+        let locator = fx.bundle_locator();
+
+        fx.write("app/resources/bin/manifest.json", b"{ not json");
+        assert_eq!(locator.load_manifest().err().unwrap().code, ErrorCode::InvalidConfig);
+        assert_eq!(locator.verify_tool("tool").err().unwrap().code, ErrorCode::InvalidConfig);
+
+        std::fs::remove_file(fx.path("app/resources/bin/manifest.json")).unwrap();
+        assert_eq!(locator.load_manifest().err().unwrap().code, ErrorCode::NotFound);
+        assert!(!locator.executable_exists("tool"));
+        // 诊断不依赖清单
+        assert!(locator.diagnose(Some("tool")).unwrap().candidates.iter().any(|check| check.found));
+    }
+
+    #[test]
+    fn installed_version_takes_precedence_and_is_verified() {
+        let fx = Fixture::new();
+        fx.write(&format!("app/resources/bin/{}/{}", arch_dir(), exe("tool")), b"bundled");
+
+        let artifact = b"installed binary";
+        fx.write("registry/tool-1.0.0", artifact);
+        let index = format!(
+            r#"{{"tools":[{{"name":"tool","executable":"tool","releases":[{{"version":"1.0.0","platforms":{{"{}":{{"url":"tool-1.0.0","sha256":"{}","format":"raw"}}}}}}]}}]}}"#,
+            arch_dir(),
+            sha256_of(artifact)
+        );
+        let index_path = fx.write("registry/index.json", index.as_bytes());
+        let publisher = ed25519_dalek::SigningKey::from_bytes(&[7; 32]);
+        let signature = ed25519_dalek::Signer::sign(&publisher, index.as_bytes()).to_bytes();
+        fx.write("registry/index.json.sig", signature.iter().map(|b| format!("{:02x}", b)).collect::<String>().as_bytes());
+        let index_url = format!("file://{}", index_path.to_string_lossy().replace('\\', "/"));
+
+        let store = ToolStore::new(fx.path("data/tools"));
+        let receipt = store
+            .install(&index_url, &publisher.verifying_key(), "tool", None, &platform_dirs(), file_name_candidates)
+            .unwrap();
+        // 安装后不会自动切换，仍使用随应用发布的版本
+        assert!(fx.bundle_locator().verify_tool("tool").unwrap().installed.is_none());
+        store.activate("tool", &receipt.version).unwrap();
+
+        let locator = fx.bundle_locator();
+        let resolved = locator.verify_tool("tool").unwrap();
+        assert_eq!(resolved.installed.as_ref().map(|r| r.version.as_str()), Some("1.0.0"));
+        assert_eq!(std::fs::read(&resolved.path).unwrap(), artifact);
+
+        std::fs::write(&resolved.path, b"tampered").unwrap();
+        assert_eq!(locator.verify_tool("tool").err().unwrap().code, ErrorCode::ChecksumMismatch);
+    }
+}
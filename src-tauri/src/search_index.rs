@@ -0,0 +1,654 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::claude_commands::split_tools;
+use crate::error::{AppError, AppResult};
+use crate::file_watcher::{ChangeKind, ChangeTarget, FileChange, AGENT_FILE_NAMES};
+use crate::prompt_store::{split_frontmatter, PromptScope};
+
+/// BM25 参数
+const K1: f32 = 1.2;
+const B: f32 = 0.75;
+
+/// 各字段的权重：名称和描述中的命中比正文更重要
+const NAME_WEIGHT: f32 = 3.0;
+const DESCRIPTION_WEIGHT: f32 = 2.0;
+const BODY_WEIGHT: f32 = 1.0;
+
+/// 摘要在命中位置前后保留的字符数
+const SNIPPET_RADIUS: usize = 40;
+
+/// 被索引文档的类型
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentKind {
+    /// `.codex/prompts/*.md`
+    Prompt,
+    /// `.claude/commands/**/*.md`
+    ClaudeCommand,
+    /// AGENTS.md、CLAUDE.md 等说明文件
+    AgentFile,
+}
+
+/// 一个被索引的文档
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDocument {
+    pub kind: DocumentKind,
+    pub scope: PromptScope,
+    pub name: String,
+    pub description: String,
+    pub category: Option<String>,
+    pub tools: Vec<String>,
+    #[serde(skip)]
+    pub body: String,
+    pub path: PathBuf,
+    pub project_path: Option<PathBuf>,
+}
+
+/// 搜索条件；除 `query` 外都是可选的过滤项
+#[derive(serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    pub query: String,
+    pub kind: Option<DocumentKind>,
+    pub scope: Option<PromptScope>,
+    pub category: Option<String>,
+    /// 只返回声明了该工具的提示词或命令
+    pub tool: Option<String>,
+    pub project_path: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// 一条搜索结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub document: SearchDocument,
+    pub score: f32,
+    /// 命中位置附近的正文
+    pub snippet: String,
+    /// 摘要中命中词的位置，按字符计的 `[start, end)`
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// 判断字符是否按 CJK 方式切分
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // 平假名、片假名
+        | 0x3400..=0x4DBF   // CJK 扩展 A
+        | 0x4E00..=0x9FFF   // CJK 统一汉字
+        | 0xAC00..=0xD7AF   // 韩文音节
+        | 0xF900..=0xFAFF   // CJK 兼容汉字
+        | 0x20000..=0x2A6DF // CJK 扩展 B
+    )
+}
+
+/// 分词：字母数字按单词切分并转为小写，CJK 连续文本切分为二元组
+///
+/// 只有一个字的 CJK 片段单独成词；查询中的单字由 `SearchIndex::expand_terms` 展开到包含它的二元组。
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+
+    fn flush_cjk(cjk: &mut Vec<char>, tokens: &mut Vec<String>) {
+        match cjk.len() {
+            0 => {}
+            1 => tokens.push(cjk[0].to_string()),
+            _ => tokens.extend(cjk.windows(2).map(|pair| pair.iter().collect())),
+        }
+        cjk.clear();
+    }
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            cjk.push(c);
+        } else if c.is_alphanumeric() || c == '_' {
+            flush_cjk(&mut cjk, &mut tokens);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_cjk(&mut cjk, &mut tokens);
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+        }
+    }
+    flush_cjk(&mut cjk, &mut tokens);
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+struct IndexedDocument {
+    document: SearchDocument,
+    /// 加权后的文档长度
+    length: f32,
+    terms: Vec<String>,
+}
+
+/// 倒排索引
+#[derive(Default)]
+pub struct SearchIndex {
+    documents: HashMap<u32, IndexedDocument>,
+    by_path: HashMap<PathBuf, u32>,
+    /// 词 -> 文档 -> 加权词频
+    postings: HashMap<String, HashMap<u32, f32>>,
+    total_length: f32,
+    next_id: u32,
+}
+
+impl SearchIndex {
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// 加入或替换文档；同一路径的旧文档会被移除
+    pub fn upsert(&mut self, document: SearchDocument) {
+        self.remove(&document.path);
+
+        let mut weights: HashMap<String, f32> = HashMap::new();
+        let mut length = 0.0;
+        for (text, weight) in [
+            (document.name.as_str(), NAME_WEIGHT),
+            (document.description.as_str(), DESCRIPTION_WEIGHT),
+            (document.body.as_str(), BODY_WEIGHT),
+        ] {
+            for token in tokenize(text) {
+                *weights.entry(token).or_default() += weight;
+                length += weight;
+            }
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        for (term, weight) in &weights {
+            self.postings.entry(term.clone()).or_default().insert(id, *weight);
+        }
+        self.total_length += length;
+        self.by_path.insert(document.path.clone(), id);
+        self.documents.insert(id, IndexedDocument { document, length, terms: weights.into_keys().collect() });
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        let Some(id) = self.by_path.remove(path) else {
+            return;
+        };
+        let Some(indexed) = self.documents.remove(&id) else {
+            return;
+        };
+        for term in &indexed.terms {
+            if let Some(posting) = self.postings.get_mut(term) {
+                posting.remove(&id);
+                if posting.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        self.total_length -= indexed.length;
+    }
+
+    /// 移除某个项目下的所有文档
+    pub fn remove_project(&mut self, project: &Path) {
+        let paths: Vec<PathBuf> = self
+            .documents
+            .values()
+            .filter(|d| d.document.project_path.as_deref() == Some(project))
+            .map(|d| d.document.path.clone())
+            .collect();
+        for path in paths {
+            self.remove(&path);
+        }
+    }
+
+    fn matches_filters(document: &SearchDocument, query: &SearchQuery) -> bool {
+        query.kind.is_none_or(|kind| document.kind == kind)
+            && query.scope.is_none_or(|scope| document.scope == scope)
+            && query.category.as_deref().is_none_or(|c| document.category.as_deref() == Some(c))
+            && query.tool.as_deref().is_none_or(|tool| document.tools.iter().any(|t| t.eq_ignore_ascii_case(tool)))
+            && query
+                .project_path
+                .as_deref()
+                .is_none_or(|p| document.project_path.as_deref() == Some(Path::new(p)))
+    }
+
+    /// 查询词对应的索引词；最后一个非 CJK 词按前缀匹配，便于边输入边搜索，
+    /// 单个 CJK 字匹配所有包含它的二元组，例如「审」命中「代码审查」中的「码审」和「审查」
+    fn expand_terms(&self, tokens: &[String]) -> Vec<String> {
+        let mut terms: Vec<String> = Vec::new();
+        for (i, token) in tokens.iter().enumerate() {
+            let is_last = i + 1 == tokens.len();
+            let single_cjk = token.chars().count() == 1 && token.chars().all(is_cjk);
+            if single_cjk {
+                terms.extend(
+                    self.postings.keys().filter(|t| t.chars().all(is_cjk) && t.contains(token.as_str())).cloned(),
+                );
+            } else if is_last && !token.chars().any(is_cjk) {
+                terms.extend(self.postings.keys().filter(|t| t.starts_with(token.as_str())).cloned());
+            } else if self.postings.contains_key(token) {
+                terms.push(token.clone());
+            }
+        }
+        terms.sort();
+        terms.dedup();
+        terms
+    }
+
+    /// 按 BM25 排序返回结果；查询为空时按名称列出所有满足过滤条件的文档
+    pub fn search(&self, query: &SearchQuery) -> Vec<SearchHit> {
+        let limit = query.limit.unwrap_or(50).clamp(1, 500);
+        let tokens = tokenize(&query.query);
+
+        if tokens.is_empty() {
+            let mut documents: Vec<&SearchDocument> = self
+                .documents
+                .values()
+                .map(|d| &d.document)
+                .filter(|d| Self::matches_filters(d, query))
+                .collect();
+            documents.sort_by(|a, b| a.name.cmp(&b.name));
+            return documents
+                .into_iter()
+                .take(limit)
+                .map(|d| SearchHit { document: d.clone(), score: 0.0, snippet: snippet(&d.body, &[]).0, highlights: Vec::new() })
+                .collect();
+        }
+
+        let count = self.documents.len() as f32;
+        let average_length = if count > 0.0 { self.total_length / count } else { 1.0 };
+        let mut scores: HashMap<u32, f32> = HashMap::new();
+        for term in self.expand_terms(&tokens) {
+            let Some(posting) = self.postings.get(&term) else {
+                continue;
+            };
+            let df = posting.len() as f32;
+            let idf = ((count - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for (id, tf) in posting {
+                let length = self.documents.get(id).map_or(average_length, |d| d.length);
+                let norm = tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length / average_length));
+                *scores.entry(*id).or_default() += idf * norm;
+            }
+        }
+
+        let mut ranked: Vec<(u32, f32)> = scores
+            .into_iter()
+            .filter(|(id, _)| self.documents.get(id).is_some_and(|d| Self::matches_filters(&d.document, query)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let needles: Vec<String> = query
+            .query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        ranked
+            .into_iter()
+            .take(limit)
+            .filter_map(|(id, score)| {
+                let document = &self.documents.get(&id)?.document;
+                let (snippet, highlights) = snippet(&document.body, &needles);
+                Some(SearchHit { document: document.clone(), score, snippet, highlights })
+            })
+            .collect()
+    }
+}
+
+/// 截取第一个命中位置附近的正文，并标出摘要中所有命中词的位置
+fn snippet(body: &str, needles: &[String]) -> (String, Vec<(usize, usize)>) {
+    let chars: Vec<char> = body.chars().collect();
+    let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase().next()).collect();
+    let needles: Vec<Vec<char>> = needles.iter().map(|n| n.chars().collect()).filter(|n: &Vec<char>| !n.is_empty()).collect();
+
+    let find_all = |range: std::ops::Range<usize>| {
+        let mut hits = Vec::new();
+        for needle in &needles {
+            let mut i = range.start;
+            while i + needle.len() <= range.end {
+                if lower[i..i + needle.len()] == needle[..] {
+                    hits.push((i, i + needle.len()));
+                    i += needle.len();
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        hits.sort();
+        hits
+    };
+
+    let first = find_all(0..lower.len()).first().map_or(0, |(start, _)| *start);
+    let start = first.saturating_sub(SNIPPET_RADIUS);
+    let end = (first + SNIPPET_RADIUS * 2).min(chars.len());
+
+    let mut text: String = chars[start..end].iter().collect();
+    let mut offset = 0;
+    if start > 0 {
+        text.insert(0, '…');
+        offset = 1;
+    }
+    if end < chars.len() {
+        text.push('…');
+    }
+    let text = text.replace(['\n', '\r'], " ");
+    let highlights = find_all(start..end)
+        .into_iter()
+        .map(|(s, e)| (s - start + offset, e - start + offset))
+        .collect();
+    (text, highlights)
+}
+
+/// frontmatter 中字符串或列表形式的工具声明
+fn yaml_list(value: Option<&serde_yaml::Value>) -> Vec<String> {
+    match value {
+        Some(serde_yaml::Value::String(text)) => split_tools(text),
+        Some(serde_yaml::Value::Sequence(items)) => items.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 读取一个 Markdown 文件并转换为索引文档；读取失败时返回 `None`
+pub fn load_document(
+    path: &Path,
+    kind: DocumentKind,
+    scope: PromptScope,
+    name: String,
+    project_path: Option<&Path>,
+) -> Option<SearchDocument> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let (yaml, body) = split_frontmatter(&text);
+    let meta: serde_yaml::Mapping = yaml.and_then(|y| serde_yaml::from_str(y).ok()).unwrap_or_default();
+    let field = |key: &str| meta.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+    let description = field("description").unwrap_or_else(|| {
+        // 说明文件没有 frontmatter，用第一个标题作为描述
+        body.lines()
+            .map(str::trim)
+            .find(|line| line.starts_with('#'))
+            .map(|line| line.trim_start_matches('#').trim().to_string())
+            .unwrap_or_default()
+    });
+    let tools = match meta.get("allowed-tools") {
+        Some(value) => yaml_list(Some(value)),
+        None => yaml_list(meta.get("tools")),
+    };
+    Some(SearchDocument {
+        kind,
+        scope,
+        category: field("category").or_else(|| name.rsplit_once(':').map(|(ns, _)| ns.to_string())),
+        tools,
+        body: body.trim().to_string(),
+        path: path.to_path_buf(),
+        project_path: project_path.map(Path::to_path_buf),
+        name,
+        description,
+    })
+}
+
+/// 目录下的 Markdown 文件；`recursive` 时名称中的子目录用 `:` 连接
+/// 文档相对 `dir` 的名称：去掉 `.md`，子目录用 `:` 连接，与 Claude 命令的调用名称一致
+pub fn document_name(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir)
+        .unwrap_or(path)
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+pub fn markdown_files(dir: &Path, recursive: bool) -> Vec<(PathBuf, String)> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "md") {
+                let name = document_name(dir, &path);
+                files.push((path, name));
+            }
+        }
+    }
+    files
+}
+
+/// 收集用户目录或项目目录下所有可索引的文档
+///
+/// `base` 为家目录（用户级）或项目根目录；用户级的说明文件位于 `~/.codex` 和 `~/.claude` 中，
+/// 项目级的位于项目根目录。
+pub fn collect_documents(base: &Path, scope: PromptScope) -> Vec<SearchDocument> {
+    let project = (scope == PromptScope::Project).then_some(base);
+    let mut documents = Vec::new();
+
+    for (path, name) in markdown_files(&base.join(".codex").join("prompts"), false) {
+        documents.extend(load_document(&path, DocumentKind::Prompt, scope, name, project));
+    }
+    for (path, name) in markdown_files(&base.join(".claude").join("commands"), true) {
+        documents.extend(load_document(&path, DocumentKind::ClaudeCommand, scope, name, project));
+    }
+
+    let agent_dirs = match scope {
+        PromptScope::User => vec![base.join(".codex"), base.join(".claude")],
+        PromptScope::Project => vec![base.to_path_buf()],
+    };
+    for dir in agent_dirs {
+        for file_name in AGENT_FILE_NAMES {
+            let path = dir.join(file_name);
+            if path.is_file() {
+                documents.extend(load_document(&path, DocumentKind::AgentFile, scope, file_name.to_string(), project));
+            }
+        }
+    }
+    documents
+}
+
+/// 搜索索引状态；首次搜索时构建，之后由文件监听增量更新
+#[derive(Default)]
+pub struct SearchIndexState {
+    inner: Mutex<IndexInner>,
+}
+
+#[derive(Default)]
+struct IndexInner {
+    index: SearchIndex,
+    built: bool,
+    projects: BTreeSet<PathBuf>,
+}
+
+impl IndexInner {
+    fn rebuild(&mut self, home: Option<&Path>) {
+        self.index = SearchIndex::default();
+        if let Some(home) = home {
+            for document in collect_documents(home, PromptScope::User) {
+                self.index.upsert(document);
+            }
+        }
+        for project in &self.projects {
+            for document in collect_documents(project, PromptScope::Project) {
+                self.index.upsert(document);
+            }
+        }
+        self.built = true;
+    }
+}
+
+fn home_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle.path().home_dir().ok()
+}
+
+/// 按文件监听推送的变更更新索引；索引尚未构建时忽略，首次搜索时会完整构建
+pub fn apply_changes(app_handle: &AppHandle, changes: &[FileChange]) {
+    let state = app_handle.state::<SearchIndexState>();
+    let Ok(mut inner) = state.inner.lock() else {
+        return;
+    };
+    if !inner.built {
+        return;
+    }
+    for change in changes {
+        let kind = match change.target {
+            ChangeTarget::Prompt => DocumentKind::Prompt,
+            ChangeTarget::ClaudeCommand => DocumentKind::ClaudeCommand,
+            ChangeTarget::AgentFile => DocumentKind::AgentFile,
+            ChangeTarget::Config => continue,
+        };
+        if change.change == ChangeKind::Removed {
+            inner.index.remove(&change.path);
+            continue;
+        }
+        let name = change
+            .name
+            .clone()
+            .unwrap_or_else(|| change.path.file_name().unwrap_or_default().to_string_lossy().to_string());
+        if let Some(document) = load_document(&change.path, kind, change.scope, name, change.project_path.as_deref()) {
+            inner.index.upsert(document);
+        }
+    }
+}
+
+/// 将项目加入索引范围
+pub fn add_project(app_handle: &AppHandle, project: &Path) {
+    let state = app_handle.state::<SearchIndexState>();
+    let Ok(mut inner) = state.inner.lock() else {
+        return;
+    };
+    if inner.projects.insert(project.to_path_buf()) && inner.built {
+        for document in collect_documents(project, PromptScope::Project) {
+            inner.index.upsert(document);
+        }
+    }
+}
+
+/// 将项目移出索引范围
+pub fn remove_project(app_handle: &AppHandle, project: &Path) {
+    let state = app_handle.state::<SearchIndexState>();
+    let Ok(mut inner) = state.inner.lock() else {
+        return;
+    };
+    inner.projects.remove(project);
+    inner.index.remove_project(project);
+}
+
+/// Tauri 命令：全文搜索提示词、Claude 命令和说明文件
+#[tauri::command]
+pub async fn search_prompts(app_handle: AppHandle, query: SearchQuery) -> AppResult<Vec<SearchHit>> {
+    let state = app_handle.state::<SearchIndexState>();
+    let mut inner = state.inner.lock().map_err(|_| AppError::lock())?;
+    if !inner.built {
+        inner.rebuild(home_dir(&app_handle).as_deref());
+    }
+    Ok(inner.index.search(&query))
+}
+
+/// Tauri 命令：重新扫描所有目录并重建索引，返回文档数
+#[tauri::command]
+pub async fn rebuild_search_index(app_handle: AppHandle) -> AppResult<usize> {
+    let state = app_handle.state::<SearchIndexState>();
+    let mut inner = state.inner.lock().map_err(|_| AppError::lock())?;
+    inner.rebuild(home_dir(&app_handle).as_deref());
+    Ok(inner.index.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(name: &str, description: &str, body: &str) -> SearchDocument {
+        SearchDocument {
+            kind: DocumentKind::Prompt,
+            scope: PromptScope::User,
+            name: name.to_string(),
+            description: description.to_string(),
+            category: Some("general".to_string()),
+            tools: vec!["Bash".to_string()],
+            body: body.to_string(),
+            path: PathBuf::from(format!("/prompts/{}.md", name)),
+            project_path: None,
+        }
+    }
+
+    fn query(text: &str) -> SearchQuery {
+        SearchQuery { query: text.to_string(), ..Default::default() }
+    }
+
+    fn names(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.document.name.as_str()).collect()
+    }
+
+    #[test]
+    fn tokenizes_mixed_latin_and_cjk_text() {
+        assert_eq!(tokenize("Review 代码审查 PR-42"), ["review", "代码", "码审", "审查", "pr", "42"]);
+        assert_eq!(tokenize("写"), ["写"]);
+        assert_eq!(tokenize("テスト"), ["テス", "スト"]);
+    }
+
+    #[test]
+    fn ranks_name_matches_above_body_matches() {
+        let mut index = SearchIndex::default();
+        index.upsert(document("notes", "misc", "remember to review the changes"));
+        index.upsert(document("review", "code review checklist", "check the diff"));
+
+        assert_eq!(names(&index.search(&query("review"))), ["review", "notes"]);
+    }
+
+    #[test]
+    fn finds_cjk_text_and_highlights_snippet() {
+        let mut index = SearchIndex::default();
+        index.upsert(document("refactor", "重构", "请帮我重构这段代码，保持行为不变"));
+        index.upsert(document("tests", "测试", "为这个模块补充单元测试"));
+
+        let hits = index.search(&query("重构"));
+        assert_eq!(names(&hits), ["refactor"]);
+        let (start, end) = hits[0].highlights[0];
+        let highlighted: String = hits[0].snippet.chars().skip(start).take(end - start).collect();
+        assert_eq!(highlighted, "重构");
+    }
+
+    #[test]
+    fn single_cjk_character_matches_inside_words() {
+        let mut index = SearchIndex::default();
+        index.upsert(document("review", "代码审查", "检查改动"));
+        index.upsert(document("tests", "测试", "补充单元测试"));
+
+        assert_eq!(names(&index.search(&query("审"))), ["review"]);
+        // 片段末尾的字只出现在二元组的第二位
+        assert_eq!(names(&index.search(&query("试"))), ["tests"]);
+        assert_eq!(names(&index.search(&query("查"))), ["review"]);
+    }
+
+    #[test]
+    fn last_term_matches_as_prefix() {
+        let mut index = SearchIndex::default();
+        index.upsert(document("deploy", "deployment steps", "ship it"));
+        assert_eq!(names(&index.search(&query("depl"))), ["deploy"]);
+    }
+
+    #[test]
+    fn filters_and_removal_apply() {
+        let mut index = SearchIndex::default();
+        index.upsert(document("a", "shared words", "alpha"));
+        let mut other = document("b", "shared words", "beta");
+        other.tools = vec!["Read".to_string()];
+        index.upsert(other);
+
+        let filtered = index.search(&SearchQuery { tool: Some("read".to_string()), ..query("shared") });
+        assert_eq!(names(&filtered), ["b"]);
+
+        index.remove(Path::new("/prompts/b.md"));
+        assert_eq!(names(&index.search(&query("shared"))), ["a"]);
+        assert!(index.search(&query("beta")).is_empty());
+    }
+}
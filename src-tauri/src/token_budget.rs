@@ -0,0 +1,353 @@
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+use tiktoken_rs::CoreBPE;
+
+use crate::error::{AppError, AppResult};
+use crate::i18n::tf;
+use crate::prompt_store::{PromptScope, PromptStore};
+use crate::prompt_template::{render, ArgumentValues, TemplateError};
+
+/// Codex 的默认模型，未指定模型时按它估算
+const DEFAULT_MODEL: &str = "gpt-5";
+
+/// Codex 读取项目说明文件的默认字节上限，可由 `~/.codex/config.toml` 的 `project_doc_max_bytes` 覆盖
+pub const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024;
+
+/// 每个目录中优先读取的说明文件，前者存在时忽略后者
+const INSTRUCTION_FILE_NAMES: &[&str] = &["AGENTS.override.md", "AGENTS.md"];
+
+/// 内置的 BPE 分词表，随程序打包，不需要联网
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenizerFamily {
+    /// GPT-4o、GPT-4.1、GPT-5、o 系列和 Codex 模型
+    O200k,
+    /// GPT-4、GPT-3.5
+    Cl100k,
+    /// 旧的 Codex 和 text-davinci 模型
+    P50k,
+    /// GPT-3
+    R50k,
+}
+
+impl TokenizerFamily {
+    fn bpe(self) -> &'static CoreBPE {
+        match self {
+            Self::O200k => tiktoken_rs::o200k_base_singleton(),
+            Self::Cl100k => tiktoken_rs::cl100k_base_singleton(),
+            Self::P50k => tiktoken_rs::p50k_base_singleton(),
+            Self::R50k => tiktoken_rs::r50k_base_singleton(),
+        }
+    }
+}
+
+/// 已知模型的分词表和上下文窗口，按顺序匹配第一个名称前缀
+///
+/// Claude 没有公开的分词表，用 cl100k 近似，结果标记为 `approximate`。
+const MODELS: &[(&str, TokenizerFamily, usize, bool)] = &[
+    ("gpt-5", TokenizerFamily::O200k, 272_000, false),
+    ("gpt-4.1", TokenizerFamily::O200k, 1_047_576, false),
+    ("gpt-4o", TokenizerFamily::O200k, 128_000, false),
+    ("o1", TokenizerFamily::O200k, 200_000, false),
+    ("o3", TokenizerFamily::O200k, 200_000, false),
+    ("o4", TokenizerFamily::O200k, 200_000, false),
+    ("codex", TokenizerFamily::O200k, 200_000, false),
+    ("gpt-4-turbo", TokenizerFamily::Cl100k, 128_000, false),
+    ("gpt-4", TokenizerFamily::Cl100k, 8_192, false),
+    ("gpt-3.5-turbo", TokenizerFamily::Cl100k, 16_385, false),
+    ("claude", TokenizerFamily::Cl100k, 200_000, true),
+];
+
+/// 估算使用的模型、分词表和上下文窗口
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelProfile {
+    pub model: String,
+    pub tokenizer: TokenizerFamily,
+    /// 分词表与模型实际使用的不同，结果只是近似值
+    pub approximate: bool,
+    pub context_window: Option<usize>,
+}
+
+impl ModelProfile {
+    /// 按模型名选择分词表；未知模型使用 o200k 并标记为近似。`tokenizer` 和 `context_window` 覆盖默认值
+    pub fn resolve(model: Option<&str>, tokenizer: Option<TokenizerFamily>, context_window: Option<usize>) -> Self {
+        let model = model.map(str::trim).filter(|m| !m.is_empty()).unwrap_or(DEFAULT_MODEL).to_string();
+        let lower = model.to_ascii_lowercase();
+        let known = MODELS.iter().find(|(prefix, ..)| lower.starts_with(prefix));
+        let (family, window, approximate) = match known {
+            Some(&(_, family, window, approximate)) => (family, Some(window), approximate),
+            None => (TokenizerFamily::O200k, None, true),
+        };
+        Self {
+            model,
+            tokenizer: tokenizer.unwrap_or(family),
+            approximate,
+            context_window: context_window.or(window),
+        }
+    }
+
+    pub fn count(&self, text: &str) -> TokenCount {
+        TokenCount {
+            tokens: self.tokenizer.bpe().encode_ordinary(text).len(),
+            characters: text.chars().count(),
+            bytes: text.len(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCount {
+    pub tokens: usize,
+    pub characters: usize,
+    pub bytes: usize,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextTokens {
+    pub profile: ModelProfile,
+    #[serde(flatten)]
+    pub count: TokenCount,
+}
+
+/// 提示词的 token 数；给出参数时同时统计渲染结果
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTokens {
+    pub profile: ModelProfile,
+    /// 文件正文（不含 frontmatter）
+    pub source: TokenCount,
+    /// 渲染后的文本；没有给出参数或渲染失败时为空
+    pub rendered: Option<TokenCount>,
+    pub errors: Vec<TemplateError>,
+}
+
+/// 说明文件链中的一个文件；`count` 只统计实际被加载的部分
+#[derive(serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionFile {
+    pub path: PathBuf,
+    pub scope: PromptScope,
+    #[serde(flatten)]
+    pub count: TokenCount,
+    /// 文件的完整大小
+    pub file_bytes: usize,
+    /// 超出项目说明的字节上限，只加载了前一部分或完全没有加载
+    pub truncated: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionBudget {
+    pub profile: ModelProfile,
+    pub files: Vec<InstructionFile>,
+    pub total_tokens: usize,
+    /// 占上下文窗口的百分比
+    pub window_percent: Option<f64>,
+    pub max_project_bytes: usize,
+}
+
+/// 目录中优先级最高的非空说明文件
+fn instruction_file(dir: &Path) -> Option<(PathBuf, String)> {
+    INSTRUCTION_FILE_NAMES.iter().find_map(|name| {
+        let path = dir.join(name);
+        let text = std::fs::read_to_string(&path).ok()?;
+        (!text.trim().is_empty()).then_some((path, text))
+    })
+}
+
+/// 从项目目录向上查找包含 `.git` 的仓库根目录；不在仓库中时返回项目目录本身
+fn project_root(project: &Path) -> &Path {
+    project.ancestors().find(|dir| dir.join(".git").exists()).unwrap_or(project)
+}
+
+/// 按 Codex 的加载顺序收集说明文件及实际加载的内容
+///
+/// 先是 `<codex_home>` 下的全局说明，再从仓库根目录到项目目录逐级读取；
+/// 项目说明的总大小受 `max_project_bytes` 限制，超出部分被截断。
+/// 每个目录只取 `AGENTS.override.md` 或 `AGENTS.md` 中的一个。
+pub fn instruction_stack(codex_home: &Path, project: Option<&Path>, max_project_bytes: usize) -> Vec<(InstructionFile, String)> {
+    let mut stack = Vec::new();
+    if let Some((path, text)) = instruction_file(codex_home) {
+        let file = InstructionFile {
+            path,
+            scope: PromptScope::User,
+            count: TokenCount::default(),
+            file_bytes: text.len(),
+            truncated: false,
+        };
+        stack.push((file, text));
+    }
+    let Some(project) = project else {
+        return stack;
+    };
+
+    let root = project_root(project);
+    let mut dirs: Vec<&Path> = project.ancestors().take_while(|dir| dir.starts_with(root)).collect();
+    dirs.reverse();
+    let mut remaining = max_project_bytes;
+    for dir in dirs {
+        let Some((path, text)) = instruction_file(dir) else {
+            continue;
+        };
+        let file_bytes = text.len();
+        let mut end = file_bytes.min(remaining);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        remaining -= end;
+        let file = InstructionFile {
+            path,
+            scope: PromptScope::Project,
+            count: TokenCount::default(),
+            file_bytes,
+            truncated: end < file_bytes,
+        };
+        stack.push((file, text[..end].to_string()));
+    }
+    stack
+}
+
+/// `~/.codex/config.toml` 中的 `project_doc_max_bytes`，未设置时为默认值
+fn project_doc_max_bytes(codex_home: &Path) -> usize {
+    std::fs::read_to_string(codex_home.join("config.toml"))
+        .ok()
+        .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok())
+        .and_then(|doc| doc.get("project_doc_max_bytes").and_then(|v| v.as_integer()))
+        .and_then(|n| usize::try_from(n).ok())
+        .unwrap_or(PROJECT_DOC_MAX_BYTES)
+}
+
+/// 统计说明文件链的 token 数；文件之间的分隔符不计入
+pub fn instruction_budget(profile: ModelProfile, codex_home: &Path, project: Option<&Path>) -> InstructionBudget {
+    let max_project_bytes = project_doc_max_bytes(codex_home);
+    let files: Vec<InstructionFile> = instruction_stack(codex_home, project, max_project_bytes)
+        .into_iter()
+        .map(|(file, loaded)| InstructionFile { count: profile.count(&loaded), ..file })
+        .collect();
+    let total_tokens = files.iter().map(|f| f.count.tokens).sum();
+    InstructionBudget {
+        window_percent: profile.context_window.map(|window| total_tokens as f64 * 100.0 / window as f64),
+        profile,
+        files,
+        total_tokens,
+        max_project_bytes,
+    }
+}
+
+fn codex_home(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    app_handle
+        .path()
+        .home_dir()
+        .map(|home| home.join(".codex"))
+        .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))
+}
+
+/// Tauri 命令：统计任意文本的 token 数，例如 `render_template` 的预览结果
+#[tauri::command(rename_all = "camelCase")]
+pub async fn count_tokens(text: String, model: Option<String>, tokenizer: Option<TokenizerFamily>) -> AppResult<TextTokens> {
+    let profile = ModelProfile::resolve(model.as_deref(), tokenizer, None);
+    let count = profile.count(&text);
+    Ok(TextTokens { profile, count })
+}
+
+/// Tauri 命令：统计已保存提示词的 token 数；给出 `values` 或 `rawArguments` 时同时统计渲染结果
+#[tauri::command(rename_all = "camelCase")]
+pub async fn count_prompt_tokens(
+    app_handle: AppHandle,
+    name: String,
+    project_path: Option<String>,
+    values: Option<ArgumentValues>,
+    raw_arguments: Option<String>,
+    model: Option<String>,
+    tokenizer: Option<TokenizerFamily>,
+) -> AppResult<PromptTokens> {
+    let prompt = PromptStore::for_scope(&app_handle, project_path.as_deref())?.get(&name)?;
+    let profile = ModelProfile::resolve(model.as_deref(), tokenizer, None);
+    let source = profile.count(&prompt.content);
+    let (rendered, errors) = if values.is_some() || raw_arguments.is_some() {
+        let result = render(&prompt.content, &prompt.arguments, &values.unwrap_or_default(), raw_arguments.as_deref());
+        (result.text.map(|text| profile.count(&text)), result.errors)
+    } else {
+        (None, Vec::new())
+    };
+    Ok(PromptTokens { profile, source, rendered, errors })
+}
+
+/// Tauri 命令：估算 Codex 为项目加载的全局和项目 AGENTS.md 占用的 token，按文件列出
+#[tauri::command(rename_all = "camelCase")]
+pub async fn estimate_instruction_tokens(
+    app_handle: AppHandle,
+    project_path: Option<String>,
+    model: Option<String>,
+    tokenizer: Option<TokenizerFamily>,
+    context_window: Option<usize>,
+) -> AppResult<InstructionBudget> {
+    let home = codex_home(&app_handle)?;
+    let profile = ModelProfile::resolve(model.as_deref(), tokenizer, context_window);
+    tauri::async_runtime::spawn_blocking(move || instruction_budget(profile, &home, project_path.as_deref().map(Path::new)))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_models_to_tokenizers() {
+        let codex = ModelProfile::resolve(Some("gpt-5-codex"), None, None);
+        assert_eq!((codex.tokenizer, codex.approximate, codex.context_window), (TokenizerFamily::O200k, false, Some(272_000)));
+        assert_eq!(ModelProfile::resolve(Some("gpt-4-0613"), None, None).tokenizer, TokenizerFamily::Cl100k);
+        assert!(ModelProfile::resolve(Some("claude-sonnet-4"), None, None).approximate);
+
+        let unknown = ModelProfile::resolve(Some("local-llm"), Some(TokenizerFamily::P50k), Some(4096));
+        assert_eq!((unknown.tokenizer, unknown.approximate, unknown.context_window), (TokenizerFamily::P50k, true, Some(4096)));
+        assert_eq!(ModelProfile::resolve(None, None, None).model, DEFAULT_MODEL);
+    }
+
+    #[test]
+    fn counts_tokens_with_bundled_tables() {
+        for family in [TokenizerFamily::O200k, TokenizerFamily::Cl100k] {
+            let profile = ModelProfile::resolve(None, Some(family), None);
+            assert_eq!(profile.count("hello world").tokens, 2);
+        }
+        let count = ModelProfile::resolve(None, None, None).count("你好");
+        assert_eq!((count.characters, count.bytes), (2, 6));
+        assert!(count.tokens >= 1);
+    }
+
+    #[test]
+    fn collects_the_instruction_chain_in_load_order() {
+        let home = tempfile::tempdir().unwrap();
+        let repo = tempfile::tempdir().unwrap();
+        let app = repo.path().join("apps").join("web");
+        std::fs::create_dir_all(repo.path().join(".git")).unwrap();
+        std::fs::create_dir_all(&app).unwrap();
+        std::fs::write(home.path().join("AGENTS.md"), "global rules").unwrap();
+        std::fs::write(repo.path().join("AGENTS.md"), "root rules").unwrap();
+        std::fs::write(app.join("AGENTS.md"), "ignored").unwrap();
+        std::fs::write(app.join("AGENTS.override.md"), "web rules").unwrap();
+        // 空文件不算
+        std::fs::write(repo.path().join("apps").join("AGENTS.md"), "\n").unwrap();
+
+        let stack = instruction_stack(home.path(), Some(&app), PROJECT_DOC_MAX_BYTES);
+        let loaded: Vec<(&str, bool)> = stack.iter().map(|(f, text)| (text.as_str(), f.scope == PromptScope::User)).collect();
+        assert_eq!(loaded, [("global rules", true), ("root rules", false), ("web rules", false)]);
+
+        // 项目说明的上限为 12 字节：根目录的 10 字节全部加载，下一个文件只剩 2 字节
+        let stack = instruction_stack(home.path(), Some(&app), 12);
+        let truncated: Vec<(&str, bool)> = stack.iter().map(|(f, text)| (text.as_str(), f.truncated)).collect();
+        assert_eq!(truncated, [("global rules", false), ("root rules", false), ("we", true)]);
+
+        std::fs::write(home.path().join("config.toml"), "project_doc_max_bytes = 12\n").unwrap();
+        let budget = instruction_budget(ModelProfile::resolve(None, None, None), home.path(), Some(&app));
+        assert_eq!(budget.max_project_bytes, 12);
+        assert_eq!(budget.files[2].file_bytes, 9);
+        assert_eq!(budget.total_tokens, budget.files.iter().map(|f| f.count.tokens).sum::<usize>());
+        assert!(budget.window_percent.is_some_and(|p| p > 0.0));
+    }
+}
@@ -0,0 +1,783 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use crate::error::{AppError, AppResult, ErrorCode};
+use crate::i18n::tf;
+use crate::resource_manager::PathProvider;
+
+/// 工具索引中某个版本在某个平台上的下载信息
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolArtifact {
+    /// 下载地址，可以是相对索引文件的路径
+    pub url: String,
+    /// 压缩包的 SHA-256（十六进制）
+    pub sha256: String,
+    /// zip / tar.gz / raw，缺省时按 URL 后缀判断
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// 工具索引中的一个版本
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolRelease {
+    pub version: String,
+    /// 平台目录名（例如 `windows-x64`、`linux`）到下载信息的映射
+    pub platforms: HashMap<String, ToolArtifact>,
+}
+
+/// 工具索引中的一个工具
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedTool {
+    pub name: String,
+    /// 不含扩展名的可执行文件名
+    pub executable: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 按发布顺序排列，最后一个为最新版本
+    pub releases: Vec<ToolRelease>,
+}
+
+/// 远程工具索引
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolIndex {
+    #[serde(default)]
+    pub tools: Vec<IndexedTool>,
+}
+
+/// 工具仓库配置，保存在 `<app_config>/tool-registry.json`
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryConfig {
+    /// 索引地址，必须是 `https://`；debug 构建也接受 `file://`
+    #[serde(default)]
+    pub index_url: Option<String>,
+}
+
+/// 安装某个版本时写入的记录，位于版本目录下的 `install.json`
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallReceipt {
+    pub name: String,
+    pub version: String,
+    pub platform: String,
+    /// 可执行文件相对版本目录的路径
+    pub executable: PathBuf,
+    /// 可执行文件的 SHA-256，执行前据此校验
+    pub executable_sha256: String,
+    pub source_url: String,
+    pub installed_at: u64,
+}
+
+/// 已安装工具的概况
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledTool {
+    pub name: String,
+    pub versions: Vec<String>,
+    pub active: Option<String>,
+}
+
+/// 已安装工具的存储，位于 `<app_data>/tools/`
+///
+/// 目录结构为 `<name>/<version>/`，`<name>/active` 文件记录当前使用的版本。
+/// 只依赖根目录路径，便于针对临时目录和 `file://` 索引测试。
+pub struct ToolStore {
+    root: PathBuf,
+}
+
+fn io_error(e: impl std::fmt::Display, path: &Path) -> AppError {
+    AppError::io(tf("error.readFile", &[("error", &e)]), path)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 发布者的 Ed25519 公钥（十六进制），构建时由环境变量 `MAKINGSTORE_TOOL_PUBLISHER_KEY` 写入
+///
+/// 索引必须带有该公钥签名的 `<index_url>.sig`，未配置公钥的构建不能安装工具。
+const PUBLISHER_KEY: Option<&str> = option_env!("MAKINGSTORE_TOOL_PUBLISHER_KEY");
+
+pub(crate) fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    let value = value.trim();
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| value.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
+
+/// 解析十六进制的 Ed25519 公钥
+pub fn parse_public_key(hex: &str) -> AppResult<VerifyingKey> {
+    decode_hex(hex)
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| AppError::new(ErrorCode::InvalidConfig, tf("error.invalidPublisherKey", &[])))
+}
+
+/// 构建时固定的发布者公钥
+pub fn publisher_key() -> AppResult<VerifyingKey> {
+    PUBLISHER_KEY
+        .ok_or_else(|| AppError::new(ErrorCode::InvalidConfig, tf("error.noPublisherKey", &[])))
+        .and_then(parse_public_key)
+}
+
+/// 校验索引内容的分离签名（十六进制编码的 64 字节 Ed25519 签名）
+fn verify_signature(key: &VerifyingKey, content: &[u8], signature_hex: &[u8]) -> AppResult<()> {
+    let invalid = || AppError::new(ErrorCode::ChecksumMismatch, tf("error.indexSignature", &[]));
+    let signature = std::str::from_utf8(signature_hex)
+        .ok()
+        .and_then(decode_hex)
+        .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+        .map(|bytes| Signature::from_bytes(&bytes))
+        .ok_or_else(invalid)?;
+    key.verify_strict(content, &signature).map_err(|_| invalid())
+}
+
+/// 按语义化版本比较：数字段按数值比较，预发布版本低于对应的正式版本，
+/// 构建元数据（`+` 之后）不参与比较。无法解析的部分按字符串比较。
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (&str, Option<&str>) {
+        let version = version.trim_start_matches('v');
+        let version = version.split_once('+').map_or(version, |(v, _)| v);
+        match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        }
+    }
+    fn compare_identifiers(x: &str, y: &str) -> Ordering {
+        match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            // 数字标识符低于字母标识符
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        }
+    }
+    fn compare_parts(a: &str, b: &str) -> Ordering {
+        let (mut left, mut right) = (a.split('.'), b.split('.'));
+        loop {
+            let order = match (left.next(), right.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(x), Some(y)) => compare_identifiers(x, y),
+            };
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+    }
+
+    let ((core_a, pre_a), (core_b, pre_b)) = (split(a), split(b));
+    compare_parts(core_a, core_b).then_with(|| match (pre_a, pre_b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(x), Some(y)) => compare_parts(x, y),
+    })
+}
+
+/// 名称和版本会成为目录名，拒绝路径分隔符和 `..`
+fn validate_segment(value: &str) -> AppResult<()> {
+    if value.is_empty() || value == "." || value == ".." || value.contains(['/', '\\', ':']) {
+        return Err(AppError::new(
+            ErrorCode::InvalidArgument,
+            tf("error.invalidToolName", &[("name", &value)]),
+        ));
+    }
+    Ok(())
+}
+
+/// `file://` 地址转换为本地路径，兼容 Windows 的 `file:///C:/...`
+fn file_url_to_path(url: &str) -> Option<PathBuf> {
+    let rest = url.strip_prefix("file://")?;
+    let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+    let bytes = rest.as_bytes();
+    if bytes.len() > 2 && bytes[0] == b'/' && bytes[2] == b':' {
+        return Some(PathBuf::from(&rest[1..]));
+    }
+    Some(PathBuf::from(rest))
+}
+
+/// 读取 `https://` 地址的内容
+///
+/// debug 构建还接受 `file://`，便于在本地调试索引和编写测试；明文 `http://` 总是拒绝。
+pub fn fetch_bytes(url: &str) -> AppResult<Vec<u8>> {
+    let fetch_error = |e: &dyn std::fmt::Display| {
+        AppError::new(ErrorCode::Io, tf("error.download", &[("url", &url), ("error", e)]))
+    };
+
+    if let Some(path) = file_url_to_path(url).filter(|_| cfg!(debug_assertions)) {
+        return std::fs::read(&path).map_err(|e| fetch_error(&e).with_path(&path));
+    }
+    if url.starts_with("https://") {
+        let response = ureq::get(url).call().map_err(|e| fetch_error(&e))?;
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut bytes)
+            .map_err(|e| fetch_error(&e))?;
+        return Ok(bytes);
+    }
+    Err(AppError::new(
+        ErrorCode::InvalidArgument,
+        tf("error.unsupportedUrl", &[("url", &url)]),
+    ))
+}
+
+/// 将相对地址解析为相对索引文件所在位置的绝对地址
+fn join_url(index_url: &str, url: &str) -> String {
+    if url.contains("://") {
+        return url.to_string();
+    }
+    match index_url.rfind('/') {
+        Some(pos) => format!("{}/{}", &index_url[..pos], url.trim_start_matches("./")),
+        None => url.to_string(),
+    }
+}
+
+/// 读取工具索引，用发布者公钥校验 `<index_url>.sig` 后再解析
+///
+/// 索引中记录了每个下载文件的 SHA-256，签名通过后这些校验和才可信。
+pub fn fetch_index(index_url: &str, publisher_key: &VerifyingKey) -> AppResult<ToolIndex> {
+    let bytes = fetch_bytes(index_url)?;
+    let signature = fetch_bytes(&format!("{}.sig", index_url))?;
+    verify_signature(publisher_key, &bytes, &signature)?;
+    serde_json::from_slice(&bytes).map_err(|e| {
+        AppError::new(ErrorCode::InvalidConfig, tf("error.invalidIndex", &[("error", &e)]))
+    })
+}
+
+/// 解压下载的压缩包到目标目录
+fn unpack(bytes: &[u8], format: &str, file_name: &str, dest: &Path) -> AppResult<()> {
+    let unpack_error = |e: &dyn std::fmt::Display| {
+        AppError::new(ErrorCode::Io, tf("error.unpack", &[("error", e)])).with_path(dest)
+    };
+
+    match format {
+        "zip" => {
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(|e| unpack_error(&e))?;
+            // extract 会拒绝指向目标目录之外的条目
+            archive.extract(dest).map_err(|e| unpack_error(&e))
+        }
+        "tar.gz" | "tgz" => {
+            let decoder = flate2::read::GzDecoder::new(bytes);
+            // unpack 会拒绝包含 `..` 的条目
+            tar::Archive::new(decoder).unpack(dest).map_err(|e| unpack_error(&e))
+        }
+        "raw" => std::fs::write(dest.join(file_name), bytes).map_err(|e| unpack_error(&e)),
+        other => Err(AppError::new(
+            ErrorCode::InvalidConfig,
+            tf("error.unsupportedArchive", &[("format", &other)]),
+        )),
+    }
+}
+
+/// 根据 URL 推断压缩格式
+fn infer_format(url: &str) -> &'static str {
+    let lower = url.to_ascii_lowercase();
+    if lower.ends_with(".zip") {
+        "zip"
+    } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        "tar.gz"
+    } else {
+        "raw"
+    }
+}
+
+/// 在解压后的目录中查找可执行文件，返回相对路径
+fn find_executable(dir: &Path, candidates: &[String]) -> Option<PathBuf> {
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current).ok()?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .file_name()
+                .is_some_and(|name| candidates.iter().any(|c| name.to_string_lossy() == c.as_str()))
+            {
+                return path.strip_prefix(dir).ok().map(Path::to_path_buf);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(unix)]
+fn mark_executable(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn mark_executable(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+impl ToolStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// `<app_data>/tools`
+    pub fn for_app(paths: &impl PathProvider) -> AppResult<Self> {
+        paths
+            .app_data_dir()
+            .map(|dir| Self::new(dir.join("tools")))
+            .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))
+    }
+
+    fn tool_dir(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    fn version_dir(&self, name: &str, version: &str) -> PathBuf {
+        self.tool_dir(name).join(version)
+    }
+
+    /// 当前使用的版本
+    pub fn active_version(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(self.tool_dir(name).join("active"))
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty() && self.version_dir(name, v).is_dir())
+    }
+
+    /// 读取某个已安装版本的记录
+    pub fn receipt(&self, name: &str, version: &str) -> Option<InstallReceipt> {
+        let content = std::fs::read_to_string(self.version_dir(name, version).join("install.json")).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// 当前使用版本的可执行文件路径及其记录
+    pub fn active_executable(&self, name: &str) -> Option<(PathBuf, InstallReceipt)> {
+        let version = self.active_version(name)?;
+        let receipt = self.receipt(name, &version)?;
+        let path = self.version_dir(name, &version).join(&receipt.executable);
+        path.is_file().then_some((path, receipt))
+    }
+
+    /// 已安装的版本，按语义化版本从低到高排序
+    pub fn installed_versions(&self, name: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(self.tool_dir(name)) else {
+            return Vec::new();
+        };
+        let mut versions: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().join("install.json").is_file())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions
+    }
+
+    /// 所有已安装的工具
+    pub fn list(&self) -> Vec<InstalledTool> {
+        let Ok(entries) = std::fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+        let mut tools: Vec<InstalledTool> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .map(|name| InstalledTool {
+                versions: self.installed_versions(&name),
+                active: self.active_version(&name),
+                name,
+            })
+            .filter(|tool| !tool.versions.is_empty())
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        tools
+    }
+
+    /// 切换当前使用的版本
+    pub fn activate(&self, name: &str, version: &str) -> AppResult<()> {
+        validate_segment(name)?;
+        validate_segment(version)?;
+        if self.receipt(name, version).is_none() {
+            return Err(AppError::new(
+                ErrorCode::NotFound,
+                tf("error.versionNotInstalled", &[("name", &name), ("version", &version)]),
+            ));
+        }
+        let path = self.tool_dir(name).join("active");
+        std::fs::write(&path, version).map_err(|e| io_error(e, &path))
+    }
+
+    /// 删除某个已安装版本；删除的是当前版本时同时清除 active 记录
+    pub fn remove(&self, name: &str, version: &str) -> AppResult<()> {
+        validate_segment(name)?;
+        validate_segment(version)?;
+        let dir = self.version_dir(name, version);
+        if !dir.is_dir() {
+            return Err(AppError::new(
+                ErrorCode::NotFound,
+                tf("error.versionNotInstalled", &[("name", &name), ("version", &version)]),
+            ));
+        }
+        if self.active_version(name).as_deref() == Some(version) {
+            let _ = std::fs::remove_file(self.tool_dir(name).join("active"));
+        }
+        std::fs::remove_dir_all(&dir).map_err(|e| io_error(e, &dir))
+    }
+
+    /// 从索引下载并安装工具
+    ///
+    /// `version` 为空时安装最新版本；`platforms` 为按优先级排列的平台目录名。
+    /// 索引签名必须能用 `publisher_key` 验证，下载内容的 SHA-256 必须与索引一致，
+    /// 解压到临时目录后再整体移动到版本目录。安装不会切换当前版本。
+    pub fn install(
+        &self,
+        index_url: &str,
+        publisher_key: &VerifyingKey,
+        name: &str,
+        version: Option<&str>,
+        platforms: &[String],
+        executable_candidates: impl Fn(&str) -> Vec<String>,
+    ) -> AppResult<InstallReceipt> {
+        validate_segment(name)?;
+        let index = fetch_index(index_url, publisher_key)?;
+        let tool = index.tools.iter().find(|t| t.name == name).ok_or_else(|| {
+            AppError::new(ErrorCode::NotFound, tf("error.toolNotInIndex", &[("name", &name)]))
+        })?;
+        // 可执行文件名来自远程索引，会被拼接为解压目录下的路径，只允许单个文件名
+        validate_segment(&tool.executable)?;
+        let release = match version {
+            Some(v) => tool.releases.iter().find(|r| r.version == v),
+            None => tool.releases.iter().max_by(|a, b| compare_versions(&a.version, &b.version)),
+        }
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorCode::NotFound,
+                tf("error.versionNotFound", &[("name", &name), ("version", &version.unwrap_or("latest"))]),
+            )
+        })?;
+        validate_segment(&release.version)?;
+
+        let (platform, artifact) = platforms
+            .iter()
+            .find_map(|p| release.platforms.get(p).map(|a| (p.clone(), a)))
+            .ok_or_else(|| {
+                AppError::new(
+                    ErrorCode::Unsupported,
+                    tf("error.noArtifact", &[("name", &name), ("version", &release.version)]),
+                )
+            })?;
+
+        let url = join_url(index_url, &artifact.url);
+        let bytes = fetch_bytes(&url)?;
+        let actual = sha256_hex(&bytes);
+        if !actual.eq_ignore_ascii_case(artifact.sha256.trim()) {
+            return Err(AppError::new(
+                ErrorCode::ChecksumMismatch,
+                tf("error.downloadChecksum", &[("name", &name)]),
+            )
+            .with_program(name));
+        }
+
+        let tool_dir = self.tool_dir(name);
+        let staging = tool_dir.join(format!(".staging-{}", release.version));
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging).map_err(|e| io_error(e, &staging))?;
+        }
+        std::fs::create_dir_all(&staging).map_err(|e| io_error(e, &staging))?;
+
+        let result = (|| {
+            let format = artifact.format.as_deref().unwrap_or_else(|| infer_format(&url));
+            let not_found = || {
+                AppError::new(
+                    ErrorCode::ExecutableNotFound,
+                    tf("error.executableNotFound", &[("program", &tool.executable)]),
+                )
+                .with_path(&staging)
+            };
+            let candidates = executable_candidates(&tool.executable);
+            unpack(&bytes, format, candidates.first().ok_or_else(not_found)?, &staging)?;
+
+            let executable = find_executable(&staging, &candidates).ok_or_else(not_found)?;
+            let exe_path = staging.join(&executable);
+            mark_executable(&exe_path).map_err(|e| io_error(e, &exe_path))?;
+            let exe_bytes = std::fs::read(&exe_path).map_err(|e| io_error(e, &exe_path))?;
+
+            let receipt = InstallReceipt {
+                name: name.to_string(),
+                version: release.version.clone(),
+                platform,
+                executable,
+                executable_sha256: sha256_hex(&exe_bytes),
+                source_url: url.clone(),
+                installed_at: crate::process::now_millis(),
+            };
+            let receipt_path = staging.join("install.json");
+            let content = serde_json::to_string_pretty(&receipt).map_err(|e| io_error(e, &receipt_path))?;
+            std::fs::write(&receipt_path, content).map_err(|e| io_error(e, &receipt_path))?;
+
+            let target = self.version_dir(name, &release.version);
+            if target.exists() {
+                std::fs::remove_dir_all(&target).map_err(|e| io_error(e, &target))?;
+            }
+            std::fs::rename(&staging, &target).map_err(|e| io_error(e, &target))?;
+            Ok(receipt)
+        })();
+
+        if result.is_err() {
+            let _ = std::fs::remove_dir_all(&staging);
+        }
+        result
+    }
+}
+
+fn config_path(app_handle: &AppHandle) -> AppResult<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join("tool-registry.json"))
+        .map_err(|e| AppError::internal(tf("error.resolveDir", &[("error", &e)])))
+}
+
+/// 读取工具仓库配置；环境变量 `MAKINGSTORE_TOOL_INDEX` 优先于配置文件
+///
+/// 配置文件位于 `<app_config>`，不在前端的文件系统作用域内，也没有写入命令，
+/// 页面无法把索引指向其他地址。
+pub fn load_config(app_handle: &AppHandle) -> AppResult<RegistryConfig> {
+    let path = config_path(app_handle)?;
+    let mut config: RegistryConfig = match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| {
+            AppError::new(ErrorCode::InvalidConfig, tf("error.invalidIndex", &[("error", &e)])).with_path(&path)
+        })?,
+        Err(_) => RegistryConfig::default(),
+    };
+    if let Ok(url) = std::env::var("MAKINGSTORE_TOOL_INDEX") {
+        config.index_url = Some(url);
+    }
+    Ok(config)
+}
+
+fn index_url(app_handle: &AppHandle) -> AppResult<String> {
+    load_config(app_handle)?
+        .index_url
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| AppError::new(ErrorCode::InvalidConfig, tf("error.noIndexUrl", &[])))
+}
+
+/// Tauri 命令：读取工具仓库配置
+#[tauri::command]
+pub async fn get_tool_registry_config(app_handle: AppHandle) -> AppResult<RegistryConfig> {
+    load_config(&app_handle)
+}
+
+/// Tauri 命令：获取远程索引中的工具列表
+#[tauri::command]
+pub async fn fetch_tool_index(app_handle: AppHandle) -> AppResult<ToolIndex> {
+    let url = index_url(&app_handle)?;
+    let key = publisher_key()?;
+    tauri::async_runtime::spawn_blocking(move || fetch_index(&url, &key))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+/// Tauri 命令：列出已安装的工具及版本
+#[tauri::command]
+pub async fn list_installed_tools(app_handle: AppHandle) -> AppResult<Vec<InstalledTool>> {
+    Ok(ToolStore::for_app(&app_handle)?.list())
+}
+
+/// Tauri 命令：安装指定版本，默认安装最新版本
+///
+/// 安装后仍使用原来的版本，需要通过 `activate_tool_version` 显式切换。
+#[tauri::command]
+pub async fn install_tool(app_handle: AppHandle, name: String, version: Option<String>) -> AppResult<InstallReceipt> {
+    let url = index_url(&app_handle)?;
+    let key = publisher_key()?;
+    let store = ToolStore::for_app(&app_handle)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let platforms = crate::resource_manager::platform_dirs();
+        store.install(
+            &url,
+            &key,
+            &name,
+            version.as_deref(),
+            &platforms,
+            crate::resource_manager::file_name_candidates,
+        )
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+/// Tauri 命令：切换工具的当前版本
+#[tauri::command]
+pub async fn activate_tool_version(app_handle: AppHandle, name: String, version: String) -> AppResult<()> {
+    ToolStore::for_app(&app_handle)?.activate(&name, &version)
+}
+
+/// Tauri 命令：删除已安装的某个版本
+#[tauri::command]
+pub async fn remove_tool_version(app_handle: AppHandle, name: String, version: String) -> AppResult<()> {
+    ToolStore::for_app(&app_handle)?.remove(&name, &version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// 写入索引和对应的签名文件，返回索引的 `file://` 地址
+    fn publish_index(dir: &Path, index: &str, key: &SigningKey) -> String {
+        std::fs::write(dir.join("index.json"), index).unwrap();
+        std::fs::write(dir.join("index.json.sig"), hex(&key.sign(index.as_bytes()).to_bytes())).unwrap();
+        format!("file://{}", dir.join("index.json").to_string_lossy().replace('\\', "/"))
+    }
+
+    fn release(version: &str, artifact: &[u8]) -> String {
+        format!(
+            r#"{{"version":"{}","platforms":{{"linux":{{"url":"tool-{}","sha256":"{}","format":"raw"}}}}}}"#,
+            version,
+            version,
+            sha256_hex(artifact)
+        )
+    }
+
+    fn candidates(name: &str) -> Vec<String> {
+        vec![name.to_string()]
+    }
+
+    #[test]
+    fn versions_sort_by_semver() {
+        let mut versions = vec!["1.10.0", "1.2.0", "1.2.0-rc.1", "1.2.0-beta", "v0.9", "1.2.0-rc.10", "1.2.0+build.5"];
+        versions.sort_by(|a, b| compare_versions(a, b));
+        assert_eq!(versions, ["v0.9", "1.2.0-beta", "1.2.0-rc.1", "1.2.0-rc.10", "1.2.0", "1.2.0+build.5", "1.10.0"]);
+    }
+
+    #[test]
+    fn only_https_and_debug_file_urls_are_fetched() {
+        assert_eq!(fetch_bytes("http://example.com/index.json").err().unwrap().code, ErrorCode::InvalidArgument);
+        assert_eq!(fetch_bytes("ftp://example.com/index.json").err().unwrap().code, ErrorCode::InvalidArgument);
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), b"local").unwrap();
+        let url = format!("file://{}", dir.path().join("a.txt").to_string_lossy().replace('\\', "/"));
+        assert_eq!(fetch_bytes(&url).unwrap(), b"local");
+    }
+
+    #[test]
+    fn index_signature_must_match_the_pinned_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let publisher = SigningKey::from_bytes(&[7; 32]);
+        let attacker = SigningKey::from_bytes(&[9; 32]);
+        let index = r#"{"tools":[]}"#;
+
+        let url = publish_index(dir.path(), index, &publisher);
+        assert!(fetch_index(&url, &publisher.verifying_key()).is_ok());
+
+        publish_index(dir.path(), index, &attacker);
+        let err = fetch_index(&url, &publisher.verifying_key()).err().unwrap();
+        assert_eq!(err.code, ErrorCode::ChecksumMismatch);
+
+        // 签名之后改动的索引同样被拒绝
+        publish_index(dir.path(), index, &publisher);
+        std::fs::write(dir.path().join("index.json"), r#"{"tools":[ ]}"#).unwrap();
+        assert!(fetch_index(&url, &publisher.verifying_key()).is_err());
+
+        std::fs::remove_file(dir.path().join("index.json.sig")).unwrap();
+        assert!(fetch_index(&url, &publisher.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn parses_hex_public_keys() {
+        let key = SigningKey::from_bytes(&[7; 32]).verifying_key();
+        assert_eq!(parse_public_key(&hex(key.as_bytes())).unwrap(), key);
+        assert!(parse_public_key("abcd").is_err());
+        assert!(parse_public_key(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn install_picks_the_highest_version_without_activating_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let publisher = SigningKey::from_bytes(&[7; 32]);
+        for version in ["1.9.0", "1.10.0"] {
+            std::fs::write(dir.path().join(format!("tool-{}", version)), version).unwrap();
+        }
+        let index = format!(
+            r#"{{"tools":[{{"name":"tool","executable":"tool","releases":[{},{}]}}]}}"#,
+            release("1.10.0", b"1.10.0"),
+            release("1.9.0", b"1.9.0")
+        );
+        let url = publish_index(dir.path(), &index, &publisher);
+
+        let store = ToolStore::new(dir.path().join("tools"));
+        let platforms = ["linux".to_string()];
+        let latest = store.install(&url, &publisher.verifying_key(), "tool", None, &platforms, candidates).unwrap();
+        assert_eq!(latest.version, "1.10.0");
+        assert_eq!(store.active_version("tool"), None);
+        assert!(store.active_executable("tool").is_none());
+
+        store.install(&url, &publisher.verifying_key(), "tool", Some("1.9.0"), &platforms, candidates).unwrap();
+        assert_eq!(store.installed_versions("tool"), ["1.9.0", "1.10.0"]);
+
+        store.activate("tool", "1.9.0").unwrap();
+        let (path, receipt) = store.active_executable("tool").unwrap();
+        assert_eq!(receipt.version, "1.9.0");
+        assert_eq!(std::fs::read(path).unwrap(), b"1.9.0");
+    }
+
+    #[test]
+    fn tampered_downloads_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let publisher = SigningKey::from_bytes(&[7; 32]);
+        std::fs::write(dir.path().join("tool-1.0.0"), b"tampered").unwrap();
+        let index = format!(
+            r#"{{"tools":[{{"name":"tool","executable":"tool","releases":[{}]}}]}}"#,
+            release("1.0.0", b"original")
+        );
+        let url = publish_index(dir.path(), &index, &publisher);
+
+        let store = ToolStore::new(dir.path().join("tools"));
+        let err = store
+            .install(&url, &publisher.verifying_key(), "tool", None, &["linux".to_string()], candidates)
+            .err()
+            .unwrap();
+        assert_eq!(err.code, ErrorCode::ChecksumMismatch);
+        assert!(store.installed_versions("tool").is_empty());
+    }
+
+    #[test]
+    fn index_executables_must_be_plain_file_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let publisher = SigningKey::from_bytes(&[7; 32]);
+        std::fs::write(dir.path().join("tool-1.0.0"), b"binary").unwrap();
+        let store = ToolStore::new(dir.path().join("tools"));
+        let install = |executable: &str, candidates: fn(&str) -> Vec<String>| {
+            let index = format!(
+                r#"{{"tools":[{{"name":"tool","executable":"{}","releases":[{}]}}]}}"#,
+                executable,
+                release("1.0.0", b"binary")
+            );
+            let url = publish_index(dir.path(), &index, &publisher);
+            store.install(&url, &publisher.verifying_key(), "tool", None, &["linux".to_string()], candidates).err().unwrap().code
+        };
+
+        assert_eq!(install("../../escape", candidates), ErrorCode::InvalidArgument);
+        assert!(!dir.path().join("escape").exists());
+        assert_eq!(install("tool", |_| Vec::new()), ErrorCode::ExecutableNotFound);
+    }
+}
@@ -1,5 +1,8 @@
 import { useState, useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { toast } from "sonner";
 import { AppLayout } from "./components/layout";
+import { Toaster } from "./components/ui/sonner";
 import routes from "./routes";
 
 function App() {
@@ -18,9 +21,20 @@ function App() {
     return () => window.removeEventListener('popstate', handlePopState);
   }, []);
 
+  // 后台任务（审计日志、文件监听等）无法返回给调用方的错误
+  useEffect(() => {
+    const unlisten = listen<{ message: string }>('app-error', (event) => {
+      toast.error(event.payload.message);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   return (
     <AppLayout>
       <CurrentComponent />
+      <Toaster />
     </AppLayout>
   );
 }
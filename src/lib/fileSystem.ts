@@ -143,10 +143,11 @@ export class FileSystemManager {
   private generateMarkdownPrompt(prompt: CustomPrompt): string {
     const frontmatter = [
       '---',
-      `description: "${prompt.description}"`,
-      `category: "${prompt.category || 'general'}"`,
+      // JSON 字符串同时是合法的 YAML 双引号字符串，值中的引号和换行会被正确转义
+      `description: ${JSON.stringify(prompt.description ?? '')}`,
+      `category: ${JSON.stringify(prompt.category || 'general')}`,
       prompt.allowedTools && prompt.allowedTools.length > 0 
-        ? `tools: "${prompt.allowedTools.join(', ')}"` 
+        ? `tools: ${JSON.stringify(prompt.allowedTools.join(', '))}` 
         : '',
       '---',
       ''
@@ -1,5 +1,18 @@
 import { invoke } from '@tauri-apps/api/core';
 
+/**
+ * 执行外部工具的可选参数
+ */
+export interface ExternalToolOptions {
+  cwd?: string;
+  env?: Record<string, string>;
+  /** 写入 stdin 的内容，字符串或字节数组 */
+  stdin?: string | number[];
+  timeoutMs?: number;
+  /** 为 true 时立即返回任务 ID，输出通过 tool-output 事件逐行推送 */
+  stream?: boolean;
+}
+
 /**
  * 资源管理器类，用于管理和访问外部可执行文件
  */
@@ -35,11 +48,16 @@ export class ResourceManager {
    * 执行外部工具
    * @param exeName 可执行文件名
    * @param args 命令行参数
-   * @returns 执行结果的标准输出
+   * @param options 工作目录、环境变量、stdin、超时及流式执行选项
+   * @returns 执行结果的标准输出；流式执行时为任务 ID
    */
-  static async executeExternalTool(exeName: string, args: string[] = []): Promise<string> {
+  static async executeExternalTool(
+    exeName: string,
+    args: string[] = [],
+    options?: ExternalToolOptions
+  ): Promise<string> {
     try {
-      return await invoke<string>('execute_external_tool', { exeName, args });
+      return await invoke<string>('execute_external_tool', { exeName, args, options });
     } catch (error) {
       throw new Error(`Failed to execute ${exeName}: ${error}`);
     }
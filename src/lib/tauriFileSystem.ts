@@ -2,13 +2,11 @@ import {
   readTextFile,
   writeTextFile,
   exists,
-  readDir,
   mkdir,
-  remove,
 } from "@tauri-apps/plugin-fs";
 import { homeDir } from "@tauri-apps/api/path";
 import { invoke } from "@tauri-apps/api/core";
-import type { CustomPrompt } from "../types/customPrompt";
+import type { CustomPrompt, PromptArgument } from "../types/customPrompt";
 
 export interface CodexConfig {
   version: string;
@@ -16,6 +14,16 @@ export interface CodexConfig {
   lastModified: string;
 }
 
+/**
+ * 后端 read_agent_file / read_config_file 返回的文件内容
+ *
+ * 保存时把 updatedAt 作为 expectedUpdatedAt 传回，文件在读取后被外部修改时后端会拒绝覆盖
+ */
+export interface FileContent {
+  content: string;
+  updatedAt: number;
+}
+
 export interface ClaudeConfig {
   version: string;
   projects: Array<{
@@ -27,6 +35,32 @@ export interface ClaudeConfig {
   settings: Record<string, any>;
 }
 
+/**
+ * 后端 list_prompts / get_prompt 返回的提示词
+ */
+interface BackendPrompt {
+  name: string;
+  scope: "user" | "project";
+  description: string;
+  category: string;
+  allowedTools: string[];
+  arguments: PromptArgument[];
+  content: string;
+  filePath: string;
+  createdAt: number;
+  updatedAt: number;
+}
+
+function toCustomPrompt(prompt: BackendPrompt): CustomPrompt {
+  return {
+    ...prompt,
+    id: `codex-${prompt.scope}-${prompt.name}`,
+    createdAt: new Date(prompt.createdAt),
+    updatedAt: new Date(prompt.updatedAt),
+    fileModifiedAt: prompt.updatedAt,
+  };
+}
+
 class TauriFileSystemManager {
   private homePath: string | null = null;
 
@@ -208,39 +242,13 @@ class TauriFileSystemManager {
 
   /**
    * 加载Codex提示
+   *
+   * 以 ~/.codex/prompts 下的 Markdown 文件为准，由后端解析 frontmatter
    */
   async loadCodexPrompts(): Promise<CustomPrompt[]> {
     try {
-      const homePath = await this.getHomePath();
-      const promptsPath = `${homePath}/.codex/prompts`;
-
-      if (!(await exists(promptsPath))) {
-        return [];
-      }
-
-      const entries = await readDir(promptsPath);
-      const prompts: CustomPrompt[] = [];
-
-      for (const entry of entries) {
-        if (entry.isFile && entry.name.endsWith(".md")) {
-          try {
-            const filePath = `${promptsPath}/${entry.name}`;
-            const content = await readTextFile(filePath);
-            const prompt = this.parseMarkdownPrompt(
-              content,
-              entry.name,
-              promptsPath
-            );
-            if (prompt) {
-              prompts.push(prompt);
-            }
-          } catch (error) {
-            console.error(`Failed to load prompt ${entry.name}:`, error);
-          }
-        }
-      }
-
-      return prompts;
+      const prompts = await invoke<BackendPrompt[]>("list_prompts");
+      return prompts.map(toCustomPrompt);
     } catch (error) {
       console.error("Failed to load Codex prompts:", error);
       return [];
@@ -249,46 +257,34 @@ class TauriFileSystemManager {
 
   /**
    * 保存Codex提示
+   *
+   * 文件已存在时更新，否则创建；提示词改名时先重命名原文件
    */
   async saveCodexPrompt(prompt: CustomPrompt): Promise<void> {
     try {
-      const homePath = await this.getHomePath();
-      const promptsPath = `${homePath}/.codex/prompts`;
-
-      // 确保目录存在
-      if (!(await exists(promptsPath))) {
-        await mkdir(promptsPath, { recursive: true });
-      }
-
-      const filePath = `${promptsPath}/${prompt.name}.md`;
-      const content = this.generateMarkdownPrompt(prompt);
-      await writeTextFile(filePath, content);
-
-      // 更新配置文件
-      let config = await this.readCodexConfig();
-
-      // 如果配置文件不存在或为空，创建默认配置
-      if (!config) {
-        config = {
-          version: "1.0.0",
-          prompts: [],
-          lastModified: new Date().toISOString(),
-        };
-      }
-
-      // 确保 prompts 数组存在
-      if (!config.prompts) {
-        config.prompts = [];
-      }
+      const input = {
+        description: prompt.description,
+        category: prompt.category,
+        allowedTools: prompt.allowedTools ?? [],
+        arguments: prompt.arguments ?? [],
+        content: prompt.content,
+        // 文件在编辑期间被外部修改时，后端返回 Conflict 错误而不是覆盖；
+        // 使用加载时记录的文件修改时间，界面上的 updatedAt 会在编辑时被改写
+        expectedUpdatedAt: prompt.fileModifiedAt,
+      };
 
-      const existingIndex = config.prompts.findIndex((p) => p.id === prompt.id);
-      if (existingIndex >= 0) {
-        config.prompts[existingIndex] = prompt;
-      } else {
-        config.prompts.push(prompt);
+      const previousName = prompt.filePath
+        ?.split(/[\\/]/)
+        .pop()
+        ?.replace(/\.md$/, "");
+      if (previousName && previousName !== prompt.name && (await exists(prompt.filePath))) {
+        await invoke("rename_prompt", { name: previousName, newName: prompt.name });
       }
 
-      await this.writeCodexConfig(config);
+      const homePath = await this.getHomePath();
+      const filePath = `${homePath}/.codex/prompts/${prompt.name}.md`;
+      const command = (await exists(filePath)) ? "update_prompt" : "create_prompt";
+      await invoke(command, { name: prompt.name, prompt: input });
     } catch (error) {
       console.error("Failed to save Codex prompt:", error);
       throw error;
@@ -300,122 +296,13 @@ class TauriFileSystemManager {
    */
   async deleteCodexPrompt(prompt: CustomPrompt): Promise<void> {
     try {
-      const homePath = await this.getHomePath();
-      const promptsPath = `${homePath}/.codex/prompts`;
-      const filePath = `${promptsPath}/${prompt.name}.md`;
-
-      // 删除文件
-      if (await exists(filePath)) {
-        await remove(filePath);
-      }
-
-      // 更新配置
-      const config = await this.readCodexConfig();
-      if (config) {
-        config.prompts = config.prompts.filter((p) => p.id !== prompt.id);
-        config.lastModified = new Date().toISOString();
-        await this.writeCodexConfig(config);
-      }
+      await invoke("delete_prompt", { name: prompt.name });
     } catch (error) {
       console.error("Failed to delete Codex prompt:", error);
       throw error;
     }
   }
 
-  /**
-   * 解析Markdown格式的命令文件
-   */
-  private parseMarkdownPrompt(
-    content: string,
-    filename: string,
-    basePath: string
-  ): CustomPrompt | null {
-    try {
-      const lines = content.split("\n");
-      let frontmatterEnd = -1;
-      let frontmatter: any = {};
-
-      // 解析frontmatter
-      if (lines[0] === "---") {
-        for (let i = 1; i < lines.length; i++) {
-          if (lines[i] === "---") {
-            frontmatterEnd = i;
-            break;
-          }
-        }
-
-        if (frontmatterEnd > 0) {
-          const frontmatterContent = lines.slice(1, frontmatterEnd).join("\n");
-          try {
-            // 简单的YAML解析（仅支持基本的key: value格式）
-            frontmatterContent.split("\n").forEach((line) => {
-              const match = line.match(/^(\w+):\s*(.+)$/);
-              if (match) {
-                const [, key, value] = match;
-                frontmatter[key] = value.replace(/^["']|["']$/g, ""); // 移除引号
-              }
-            });
-          } catch (error) {
-            console.warn("Failed to parse frontmatter:", error);
-          }
-        }
-      }
-
-      // 获取命令内容（去除frontmatter）
-      const commandContent =
-        frontmatterEnd > 0
-          ? lines
-              .slice(frontmatterEnd + 1)
-              .join("\n")
-              .trim()
-          : content.trim();
-
-      // 从文件名提取命令名（去除.md扩展名）
-      const commandName = filename.replace(/\.md$/, "");
-
-      const prompt: CustomPrompt = {
-        id: `codex-${commandName}-${Date.now()}`,
-        name: commandName,
-        description: frontmatter.description || `Prompt ${commandName}`,
-        content: commandContent,
-        scope: "user",
-        category: frontmatter.category || "general",
-        filePath: `${basePath}/${filename}`,
-        allowedTools: frontmatter.tools
-          ? frontmatter.tools.split(",").map((t: string) => t.trim())
-          : [],
-        arguments: [],
-        createdAt: new Date(),
-        updatedAt: new Date(),
-      };
-
-      return prompt;
-    } catch (error) {
-      console.error("Failed to parse markdown command:", error);
-      return null;
-    }
-  }
-
-  /**
-   * 生成Markdown格式的提示文件
-   */
-  private generateMarkdownPrompt(prompt: CustomPrompt): string {
-    const frontmatter = [
-      "---",
-      `description: "${prompt.description}"`,
-      `category: "${prompt.category || "general"}"`,
-      prompt.allowedTools && prompt.allowedTools.length > 0
-        ? `tools: "${prompt.allowedTools.join(", ")}"`
-        : "",
-      "---",
-      "",
-    ]
-      .filter(Boolean)
-      .join("\n");
-
-    return frontmatter + "\n" + prompt.content;
-  }
-
   /**
    * 检查Codex是否已安装
    */
@@ -438,9 +325,9 @@ class TauriFileSystemManager {
    */
   async installCodex(): Promise<{ success: boolean; message: string }> {
     try {
-      await invoke("execute_command", {
-        command: "npm",
-        args: ["install", "-g", "@anthropic-ai/codex"],
+      await invoke("execute_named_command", {
+        name: "npm-install-global",
+        params: { package: "@openai/codex" },
       });
 
       // 如果没有抛出异常，说明安装成功
@@ -459,9 +346,9 @@ class TauriFileSystemManager {
    */
   async updateCodex(): Promise<{ success: boolean; message: string }> {
     try {
-      (await invoke("execute_command", {
-        command: "npm",
-        args: ["update", "-g", "@anthropic-ai/codex"],
+      (await invoke("execute_named_command", {
+        name: "npm-update-global",
+        params: { package: "@openai/codex" },
       })) as string;
 
       // 如果没有抛出异常，说明更新成功
@@ -497,7 +384,7 @@ class TauriFileSystemManager {
   /**
    * 读取 ~/.codex/AGENT.md 文件
    */
-  async readAgentFile(): Promise<string> {
+  async readAgentFile(): Promise<FileContent> {
     try {
       const homePath = await this.getHomePath();
       const codexPath = `${homePath}/.codex`;
@@ -510,7 +397,7 @@ class TauriFileSystemManager {
       
       // 检查 AGENT.md 文件是否存在
       if (await exists(agentFilePath)) {
-        return await readTextFile(agentFilePath);
+        return await this.readAgentFileAt(agentFilePath);
       } else {
         // 文件不存在，创建一个空文件
         const defaultContent = 
@@ -531,8 +418,8 @@ Guidelines:
 - In your final response always share relevant file names and code snippets. Any file paths you return in your response MUST be absolute. Do NOT use relative paths.
 - For clear communication, avoid using emojis.
 `;
-        await writeTextFile(agentFilePath, defaultContent);
-        return defaultContent;
+        const updatedAt = await this.writeAgentFile(agentFilePath, defaultContent, 0);
+        return { content: defaultContent, updatedAt };
       }
     } catch (error) {
       console.error("Failed to read agent file:", error);
@@ -543,7 +430,7 @@ Guidelines:
   /**
    * 保存 ~/.codex/AGENT.md 文件
    */
-  async saveAgentFile(content: string): Promise<void> {
+  async saveAgentFile(content: string, expectedUpdatedAt?: number): Promise<number> {
     try {
       const homePath = await this.getHomePath();
       const codexPath = `${homePath}/.codex`;
@@ -554,13 +441,43 @@ Guidelines:
         await this.createCodexDirectory();
       }
       
-      await writeTextFile(agentFilePath, content);
+      return await this.writeAgentFile(agentFilePath, content, expectedUpdatedAt);
     } catch (error) {
       console.error("Failed to save agent file:", error);
       throw error;
     }
   }
 
+  /**
+   * 通过后端读取 AGENTS.md、CLAUDE.md 等说明文件，同时返回读取时的修改时间
+   */
+  async readAgentFileAt(filePath: string): Promise<FileContent> {
+    return await invoke<FileContent>("read_agent_file", { path: filePath });
+  }
+
+  /**
+   * 通过后端保存 AGENTS.md、CLAUDE.md 等说明文件，覆盖前后的内容都会记入版本历史
+   *
+   * expectedUpdatedAt 为读取时的修改时间（新建文件时为 0），文件已被外部修改时拒绝覆盖；返回保存后的修改时间
+   */
+  async writeAgentFile(filePath: string, content: string, expectedUpdatedAt?: number): Promise<number> {
+    return await invoke<number>("save_agent_file", { path: filePath, content, expectedUpdatedAt });
+  }
+
+  /**
+   * 通过后端读取 config.toml 等配置文件，同时返回读取时的修改时间
+   */
+  async readConfigFile(filePath: string): Promise<FileContent> {
+    return await invoke<FileContent>("read_config_file", { path: filePath });
+  }
+
+  /**
+   * 通过后端保存 config.toml 等配置文件，冲突检查与 writeAgentFile 相同；返回保存后的修改时间
+   */
+  async writeConfigFile(filePath: string, content: string, expectedUpdatedAt?: number): Promise<number> {
+    return await invoke<number>("save_config_file", { path: filePath, content, expectedUpdatedAt });
+  }
+
   /**
    * 通用文件读取方法
    */
@@ -22,9 +22,8 @@ const ClaudeCodeManagement = () => {
   const checkClaudeCodeInstallation = async () => {
     setIsChecking(true)
     try {
-      const result = await invoke('execute_command', {
-        command: 'claude',
-        args: ['--version']
+      const result = await invoke('execute_named_command', {
+        name: 'claude-version'
       })
       
       if (result) {
@@ -47,9 +46,9 @@ const ClaudeCodeManagement = () => {
   const installClaudeCode = async () => {
     setIsInstalling(true)
     try {
-      await invoke('execute_command', {
-        command: 'npm',
-        args: ['install', '-g', '@anthropic-ai/claude-code']
+      await invoke('execute_named_command', {
+        name: 'npm-install-global',
+        params: { package: '@anthropic-ai/claude-code' }
       })
       
       // 安装完成后重新检测
@@ -65,9 +64,9 @@ const ClaudeCodeManagement = () => {
   const updateClaudeCode = async () => {
     setIsInstalling(true)
     try {
-      await invoke('execute_command', {
-        command: 'npm',
-        args: ['update', '-g', '@anthropic-ai/claude-code']
+      await invoke('execute_named_command', {
+        name: 'npm-update-global',
+        params: { package: '@anthropic-ai/claude-code' }
       })
       
       // 更新完成后重新检测
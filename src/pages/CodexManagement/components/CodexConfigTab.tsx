@@ -5,7 +5,7 @@ import { Settings, Save, RefreshCw } from 'lucide-react';
 import Editor, { useMonaco } from '@monaco-editor/react';
 import { useTheme } from 'next-themes';
 import { tauriFileSystemManager } from '../../../lib/tauriFileSystem';
-import { mkdir, exists, readTextFile, writeTextFile } from '@tauri-apps/plugin-fs';
+import { mkdir, exists } from '@tauri-apps/plugin-fs';
 
 const CodexConfigTab: React.FC = () => {
   const [configContent, setConfigContent] = useState<string>('');
@@ -14,6 +14,8 @@ const CodexConfigTab: React.FC = () => {
   const [error, setError] = useState<string>('');
   const [successMessage, setSuccessMessage] = useState<string>('');
   const [configPath, setConfigPath] = useState<string>('');
+  // 读取时 config.toml 的修改时间，保存时用于检测外部修改
+  const [configUpdatedAt, setConfigUpdatedAt] = useState<number>(0);
   const { resolvedTheme } = useTheme();
   const monaco = useMonaco();
   const editorRef = useRef<any>(null);
@@ -66,13 +68,14 @@ ignore_default_excludes = false
         const dirPath = path.substring(0, path.lastIndexOf('/'));
         await mkdir(dirPath, { recursive: true });
         
-        // 创建默认配置文件
-        await writeTextFile(path, defaultConfig);
+        // 创建默认配置文件；期间文件被其他程序创建时拒绝覆盖
+        setConfigUpdatedAt(await tauriFileSystemManager.writeConfigFile(path, defaultConfig, 0));
         setConfigContent(defaultConfig);
       } else {
         // 读取现有配置文件
-        const content = await readTextFile(path);
+        const { content, updatedAt } = await tauriFileSystemManager.readConfigFile(path);
         setConfigContent(content);
+        setConfigUpdatedAt(updatedAt);
       }
     } catch (err) {
       console.error('加载配置文件失败:', err);
@@ -91,14 +94,16 @@ ignore_default_excludes = false
     setSuccessMessage('');
     
     try {
-      await writeTextFile(configPath, configContent);
+      setConfigUpdatedAt(await tauriFileSystemManager.writeConfigFile(configPath, configContent, configUpdatedAt));
       setSuccessMessage('配置文件保存成功！');
       
       // 3秒后清除成功消息
       setTimeout(() => setSuccessMessage(''), 3000);
     } catch (err) {
       console.error('保存配置文件失败:', err);
-      setError(`保存配置文件失败: ${err instanceof Error ? err.message : '未知错误'}`);
+      // 后端错误是 { code, message } 对象，文件被外部修改时 code 为 conflict，需要重新加载
+      const message = err instanceof Error ? err.message : (err as { message?: string })?.message;
+      setError(`保存配置文件失败: ${message ?? '未知错误'}`);
     } finally {
       setIsSaving(false);
     }
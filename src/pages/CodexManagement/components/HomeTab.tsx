@@ -17,13 +17,16 @@ const HomeTab: React.FC<HomeTabProps> = () => {
   const [isLoading, setIsLoading] = useState(true);
   const [isSaving, setIsSaving] = useState(false);
   const [lastSaved, setLastSaved] = useState<Date | null>(null);
+  // 读取时文件的修改时间，保存时用于检测外部修改
+  const [agentUpdatedAt, setAgentUpdatedAt] = useState<number | undefined>(undefined);
 
   // 加载 AGENT.md 文件内容
   const loadAgentFile = async () => {
     try {
       setIsLoading(true);
-      const content = await tauriFileSystemManager.readAgentFile();
+      const { content, updatedAt } = await tauriFileSystemManager.readAgentFile();
       setAgentContent(content);
+      setAgentUpdatedAt(updatedAt);
     } catch (error) {
       console.error('Failed to load agent file:', error);
     } finally {
@@ -35,7 +38,7 @@ const HomeTab: React.FC<HomeTabProps> = () => {
   const saveAgentFile = async () => {
     try {
       setIsSaving(true);
-      await tauriFileSystemManager.saveAgentFile(agentContent);
+      setAgentUpdatedAt(await tauriFileSystemManager.saveAgentFile(agentContent, agentUpdatedAt));
       setLastSaved(new Date());
     } catch (error) {
       console.error('Failed to save agent file:', error);
@@ -65,6 +65,8 @@ const ProjectsTab: React.FC<ProjectsTabProps> = ({
   const [agentEditorOpen, setAgentEditorOpen] = useState<boolean>(false);
   const [currentProject, setCurrentProject] = useState<Project | null>(null);
   const [agentContent, setAgentContent] = useState<string>('');
+  // 读取时 AGENT.md 的修改时间，文件不存在时为 0，保存时用于检测外部修改
+  const [agentUpdatedAt, setAgentUpdatedAt] = useState<number>(0);
   const [isLoadingAgent, setIsLoadingAgent] = useState(false);
   const [isSavingAgent, setIsSavingAgent] = useState(false);
   const [lastSaved, setLastSaved] = useState<Date | null>(null);
@@ -142,11 +144,13 @@ const ProjectsTab: React.FC<ProjectsTabProps> = ({
 
     try {
       const agentPath = `${project.path}/AGENT.md`;
-      const content = await tauriFileSystemManager.readFile(agentPath);
+      const { content, updatedAt } = await tauriFileSystemManager.readAgentFileAt(agentPath);
       setAgentContent(content);
+      setAgentUpdatedAt(updatedAt);
     } catch (error) {
       // 如果文件不存在，创建默认内容
       console.log('AGENT.md file not found, creating default content');
+      setAgentUpdatedAt(0);
       setAgentContent(`# ${project.name} Agent Configuration
 
 ## Project Description
@@ -174,7 +178,7 @@ const ProjectsTab: React.FC<ProjectsTabProps> = ({
     try {
       setIsSavingAgent(true);
       const agentPath = `${currentProject.path}/AGENT.md`;
-      await tauriFileSystemManager.writeFile(agentPath, agentContent);
+      setAgentUpdatedAt(await tauriFileSystemManager.writeAgentFile(agentPath, agentContent, agentUpdatedAt));
       setLastSaved(new Date());
     } catch (error) {
       console.error('Failed to save agent file:', error);
@@ -190,8 +194,9 @@ const ProjectsTab: React.FC<ProjectsTabProps> = ({
     try {
       setIsLoadingAgent(true);
       const agentPath = `${currentProject.path}/AGENT.md`;
-      const content = await tauriFileSystemManager.readFile(agentPath);
+      const { content, updatedAt } = await tauriFileSystemManager.readAgentFileAt(agentPath);
       setAgentContent(content);
+      setAgentUpdatedAt(updatedAt);
     } catch (error) {
       console.error('Failed to load agent file:', error);
       setError('加载 AGENT.md 文件失败');
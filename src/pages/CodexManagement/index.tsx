@@ -1,4 +1,4 @@
-import React, { useState, useEffect } from 'react';
+import React, { useState, useEffect, useRef } from 'react';
 import { Button } from '@/components/ui/button';
 import { Tabs, TabsList, TabsTrigger, TabsContent } from '@/components/ui/tabs';
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card';
@@ -6,6 +6,8 @@ import { Alert, AlertDescription } from '@/components/ui/alert';
 import { Badge } from '@/components/ui/badge';
 import { Spinner } from '@/components/ui/spinner';
 import { Folder, RefreshCw, Home, Terminal, Settings } from 'lucide-react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { tauriFileSystemManager } from '../../lib/tauriFileSystem';
 import { projectsDB } from '../../lib/projectsDB';
 import { CustomPrompt } from '../../types/customPrompt';
@@ -35,6 +37,48 @@ const CodexManagement: React.FC = () => {
     initializeTauriFileSystem();
   }, []);
 
+  // 提示词文件在外部编辑器中被修改时重新加载
+  useEffect(() => {
+    const unlisten = listen('prompt-changed', () => {
+      loadPrompts();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // 监听已登记项目中的 AGENTS.md 和项目级提示词；项目被移除或离开页面时停止监听
+  const watchedProjects = useRef<Set<string>>(new Set());
+  useEffect(() => {
+    const current = new Set(projects.map((project) => project.path));
+    current.forEach((path) => {
+      if (!watchedProjects.current.has(path)) {
+        invoke('watch_project', { projectPath: path }).catch((error) =>
+          console.error('Failed to watch project:', error)
+        );
+      }
+    });
+    watchedProjects.current.forEach((path) => {
+      if (!current.has(path)) {
+        invoke('unwatch_project', { projectPath: path }).catch((error) =>
+          console.error('Failed to unwatch project:', error)
+        );
+      }
+    });
+    watchedProjects.current = current;
+  }, [projects]);
+
+  useEffect(() => {
+    return () => {
+      watchedProjects.current.forEach((path) => {
+        invoke('unwatch_project', { projectPath: path }).catch((error) =>
+          console.error('Failed to unwatch project:', error)
+        );
+      });
+      watchedProjects.current = new Set();
+    };
+  }, []);
+
   const initializeTauriFileSystem = async () => {
     try {
       await tauriFileSystemManager.init();
@@ -93,7 +93,7 @@ const MCPManagement: React.FC = () => {
 
   const checkExecutableAvailability = async () => {
     try {
-      const exeName = 'MakingMcp.Web.exe'
+      const exeName = 'making-mcp'
       const exists = await ResourceManager.checkExecutableExists(exeName)
       setIsExecutableAvailable(exists)
       
@@ -103,7 +103,7 @@ const MCPManagement: React.FC = () => {
         addLog('info', `找到可执行文件: ${path}`)
       } else {
         addLog('error', `未找到可执行文件: ${exeName}`)
-        setError('未找到 MakingMcp.Web.exe 文件，请确保文件已放置在 resources/bin/windows/ 目录中')
+        setError('未找到 MakingMcp.Web 可执行文件，请确保文件已放置在 resources/bin/<平台>/ 目录中')
       }
     } catch (err) {
       const errorMsg = `检查可执行文件失败: ${err}`
@@ -114,25 +114,21 @@ const MCPManagement: React.FC = () => {
 
   const checkProcessStatus = async () => {
     try {
-      // 检查进程是否在运行
-      const result = await invoke<string>('execute_command', {
-        command: 'tasklist',
-        args: ['/FI', 'IMAGENAME eq MakingMcp.Web.exe', '/FO', 'CSV']
+      // 由后端根据托管进程的 PID 判断，不需要执行系统命令
+      const status = await invoke<{ running: boolean; pid?: number }>('get_mcp_service_status')
+
+      setProcessStatus((previous) => {
+        if (status.running && !previous.isRunning) {
+          // 进程刚启动
+          return { isRunning: true, startTime: new Date() }
+        }
+        if (!status.running && previous.isRunning) {
+          // 进程刚停止
+          addLog('warn', 'MakingMcp.Web 进程已停止')
+          return { isRunning: false }
+        }
+        return previous
       })
-      
-      const isRunning = result.includes('MakingMcp.Web.exe')
-      
-      if (isRunning && !processStatus.isRunning) {
-        // 进程刚启动
-        setProcessStatus({
-          isRunning: true,
-          startTime: new Date()
-        })
-      } else if (!isRunning && processStatus.isRunning) {
-        // 进程刚停止
-        setProcessStatus({ isRunning: false })
-        addLog('warn', 'MakingMcp.Web.exe 进程已停止')
-      }
     } catch (err) {
       // 静默处理错误，避免频繁显示错误信息
       console.error('检查进程状态失败:', err)
@@ -11,6 +11,7 @@ export interface CustomPrompt {
   arguments?: PromptArgument[]; // 参数定义
   createdAt: Date;
   updatedAt: Date;
+  fileModifiedAt?: number; // 加载时文件的修改时间（Unix 毫秒），保存时用于检测外部修改
 }
 
 export interface PromptArgument {